@@ -1,20 +1,73 @@
 mod alerts;
+mod baselines;
+mod hashes;
+mod incidents;
 mod known_entities;
+mod prevalence;
+mod profiles;
 mod response;
+mod rules;
+mod sequences;
 mod snapshot;
+mod suppressions;
+mod yara;
+
+pub use self::baselines::app_key;
 
 use std::collections::{HashMap, HashSet, VecDeque};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex, RwLock};
+use std::time::Instant;
 
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
 
+use crate::detection::rules::{RuleFingerprint, RuleSet};
+use crate::detection::sequence::CompletedSequence;
 use crate::models::{
-    AppUsageEntry, CpuSpikeConfig, DetectionProfile, EventEnvelope, InstalledProgram,
+    AppUsageEntry, BinaryPrevalence, BinarySignature, CpuSpikeConfig,
+    DetectionProfile, EventEnvelope, InstalledProgram, IoSpikeConfig, MemoryGrowthConfig,
     PerformanceStats, ProcessMetric, ProcessNode, ResponsePolicy, SensorHealth, StartupProcess,
+    YaraMatch,
+};
+use crate::storage::{
+    AlertStore, BaselineStore, EventStore, HashCacheStore, KnownEntityStore, PrevalenceStore,
+    ProfileStore, ResponseActionStore, SequenceStore, SuppressionStore,
 };
-use crate::storage::{AlertStore, EventStore, KnownEntityStore, ResponseActionStore};
+
+/// Where each persistent store lives; [`StorePaths::in_dir`] gives the
+/// standard layout under the app data directory.
+pub struct StorePaths {
+    pub alerts: PathBuf,
+    pub known_entities: PathBuf,
+    pub events: PathBuf,
+    pub response_actions: PathBuf,
+    pub file_hashes: PathBuf,
+    pub baselines: PathBuf,
+    pub prevalence: PathBuf,
+    pub profiles: PathBuf,
+    pub sequences: PathBuf,
+    pub suppressions: PathBuf,
+    pub rules_dir: PathBuf,
+}
+
+impl StorePaths {
+    pub fn in_dir(dir: &Path) -> Self {
+        Self {
+            alerts: dir.join("alerts.json"),
+            known_entities: dir.join("known_entities.json"),
+            events: dir.join("events.db"),
+            response_actions: dir.join("response_actions.json"),
+            file_hashes: dir.join("file_hashes.db"),
+            baselines: dir.join("baselines.json"),
+            prevalence: dir.join("prevalence.db"),
+            profiles: dir.join("detection_profiles.json"),
+            sequences: dir.join("sequence_state.json"),
+            suppressions: dir.join("suppression_rules.json"),
+            rules_dir: dir.join("rules"),
+        }
+    }
+}
 
 #[derive(Clone)]
 pub struct RuntimeState {
@@ -26,12 +79,35 @@ struct RuntimeStateInner {
     process_metrics: RwLock<Vec<ProcessMetric>>,
     installed_programs: RwLock<Vec<InstalledProgram>>,
     startup_processes: RwLock<Vec<StartupProcess>>,
-    detection_profile: RwLock<DetectionProfile>,
+    active_profile: RwLock<DetectionProfile>,
+    profile_store: Mutex<ProfileStore>,
     cpu_spike_config: RwLock<CpuSpikeConfig>,
     cpu_history: Mutex<HashMap<u32, VecDeque<f32>>>,
+    io_spike_config: RwLock<IoSpikeConfig>,
+    io_history: Mutex<HashMap<u32, VecDeque<f32>>>,
+    memory_growth_config: RwLock<MemoryGrowthConfig>,
+    memory_history: Mutex<HashMap<u32, VecDeque<(Instant, f32)>>>,
     app_usage_history: Mutex<HashMap<String, AppUsageEntry>>,
+    baseline_store: Mutex<BaselineStore>,
     known_pids: Mutex<HashSet<u32>>,
-    signature_cache: Mutex<HashMap<String, bool>>,
+    signature_cache: Mutex<HashMap<String, Option<BinarySignature>>>,
+    rules_dir: PathBuf,
+    detection_rules: RwLock<Arc<RuleSet>>,
+    rules_fingerprint: Mutex<Option<RuleFingerprint>>,
+    sequence_store: Mutex<SequenceStore>,
+    sequence_alerts: Mutex<Vec<CompletedSequence>>,
+    file_hashes: RwLock<HashMap<String, String>>,
+    /// Paths whose last hash attempt failed, with the file size and
+    /// modification time seen at that point.
+    failed_hashes: Mutex<HashMap<String, Option<(u64, i64)>>>,
+    hash_queue: Mutex<VecDeque<String>>,
+    hash_store: Mutex<HashCacheStore>,
+    prevalence_store: Mutex<PrevalenceStore>,
+    prevalence_cache: Mutex<HashMap<(String, String), BinaryPrevalence>>,
+    prevalence_runs: Mutex<HashMap<u32, String>>,
+    prevalence_since: DateTime<Utc>,
+    yara_results: RwLock<HashMap<String, Vec<YaraMatch>>>,
+    yara_queue: Mutex<VecDeque<String>>,
     store: Mutex<AlertStore>,
     event_store: Mutex<EventStore>,
     known_store: Mutex<KnownEntityStore>,
@@ -41,24 +117,36 @@ struct RuntimeStateInner {
     last_loop_ms: Mutex<f32>,
     response_policy: RwLock<ResponsePolicy>,
     action_cooldowns: Mutex<HashMap<String, DateTime<Utc>>>,
-    dismissed_alerts: Mutex<HashMap<String, DateTime<Utc>>>,
+    suppression_store: Mutex<SuppressionStore>,
     host_id: String,
 }
 
 impl RuntimeState {
-    pub fn new(
-        store_path: PathBuf,
-        known_store_path: PathBuf,
-        event_store_path: PathBuf,
-        response_store_path: PathBuf,
-    ) -> Result<Self> {
-        let store = AlertStore::load(store_path).context("failed to initialize alert store")?;
-        let known_store = KnownEntityStore::load(known_store_path)
+    pub fn new(paths: StorePaths) -> Result<Self> {
+        let store = AlertStore::load(paths.alerts).context("failed to initialize alert store")?;
+        let known_store = KnownEntityStore::load(paths.known_entities)
             .context("failed to initialize known entity store")?;
-        let event_store = EventStore::load(event_store_path)
+        let event_store = EventStore::load(paths.events)
             .context("failed to initialize event store")?;
-        let response_store = ResponseActionStore::load(response_store_path)
+        let response_store = ResponseActionStore::load(paths.response_actions)
             .context("failed to initialize response action store")?;
+        let hash_store =
+            HashCacheStore::load(paths.file_hashes).context("failed to initialize hash cache store")?;
+        let baseline_store = BaselineStore::load(paths.baselines)
+            .context("failed to initialize baseline store")?;
+        let prevalence_store = PrevalenceStore::load(paths.prevalence)
+            .context("failed to initialize prevalence store")?;
+        let prevalence_since = prevalence_store
+            .earliest_first_seen()
+            .context("failed reading prevalence history")?
+            .unwrap_or_else(Utc::now);
+        let profile_store =
+            ProfileStore::load(paths.profiles).context("failed to initialize profile store")?;
+        let active_profile = profiles::resolve_active(&profile_store);
+        let sequence_store = SequenceStore::load(paths.sequences)
+            .context("failed to initialize sequence store")?;
+        let suppression_store = SuppressionStore::load(paths.suppressions)
+            .context("failed to initialize suppression store")?;
         let host_id = std::env::var("COMPUTERNAME").unwrap_or_else(|_| "unknown-host".to_string());
         Ok(Self {
             inner: Arc::new(RuntimeStateInner {
@@ -66,12 +154,33 @@ impl RuntimeState {
                 process_metrics: RwLock::new(Vec::new()),
                 installed_programs: RwLock::new(Vec::new()),
                 startup_processes: RwLock::new(Vec::new()),
-                detection_profile: RwLock::new(DetectionProfile::default()),
+                active_profile: RwLock::new(active_profile),
+                profile_store: Mutex::new(profile_store),
                 cpu_spike_config: RwLock::new(CpuSpikeConfig::default()),
                 cpu_history: Mutex::new(HashMap::new()),
+                io_spike_config: RwLock::new(IoSpikeConfig::default()),
+                io_history: Mutex::new(HashMap::new()),
+                memory_growth_config: RwLock::new(MemoryGrowthConfig::default()),
+                memory_history: Mutex::new(HashMap::new()),
                 app_usage_history: Mutex::new(HashMap::new()),
+                baseline_store: Mutex::new(baseline_store),
                 known_pids: Mutex::new(HashSet::new()),
                 signature_cache: Mutex::new(HashMap::new()),
+                rules_dir: paths.rules_dir,
+                detection_rules: RwLock::new(Arc::new(RuleSet::default_pack())),
+                rules_fingerprint: Mutex::new(None),
+                sequence_store: Mutex::new(sequence_store),
+                sequence_alerts: Mutex::new(Vec::new()),
+                file_hashes: RwLock::new(HashMap::new()),
+                failed_hashes: Mutex::new(HashMap::new()),
+                hash_queue: Mutex::new(VecDeque::new()),
+                hash_store: Mutex::new(hash_store),
+                prevalence_store: Mutex::new(prevalence_store),
+                prevalence_cache: Mutex::new(HashMap::new()),
+                prevalence_runs: Mutex::new(HashMap::new()),
+                prevalence_since,
+                yara_results: RwLock::new(HashMap::new()),
+                yara_queue: Mutex::new(VecDeque::new()),
                 store: Mutex::new(store),
                 event_store: Mutex::new(event_store),
                 known_store: Mutex::new(known_store),
@@ -81,7 +190,7 @@ impl RuntimeState {
                 last_loop_ms: Mutex::new(0.0),
                 response_policy: RwLock::new(ResponsePolicy::default()),
                 action_cooldowns: Mutex::new(HashMap::new()),
-                dismissed_alerts: Mutex::new(HashMap::new()),
+                suppression_store: Mutex::new(suppression_store),
                 host_id,
             }),
         })
@@ -112,11 +221,18 @@ impl RuntimeState {
     }
 
     pub fn get_startup_processes(&self) -> Vec<StartupProcess> {
-        self.inner
+        let mut items = self
+            .inner
             .startup_processes
             .read()
             .expect("poisoned startup processes lock")
-            .clone()
+            .clone();
+        for item in &mut items {
+            if let Some(path) = &item.executable {
+                item.yara_matches = self.yara_matches_for_path(path);
+            }
+        }
+        items
     }
 
     pub fn get_app_usage_history(&self) -> Vec<AppUsageEntry> {
@@ -144,6 +260,7 @@ impl RuntimeState {
             .expect("poisoned event store lock")
             .insert_event(&event)?;
         self.record_sensor_success(&sensor_name, None);
+        self.observe_sequence_event(&event);
         if let Some(entry) = self
             .inner
             .sensor_health
@@ -280,6 +397,14 @@ impl RuntimeState {
             .read()
             .expect("poisoned process metrics lock")
             .len();
+        let hash_backlog = self.hash_backlog();
+        let hash_cache_entries = self
+            .inner
+            .hash_store
+            .lock()
+            .expect("poisoned hash store lock")
+            .total_entries()
+            .unwrap_or(0);
 
         PerformanceStats {
             loop_last_ms: last,
@@ -288,6 +413,8 @@ impl RuntimeState {
             total_events,
             event_store_size: total_events,
             tracked_processes,
+            hash_backlog,
+            hash_cache_entries,
         }
     }
 }
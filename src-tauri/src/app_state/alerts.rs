@@ -1,12 +1,18 @@
+use anyhow::{bail, Result};
 use chrono::{DateTime, Utc};
 
-use crate::models::{Alert, AlertStatus};
+use crate::models::{Alert, AlertAction, AlertAuditEntry, AlertDisposition, AlertStatus};
 
 use super::RuntimeState;
 
+const DUPLICATE_WINDOW_SECONDS: i64 = 120;
+/// Archiving keeps the same alert quiet for a while even though the process
+/// that raised it is still running.
+const ARCHIVE_DISMISS_SECONDS: i64 = 300;
+
 impl RuntimeState {
-    pub fn add_alert_if_new(&self, alert: Alert) -> anyhow::Result<bool> {
-        if self.is_alert_suppressed(&alert) {
+    pub fn add_alert_if_new(&self, mut alert: Alert) -> Result<bool> {
+        if self.suppress_alert(&alert, DUPLICATE_WINDOW_SECONDS) {
             return Ok(false);
         }
         let mut store = self.inner.store.lock().expect("poisoned alert store lock");
@@ -14,82 +20,168 @@ impl RuntimeState {
             existing.pid == alert.pid
                 && existing.alert_type == alert.alert_type
                 && existing.title == alert.title
-                && is_recent(&existing.timestamp, 120)
+                && (is_recent(&existing.timestamp, DUPLICATE_WINDOW_SECONDS)
+                    || recently_archived(existing, ARCHIVE_DISMISS_SECONDS))
         });
         if duplicate {
             return Ok(false);
         }
+        let status = alert.status.clone();
+        record(&mut alert, AlertAction::Raised, status, None, None);
         store.push(alert)?;
         Ok(true)
     }
 
-    pub fn delete_alert(&self, alert_id: &str) -> anyhow::Result<bool> {
-        let mut store = self.inner.store.lock().expect("poisoned alert store lock");
-        let deleted_alert = store
-            .history()
-            .into_iter()
-            .find(|alert| alert.id == alert_id && alert.status == AlertStatus::Active);
-        let deleted = store.delete(alert_id)?;
-        drop(store);
-        if deleted {
-            if let Some(alert) = deleted_alert {
-                self.mark_alert_dismissed(&alert);
+    pub fn acknowledge_alert(&self, alert_id: &str) -> Result<Alert> {
+        self.update_alert(alert_id, |alert| {
+            if alert.status != AlertStatus::Active {
+                bail!("only active alerts can be acknowledged");
             }
-        }
-        Ok(deleted)
+            record(alert, AlertAction::Acknowledged, AlertStatus::Acknowledged, None, None);
+            Ok(())
+        })
     }
 
-    pub fn delete_all_active_alerts(&self) -> anyhow::Result<usize> {
-        let mut store = self.inner.store.lock().expect("poisoned alert store lock");
-        let active_alerts = store.active_alerts();
-        let deleted = store.delete_all_active()?;
-        drop(store);
-        if deleted > 0 {
-            for alert in &active_alerts {
-                self.mark_alert_dismissed(alert);
+    pub fn resolve_alert(
+        &self,
+        alert_id: &str,
+        disposition: AlertDisposition,
+        note: Option<String>,
+    ) -> Result<Alert> {
+        self.update_alert(alert_id, |alert| {
+            if !alert.status.is_open() {
+                bail!("alert is already closed; reopen it first");
             }
-        }
-        Ok(deleted)
+            alert.disposition = Some(disposition);
+            record(
+                alert,
+                AlertAction::Resolved,
+                AlertStatus::Resolved,
+                Some(disposition),
+                clean_note(note),
+            );
+            Ok(())
+        })
     }
 
-    pub fn active_alerts(&self) -> Vec<Alert> {
-        self.inner
+    pub fn reopen_alert(&self, alert_id: &str, note: Option<String>) -> Result<Alert> {
+        self.update_alert(alert_id, |alert| {
+            if alert.status.is_open() {
+                bail!("alert is already open");
+            }
+            alert.disposition = None;
+            record(alert, AlertAction::Reopened, AlertStatus::Active, None, clean_note(note));
+            Ok(())
+        })
+    }
+
+    pub fn add_alert_note(&self, alert_id: &str, note: &str) -> Result<Alert> {
+        let Some(note) = clean_note(Some(note.to_string())) else {
+            bail!("note cannot be empty");
+        };
+        self.update_alert(alert_id, |alert| {
+            let status = alert.status.clone();
+            record(alert, AlertAction::NoteAdded, status, None, Some(note));
+            Ok(())
+        })
+    }
+
+    /// Soft delete: the alert leaves the active list but stays in history
+    /// and can be reopened. Returns false when it was already archived.
+    pub fn archive_alert(&self, alert_id: &str) -> Result<bool> {
+        let archived = self
+            .inner
             .store
             .lock()
             .expect("poisoned alert store lock")
-            .active_alerts()
+            .update_all(
+                |alert| alert.id == alert_id && alert.status != AlertStatus::Archived,
+                |alert| record(alert, AlertAction::Archived, AlertStatus::Archived, None, None),
+            )?;
+        Ok(!archived.is_empty())
     }
 
-    fn is_alert_suppressed(&self, alert: &Alert) -> bool {
-        const ALERT_SUPPRESSION_SECONDS: i64 = 300;
-        let now = Utc::now();
-        let signature = alert_signature(alert);
-        let mut dismissed = self
+    pub fn archive_open_alerts(&self) -> Result<usize> {
+        let archived = self
             .inner
-            .dismissed_alerts
+            .store
             .lock()
-            .expect("poisoned dismissed alerts lock");
-        dismissed.retain(|_, timestamp| {
-            now.signed_duration_since(*timestamp).num_seconds() < ALERT_SUPPRESSION_SECONDS
-        });
-        dismissed
-            .get(&signature)
-            .map(|timestamp| {
-                now.signed_duration_since(*timestamp).num_seconds() < ALERT_SUPPRESSION_SECONDS
-            })
-            .unwrap_or(false)
+            .expect("poisoned alert store lock")
+            .update_all(
+                |alert| alert.status.is_open(),
+                |alert| record(alert, AlertAction::Archived, AlertStatus::Archived, None, None),
+            )?;
+        Ok(archived.len())
+    }
+
+    /// Every stored alert, newest first, optionally including archived ones.
+    pub fn alert_history(&self, include_archived: bool) -> Vec<Alert> {
+        let mut history = self
+            .inner
+            .store
+            .lock()
+            .expect("poisoned alert store lock")
+            .history();
+        if !include_archived {
+            history.retain(|alert| alert.status != AlertStatus::Archived);
+        }
+        history
+    }
+
+    pub fn active_alerts(&self) -> Vec<Alert> {
+        self.inner
+            .store
+            .lock()
+            .expect("poisoned alert store lock")
+            .active_alerts()
     }
 
-    fn mark_alert_dismissed(&self, alert: &Alert) {
-        let signature = alert_signature(alert);
+    fn update_alert<F>(&self, alert_id: &str, apply: F) -> Result<Alert>
+    where
+        F: FnOnce(&mut Alert) -> Result<()>,
+    {
         self.inner
-            .dismissed_alerts
+            .store
             .lock()
-            .expect("poisoned dismissed alerts lock")
-            .insert(signature, Utc::now());
+            .expect("poisoned alert store lock")
+            .update(alert_id, apply)
     }
 }
 
+fn record(
+    alert: &mut Alert,
+    action: AlertAction,
+    to_status: AlertStatus,
+    disposition: Option<AlertDisposition>,
+    note: Option<String>,
+) {
+    let from_status = (action != AlertAction::Raised).then(|| alert.status.clone());
+    alert.status = to_status.clone();
+    alert.audit.push(AlertAuditEntry {
+        timestamp: Utc::now().to_rfc3339(),
+        action,
+        from_status,
+        to_status,
+        disposition,
+        note,
+    });
+}
+
+fn recently_archived(alert: &Alert, window_seconds: i64) -> bool {
+    alert.status == AlertStatus::Archived
+        && alert
+            .audit
+            .iter()
+            .rev()
+            .find(|entry| entry.action == AlertAction::Archived)
+            .is_some_and(|entry| is_recent(&entry.timestamp, window_seconds))
+}
+
+fn clean_note(note: Option<String>) -> Option<String> {
+    note.map(|note| note.trim().to_string())
+        .filter(|note| !note.is_empty())
+}
+
 fn is_recent(timestamp: &str, window_seconds: i64) -> bool {
     let now = Utc::now();
     let parsed = DateTime::parse_from_rfc3339(timestamp)
@@ -101,12 +193,80 @@ fn is_recent(timestamp: &str, window_seconds: i64) -> bool {
     }
 }
 
-fn alert_signature(alert: &Alert) -> String {
-    format!(
-        "{}:{}:{}:{}",
-        alert.alert_type,
-        alert.pid.unwrap_or_default(),
-        alert.title.to_lowercase(),
-        format!("{:?}", &alert.severity).to_lowercase()
-    )
+#[cfg(test)]
+mod tests {
+    use crate::app_state::{RuntimeState, StorePaths};
+    use crate::models::{Alert, AlertAction, AlertDisposition, AlertSeverity, AlertStatus};
+    use crate::test_support::TempDir;
+
+    fn alert(id: &str) -> Alert {
+        Alert {
+            id: id.to_string(),
+            alert_type: "suspicious_process".to_string(),
+            severity: AlertSeverity::Warn,
+            pid: Some(7),
+            title: format!("Alert {id}"),
+            description: String::new(),
+            evidence: Vec::new(),
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            status: AlertStatus::Active,
+            evidence_refs: Vec::new(),
+            disposition: None,
+            audit: Vec::new(),
+            process: None,
+        }
+    }
+
+    #[test]
+    fn lifecycle_transitions_are_audited_and_archive_keeps_history() {
+        let dir = TempDir::new("alerts");
+        let state = RuntimeState::new(StorePaths::in_dir(&dir)).expect("runtime state");
+        state.add_alert_if_new(alert("a1")).expect("add a1");
+        state.add_alert_if_new(alert("a2")).expect("add a2");
+
+        state.acknowledge_alert("a1").expect("acknowledge");
+        assert!(state.acknowledge_alert("a1").is_err());
+        state.add_alert_note("a1", "  looked at the parent  ").expect("note");
+        let resolved = state
+            .resolve_alert("a1", AlertDisposition::FalsePositive, None)
+            .expect("resolve");
+        assert_eq!(resolved.disposition, Some(AlertDisposition::FalsePositive));
+        let actions: Vec<_> = resolved.audit.iter().map(|entry| entry.action).collect();
+        assert_eq!(
+            actions,
+            [
+                AlertAction::Raised,
+                AlertAction::Acknowledged,
+                AlertAction::NoteAdded,
+                AlertAction::Resolved
+            ]
+        );
+        assert_eq!(resolved.audit[2].note.as_deref(), Some("looked at the parent"));
+        assert!(state.add_alert_note("a1", " ").is_err());
+
+        let reopened = state.reopen_alert("a1", None).expect("reopen");
+        assert_eq!((reopened.status, reopened.disposition), (AlertStatus::Active, None));
+
+        assert!(state.archive_alert("a2").expect("archive"));
+        assert!(!state.archive_alert("a2").expect("archive again"));
+        assert_eq!(state.active_alerts().len(), 1);
+        assert_eq!(state.alert_history(false).len(), 1);
+
+        let mut stale = alert("a3");
+        stale.timestamp = (chrono::Utc::now() - chrono::Duration::seconds(200)).to_rfc3339();
+        state.add_alert_if_new(stale.clone()).expect("add a3");
+        state.archive_alert("a3").expect("archive a3");
+        stale.id = "a4".to_string();
+        stale.timestamp = chrono::Utc::now().to_rfc3339();
+        assert!(!state.add_alert_if_new(stale).expect("add a4"));
+        drop(state);
+
+        let reloaded = RuntimeState::new(StorePaths::in_dir(&dir)).expect("reload state");
+        let history = reloaded.alert_history(true);
+        assert_eq!(history.len(), 3);
+        assert!(history
+            .iter()
+            .any(|alert| alert.id == "a2" && alert.status == AlertStatus::Archived));
+        assert!(reloaded.reopen_alert("missing", None).is_err());
+    }
 }
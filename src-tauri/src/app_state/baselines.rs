@@ -0,0 +1,143 @@
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+
+use crate::detection::baseline::{self, BaselineDeviation, BaselineObservation};
+use crate::models::{AppBaseline, BaselineConfig, ProcessMetric};
+
+use super::RuntimeState;
+
+impl RuntimeState {
+    pub fn baseline_config(&self) -> BaselineConfig {
+        self.inner
+            .baseline_store
+            .lock()
+            .expect("poisoned baseline store lock")
+            .config()
+            .clone()
+    }
+
+    pub fn set_baseline_config(&self, config: BaselineConfig) -> Result<()> {
+        self.inner
+            .baseline_store
+            .lock()
+            .expect("poisoned baseline store lock")
+            .set_config(config)
+    }
+
+    /// Feeds one observation into the baseline of the process' application.
+    /// Returns the deviations from the learned profile, or nothing while the
+    /// application is still in its learning period.
+    pub fn observe_app_baseline(
+        &self,
+        metric: &ProcessMetric,
+        observation: &BaselineObservation<'_>,
+    ) -> Vec<BaselineDeviation> {
+        let key = app_key(metric);
+        let now = Utc::now();
+        let mut store = self
+            .inner
+            .baseline_store
+            .lock()
+            .expect("poisoned baseline store lock");
+        let config = store.config().clone();
+
+        let Some(entry) = store.entry(&key) else {
+            let mut created = AppBaseline {
+                app_key: key,
+                name: metric.name.clone(),
+                first_seen: now.to_rfc3339(),
+                last_seen: now.to_rfc3339(),
+                ..AppBaseline::default()
+            };
+            baseline::learn(&mut created, observation);
+            store.insert(created);
+            return Vec::new();
+        };
+
+        entry.name = metric.name.clone();
+        entry.last_seen = now.to_rfc3339();
+        if entry.learned_at.is_some() {
+            return baseline::deviations(entry, observation);
+        }
+
+        baseline::learn(entry, observation);
+        let learning_over = DateTime::parse_from_rfc3339(&entry.first_seen)
+            .map(|first_seen| {
+                now.signed_duration_since(first_seen)
+                    >= Duration::hours(i64::from(config.learning_hours))
+            })
+            .unwrap_or(true);
+        if learning_over && entry.samples >= config.min_samples {
+            entry.learned_at = Some(now.to_rfc3339());
+        }
+        Vec::new()
+    }
+
+    pub fn get_app_baseline(&self, app_key: &str) -> Option<AppBaseline> {
+        self.inner
+            .baseline_store
+            .lock()
+            .expect("poisoned baseline store lock")
+            .get(app_key)
+            .cloned()
+    }
+
+    pub fn list_app_baselines(&self) -> Vec<AppBaseline> {
+        self.inner
+            .baseline_store
+            .lock()
+            .expect("poisoned baseline store lock")
+            .list()
+    }
+
+    /// Drops the learned profile; the application starts a new learning period
+    /// the next time it is seen.
+    pub fn reset_app_baseline(&self, app_key: &str) -> Result<bool> {
+        self.inner
+            .baseline_store
+            .lock()
+            .expect("poisoned baseline store lock")
+            .remove(app_key)
+    }
+
+    pub fn flush_app_baselines(&self) -> Result<()> {
+        self.inner
+            .baseline_store
+            .lock()
+            .expect("poisoned baseline store lock")
+            .flush()
+    }
+}
+
+/// Applications are keyed by executable path, or by name when the path is
+/// not readable.
+pub fn app_key(metric: &ProcessMetric) -> String {
+    metric
+        .exe_path
+        .clone()
+        .unwrap_or_else(|| metric.name.to_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::app_state::{RuntimeState, StorePaths};
+    use crate::models::BaselineConfig;
+    use crate::test_support::TempDir;
+
+    #[test]
+    fn baseline_config_survives_a_restart() {
+        let dir = TempDir::new("baselines");
+        let state = RuntimeState::new(StorePaths::in_dir(&dir)).expect("runtime state");
+        state
+            .set_baseline_config(BaselineConfig {
+                learning_hours: 6,
+                min_samples: 20,
+            })
+            .expect("save config");
+        drop(state);
+
+        let reloaded = RuntimeState::new(StorePaths::in_dir(&dir)).expect("reload state");
+        let config = reloaded.baseline_config();
+        assert_eq!((config.learning_hours, config.min_samples), (6, 20));
+    }
+}
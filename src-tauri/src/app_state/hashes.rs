@@ -0,0 +1,108 @@
+use crate::monitoring::hash_worker;
+
+use super::RuntimeState;
+
+impl RuntimeState {
+    /// Returns the last known hash for `path` without reading the file. Paths
+    /// that were never hashed, or that `revalidate` asks to re-check, are queued
+    /// for the background hash worker; paths that failed are only queued again
+    /// once their size or modification time changes.
+    pub fn file_hash(&self, path: &str, revalidate: bool) -> Option<String> {
+        let known = self
+            .inner
+            .file_hashes
+            .read()
+            .expect("poisoned file hashes lock")
+            .get(path)
+            .cloned();
+        if (known.is_none() || revalidate) && !self.hash_failure_is_current(path) {
+            self.queue_file_hash(path);
+        }
+        known
+    }
+
+    fn hash_failure_is_current(&self, path: &str) -> bool {
+        let failed = self
+            .inner
+            .failed_hashes
+            .lock()
+            .expect("poisoned failed hashes lock")
+            .get(path)
+            .copied();
+        failed.is_some_and(|fingerprint| fingerprint == hash_worker::file_fingerprint(path))
+    }
+
+    pub fn queue_file_hash(&self, path: &str) {
+        let mut queue = self
+            .inner
+            .hash_queue
+            .lock()
+            .expect("poisoned hash queue lock");
+        if !queue.iter().any(|queued| queued == path) {
+            queue.push_back(path.to_string());
+        }
+    }
+
+    pub fn next_hash_job(&self) -> Option<String> {
+        self.inner
+            .hash_queue
+            .lock()
+            .expect("poisoned hash queue lock")
+            .pop_front()
+    }
+
+    pub fn hash_backlog(&self) -> usize {
+        self.inner
+            .hash_queue
+            .lock()
+            .expect("poisoned hash queue lock")
+            .len()
+    }
+
+    pub fn complete_hash_job(&self, path: &str, sha256: Option<String>) {
+        let mut failed = self
+            .inner
+            .failed_hashes
+            .lock()
+            .expect("poisoned failed hashes lock");
+        let mut hashes = self
+            .inner
+            .file_hashes
+            .write()
+            .expect("poisoned file hashes lock");
+        match sha256 {
+            Some(value) => {
+                failed.remove(path);
+                hashes.insert(path.to_string(), value);
+            }
+            None => {
+                failed.insert(path.to_string(), hash_worker::file_fingerprint(path));
+                hashes.remove(path);
+            }
+        }
+    }
+
+    pub fn cached_file_hash(&self, path: &str, size: u64, modified_ns: i64) -> Option<String> {
+        self.inner
+            .hash_store
+            .lock()
+            .expect("poisoned hash store lock")
+            .lookup(path, size, modified_ns)
+            .ok()
+            .flatten()
+    }
+
+    pub fn store_file_hash(
+        &self,
+        path: &str,
+        size: u64,
+        modified_ns: i64,
+        sha256: &str,
+    ) -> anyhow::Result<()> {
+        self.inner
+            .hash_store
+            .lock()
+            .expect("poisoned hash store lock")
+            .upsert(path, size, modified_ns, sha256)
+    }
+}
@@ -0,0 +1,113 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::detection::incident::{self, IncidentMember};
+use crate::detection::lineage::Ancestor;
+use crate::models::{Incident, IncidentProcess};
+
+use super::RuntimeState;
+
+/// Events this long before the first and after the last alert still belong
+/// to the incident timeline.
+const TIMELINE_MARGIN_MINUTES: i64 = 5;
+const MAX_TIMELINE_SCAN: usize = 5_000;
+const MAX_TIMELINE_EVENTS: usize = 500;
+
+impl RuntimeState {
+    /// Incidents built from the alert history, newest first. Timelines are
+    /// left empty; use [`Self::incident`] for a single incident with its events.
+    pub fn list_incidents(&self, include_archived: bool) -> Vec<Incident> {
+        let alerts = self.alert_history(include_archived);
+        let metrics = self
+            .inner
+            .process_metrics
+            .read()
+            .expect("poisoned process metrics lock");
+        let processes: HashMap<u32, Ancestor> = metrics
+            .iter()
+            .map(|metric| (metric.pid, Ancestor::from(metric)))
+            .collect();
+        // Alerts stored before they carried process context fall back to the
+        // live process with the same pid.
+        let members: Vec<IncidentMember<'_>> = alerts
+            .iter()
+            .map(|alert| IncidentMember {
+                alert,
+                process: alert.process.clone().or_else(|| {
+                    let pid = alert.pid?;
+                    metrics
+                        .iter()
+                        .find(|metric| metric.pid == pid)
+                        .map(|metric| metric.identity())
+                }),
+                at: parse_timestamp(&alert.timestamp).unwrap_or_else(Utc::now),
+            })
+            .collect();
+        drop(metrics);
+        incident::build_incidents(&members, &processes)
+    }
+
+    /// One incident with its merged timeline: every stored event around the
+    /// incident's alerts that involves one of its processes or is cited by
+    /// one of its alerts. Archived alerts are only consulted when the id is
+    /// not found among the current ones.
+    pub fn incident(&self, incident_id: &str) -> Option<Incident> {
+        let mut incident = [false, true].into_iter().find_map(|include_archived| {
+            self.list_incidents(include_archived)
+                .into_iter()
+                .find(|incident| incident.id == incident_id)
+        })?;
+
+        let margin = Duration::minutes(TIMELINE_MARGIN_MINUTES);
+        let (Some(start), Some(end)) = (
+            parse_timestamp(&incident.first_seen),
+            parse_timestamp(&incident.last_seen),
+        ) else {
+            return Some(incident);
+        };
+        let mut pids = HashSet::new();
+        collect_pids(&incident.processes, &mut pids);
+        let refs: HashSet<&str> = incident
+            .alerts
+            .iter()
+            .flat_map(|alert| alert.evidence_refs.iter().map(String::as_str))
+            .collect();
+        let events = self
+            .inner
+            .event_store
+            .lock()
+            .expect("poisoned event store lock")
+            .list_events_between(
+                &(start - margin).to_rfc3339(),
+                &(end + margin).to_rfc3339(),
+                MAX_TIMELINE_SCAN,
+            )
+            .unwrap_or_default();
+        incident.timeline = events
+            .into_iter()
+            .filter(|event| {
+                refs.contains(event.event_id.as_str())
+                    || event
+                        .process
+                        .as_ref()
+                        .is_some_and(|process| pids.contains(&process.pid))
+            })
+            .take(MAX_TIMELINE_EVENTS)
+            .collect();
+        Some(incident)
+    }
+}
+
+fn collect_pids(nodes: &[IncidentProcess], pids: &mut HashSet<u32>) {
+    for node in nodes {
+        pids.insert(node.pid);
+        collect_pids(&node.children, pids);
+    }
+}
+
+fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|timestamp| timestamp.with_timezone(&Utc))
+}
@@ -0,0 +1,110 @@
+use std::collections::HashSet;
+
+use chrono::{DateTime, Utc};
+
+use crate::models::{BinaryPrevalence, ProcessMetric};
+
+use super::RuntimeState;
+
+const MAX_TRACKED_PARENTS: usize = 32;
+
+impl RuntimeState {
+    /// Records that the process' binary is running and returns its updated
+    /// prevalence. Each process counts as one run, however many ticks it lives.
+    /// Returns `None` until the binary has been hashed.
+    pub fn observe_prevalence(
+        &self,
+        metric: &ProcessMetric,
+        parent: Option<&str>,
+    ) -> Option<BinaryPrevalence> {
+        let sha256 = metric.sha256.as_deref()?;
+        let path = metric.exe_path.as_deref()?;
+        let now = Utc::now().to_rfc3339();
+        let new_run = self
+            .inner
+            .prevalence_runs
+            .lock()
+            .expect("poisoned prevalence runs lock")
+            .insert(metric.pid, sha256.to_string())
+            .is_none_or(|previous| previous != sha256);
+
+        let mut cache = self
+            .inner
+            .prevalence_cache
+            .lock()
+            .expect("poisoned prevalence cache lock");
+        let key = (sha256.to_string(), path.to_string());
+        let mut changed = false;
+        if !cache.contains_key(&key) {
+            let stored = self
+                .inner
+                .prevalence_store
+                .lock()
+                .expect("poisoned prevalence store lock")
+                .lookup(sha256, path);
+            let entry = match stored {
+                Ok(Some(entry)) => entry,
+                Ok(None) => {
+                    changed = true;
+                    BinaryPrevalence {
+                        sha256: sha256.to_string(),
+                        path: path.to_string(),
+                        first_seen: now.clone(),
+                        last_seen: now.clone(),
+                        run_count: 0,
+                        parents: Vec::new(),
+                        file_modified: file_modified(path),
+                    }
+                }
+                Err(err) => {
+                    self.record_sensor_error("prevalence", &err.to_string());
+                    return None;
+                }
+            };
+            cache.insert(key.clone(), entry);
+        }
+        let entry = cache.get_mut(&key)?;
+
+        if new_run {
+            entry.run_count = entry.run_count.saturating_add(1);
+            entry.last_seen = now;
+            changed = true;
+        }
+        if let Some(parent) = parent.map(str::to_lowercase) {
+            if entry.parents.len() < MAX_TRACKED_PARENTS && !entry.parents.contains(&parent) {
+                entry.parents.push(parent);
+                changed = true;
+            }
+        }
+        if changed {
+            let stored = self
+                .inner
+                .prevalence_store
+                .lock()
+                .expect("poisoned prevalence store lock")
+                .upsert(entry);
+            if let Err(err) = stored {
+                self.record_sensor_error("prevalence", &err.to_string());
+            }
+        }
+        Some(entry.clone())
+    }
+
+    /// When prevalence recording began on this host.
+    pub fn prevalence_since(&self) -> DateTime<Utc> {
+        self.inner.prevalence_since
+    }
+
+    pub(super) fn prune_prevalence_runs(&self, live: &HashSet<u32>) {
+        self.inner
+            .prevalence_runs
+            .lock()
+            .expect("poisoned prevalence runs lock")
+            .retain(|pid, _| live.contains(pid));
+    }
+}
+
+fn file_modified(path: &str) -> Option<String> {
+    let modified = std::fs::metadata(path).ok()?.modified().ok()?;
+    Some(DateTime::<Utc>::from(modified).to_rfc3339())
+}
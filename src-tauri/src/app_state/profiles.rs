@@ -0,0 +1,191 @@
+use anyhow::{anyhow, bail, Result};
+
+use crate::models::DetectionProfile;
+use crate::storage::ProfileStore;
+
+use super::RuntimeState;
+
+impl RuntimeState {
+    pub fn profile(&self) -> DetectionProfile {
+        self.inner
+            .active_profile
+            .read()
+            .expect("poisoned active profile lock")
+            .clone()
+    }
+
+    /// Built-in presets first, then custom profiles in creation order.
+    pub fn list_profiles(&self) -> Vec<DetectionProfile> {
+        let store = self
+            .inner
+            .profile_store
+            .lock()
+            .expect("poisoned profile store lock");
+        let mut profiles = DetectionProfile::builtins();
+        profiles.extend(store.list());
+        profiles
+    }
+
+    pub fn create_profile(&self, mut profile: DetectionProfile) -> Result<DetectionProfile> {
+        validate(&profile)?;
+        let mut store = self
+            .inner
+            .profile_store
+            .lock()
+            .expect("poisoned profile store lock");
+        profile.id = unique_id(&store, &profile.name);
+        profile.builtin = false;
+        store.upsert(profile.clone())?;
+        Ok(profile)
+    }
+
+    pub fn update_profile(&self, mut profile: DetectionProfile) -> Result<DetectionProfile> {
+        if find_builtin(&profile.id).is_some() {
+            bail!("built-in profile '{}' is read-only; clone it to make changes", profile.id);
+        }
+        validate(&profile)?;
+        let mut store = self
+            .inner
+            .profile_store
+            .lock()
+            .expect("poisoned profile store lock");
+        if store.get(&profile.id).is_none() {
+            bail!("unknown detection profile '{}'", profile.id);
+        }
+        profile.builtin = false;
+        store.upsert(profile.clone())?;
+        if store.active_id() == Some(profile.id.as_str()) {
+            *self
+                .inner
+                .active_profile
+                .write()
+                .expect("poisoned active profile lock") = profile.clone();
+        }
+        Ok(profile)
+    }
+
+    pub fn clone_profile(&self, source_id: &str, name: &str) -> Result<DetectionProfile> {
+        let source = {
+            let store = self
+                .inner
+                .profile_store
+                .lock()
+                .expect("poisoned profile store lock");
+            lookup(&store, source_id)
+                .ok_or_else(|| anyhow!("unknown detection profile '{source_id}'"))?
+        };
+        self.create_profile(DetectionProfile {
+            name: name.trim().to_string(),
+            ..source
+        })
+    }
+
+    /// Switches scoring to the profile and remembers the choice across restarts.
+    pub fn activate_profile(&self, id: &str) -> Result<DetectionProfile> {
+        let mut store = self
+            .inner
+            .profile_store
+            .lock()
+            .expect("poisoned profile store lock");
+        let profile =
+            lookup(&store, id).ok_or_else(|| anyhow!("unknown detection profile '{id}'"))?;
+        store.set_active(id)?;
+        *self
+            .inner
+            .active_profile
+            .write()
+            .expect("poisoned active profile lock") = profile.clone();
+        Ok(profile)
+    }
+}
+
+/// The stored active profile, falling back to the default preset when the
+/// stored id no longer exists.
+pub(super) fn resolve_active(store: &ProfileStore) -> DetectionProfile {
+    store
+        .active_id()
+        .and_then(|id| lookup(store, id))
+        .unwrap_or_default()
+}
+
+fn lookup(store: &ProfileStore, id: &str) -> Option<DetectionProfile> {
+    find_builtin(id).or_else(|| store.get(id).cloned())
+}
+
+fn find_builtin(id: &str) -> Option<DetectionProfile> {
+    DetectionProfile::builtins()
+        .into_iter()
+        .find(|profile| profile.id == id)
+}
+
+fn validate(profile: &DetectionProfile) -> Result<()> {
+    if profile.name.trim().is_empty() {
+        bail!("detection profile name cannot be empty");
+    }
+    if profile.unknown_threshold > profile.suspicious_threshold {
+        bail!("unknown threshold cannot exceed the suspicious threshold");
+    }
+    let verdict = &profile.verdict;
+    if verdict.low_risk > verdict.suspicious
+        || verdict.suspicious > verdict.likely_malicious
+        || verdict.likely_malicious > verdict.confirmed_malicious
+    {
+        bail!("verdict cut-offs must increase from low risk to confirmed malicious");
+    }
+    Ok(())
+}
+
+fn unique_id(store: &ProfileStore, name: &str) -> String {
+    let slug = name
+        .trim()
+        .to_lowercase()
+        .split(|ch: char| !ch.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+    let base = if slug.is_empty() {
+        "profile".to_string()
+    } else {
+        slug
+    };
+    let mut id = base.clone();
+    let mut suffix = 2;
+    while lookup(store, &id).is_some() {
+        id = format!("{base}-{suffix}");
+        suffix += 1;
+    }
+    id
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::app_state::{RuntimeState, StorePaths};
+    use crate::test_support::TempDir;
+
+    #[test]
+    fn custom_profiles_persist_and_builtins_stay_read_only() {
+        let dir = TempDir::new("profiles");
+
+        let state = RuntimeState::new(StorePaths::in_dir(&dir)).expect("runtime state");
+        let mut balanced = state.clone_profile("balanced", "Night shift").expect("clone");
+        assert_eq!(balanced.id, "night-shift");
+        assert!(!balanced.builtin);
+        assert_eq!(balanced.suspicious_threshold, 70);
+
+        balanced.rule_weights.insert("cpu-spike".to_string(), 0);
+        state.update_profile(balanced).expect("update custom");
+        state.activate_profile("night-shift").expect("activate");
+
+        let mut preset = state.list_profiles().remove(0);
+        preset.suspicious_threshold = 10;
+        assert!(state.update_profile(preset).is_err());
+        assert!(state.activate_profile("missing").is_err());
+        drop(state);
+
+        let reloaded = RuntimeState::new(StorePaths::in_dir(&dir)).expect("reload state");
+        let active = reloaded.profile();
+        assert_eq!(active.id, "night-shift");
+        assert_eq!(active.weight("cpu-spike", 12), 0);
+        assert_eq!(reloaded.list_profiles().len(), 4);
+    }
+}
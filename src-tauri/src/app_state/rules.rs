@@ -0,0 +1,70 @@
+use std::sync::Arc;
+use std::time::Instant;
+
+use crate::detection::rules::{self, RuleSet};
+use crate::models::{DetectionRule, SigmaRuleSummary};
+
+use super::RuntimeState;
+
+const RULES_SENSOR: &str = "rules";
+
+impl RuntimeState {
+    pub fn detection_rules(&self) -> Arc<RuleSet> {
+        self.inner
+            .detection_rules
+            .read()
+            .expect("poisoned detection rules lock")
+            .clone()
+    }
+
+    pub fn list_detection_rules(&self) -> Vec<DetectionRule> {
+        self.detection_rules().definitions()
+    }
+
+    pub fn list_sigma_rules(&self) -> Vec<SigmaRuleSummary> {
+        self.detection_rules().sigma().summaries()
+    }
+
+    /// Re-reads the rules directory when any rule file was added, removed or
+    /// modified since the last load. Load problems are surfaced on the `rules`
+    /// sensor; rules that parsed cleanly are still applied.
+    pub fn reload_detection_rules_if_changed(&self) -> bool {
+        let dir = &self.inner.rules_dir;
+        if let Err(err) = rules::install_default_pack(dir) {
+            self.record_sensor_error(RULES_SENSOR, &err);
+            return false;
+        }
+
+        let current = rules::fingerprint(dir);
+        {
+            let mut last = self
+                .inner
+                .rules_fingerprint
+                .lock()
+                .expect("poisoned rules fingerprint lock");
+            if last.as_ref() == Some(&current) {
+                return false;
+            }
+            *last = Some(current);
+        }
+
+        let started = Instant::now();
+        let load = rules::load_rule_dir(dir);
+        *self
+            .inner
+            .detection_rules
+            .write()
+            .expect("poisoned detection rules lock") = Arc::new(load.rules);
+        self.clear_yara_results();
+
+        if load.errors.is_empty() {
+            self.record_sensor_success(
+                RULES_SENSOR,
+                Some(started.elapsed().as_secs_f32() * 1000.0),
+            );
+        } else {
+            self.record_sensor_error(RULES_SENSOR, &load.errors.join("; "));
+        }
+        true
+    }
+}
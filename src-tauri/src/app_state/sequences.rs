@@ -0,0 +1,100 @@
+use anyhow::Result;
+use chrono::Utc;
+
+use crate::detection::sequence::{CompletedSequence, SequenceEvent};
+use crate::models::{EventEnvelope, ProcessIdentity, SequenceRule};
+
+use super::RuntimeState;
+
+const MAX_ANCESTRY_DEPTH: usize = 32;
+
+impl RuntimeState {
+    /// Feeds a timeline event to the sequence engine. Completed sequences that
+    /// raise alerts are queued for [`Self::take_sequence_alerts`].
+    pub(super) fn observe_sequence_event(&self, event: &EventEnvelope) {
+        let rules = self.detection_rules();
+        let ancestry = self.pid_ancestry(event.process.as_ref());
+        let finished = self
+            .inner
+            .sequence_store
+            .lock()
+            .expect("poisoned sequence store lock")
+            .state_mut()
+            .observe(
+                rules.sequences(),
+                &SequenceEvent {
+                    event,
+                    ancestry: &ancestry,
+                    at: Utc::now(),
+                },
+            );
+        self.inner
+            .sequence_alerts
+            .lock()
+            .expect("poisoned sequence alerts lock")
+            .extend(finished.into_iter().filter(|done| done.alert.is_some()));
+    }
+
+    pub fn take_sequence_alerts(&self) -> Vec<CompletedSequence> {
+        std::mem::take(
+            &mut *self
+                .inner
+                .sequence_alerts
+                .lock()
+                .expect("poisoned sequence alerts lock"),
+        )
+    }
+
+    /// Completed sequences still inside their window that involve `pid` (or,
+    /// for lineage scope, one of its `ancestry` pids), including host-scoped ones.
+    pub fn active_sequences(&self, pid: u32, ancestry: &[u32]) -> Vec<CompletedSequence> {
+        self.inner
+            .sequence_store
+            .lock()
+            .expect("poisoned sequence store lock")
+            .state()
+            .active_for(pid, ancestry, Utc::now())
+            .into_iter()
+            .cloned()
+            .collect()
+    }
+
+    pub fn list_sequence_rules(&self) -> Vec<SequenceRule> {
+        self.detection_rules().sequences().definitions()
+    }
+
+    pub fn flush_sequence_state(&self) -> Result<()> {
+        self.inner
+            .sequence_store
+            .lock()
+            .expect("poisoned sequence store lock")
+            .flush()
+    }
+
+    /// Pids above `process`, nearest first, from its own parent pid and the
+    /// last process snapshot.
+    fn pid_ancestry(&self, process: Option<&ProcessIdentity>) -> Vec<u32> {
+        let Some(process) = process else {
+            return Vec::new();
+        };
+        let metrics = self
+            .inner
+            .process_metrics
+            .read()
+            .expect("poisoned process metrics lock");
+        let mut ancestry = Vec::new();
+        let mut next = process.ppid;
+        while let Some(pid) = next {
+            if pid == process.pid || ancestry.contains(&pid) || ancestry.len() >= MAX_ANCESTRY_DEPTH
+            {
+                break;
+            }
+            ancestry.push(pid);
+            next = metrics
+                .iter()
+                .find(|metric| metric.pid == pid)
+                .and_then(|metric| metric.ppid);
+        }
+        ancestry
+    }
+}
@@ -1,12 +1,15 @@
-use std::collections::HashSet;
+use std::collections::{HashSet, VecDeque};
+
+use std::time::Instant;
 
 use chrono::Utc;
 
 use crate::models::{
-    AppUsageEntry, CpuSpikeConfig, DetectionProfile, InstalledProgram, StartupProcess,
+    AppUsageEntry, BinarySignature, CpuSpikeConfig, InstalledProgram, IoSpikeConfig,
+    MemoryGrowthConfig, MemoryGrowthObservation, StartupProcess,
 };
 
-use super::RuntimeState;
+use super::{app_key, RuntimeState};
 
 impl RuntimeState {
     pub fn update_snapshot(
@@ -52,23 +55,6 @@ impl RuntimeState {
         *lock = startup_processes;
     }
 
-    pub fn profile(&self) -> DetectionProfile {
-        self.inner
-            .detection_profile
-            .read()
-            .expect("poisoned detection profile lock")
-            .clone()
-    }
-
-    pub fn set_profile(&self, profile: DetectionProfile) {
-        let mut lock = self
-            .inner
-            .detection_profile
-            .write()
-            .expect("poisoned detection profile lock");
-        *lock = profile;
-    }
-
     pub fn cpu_spike_config(&self) -> CpuSpikeConfig {
         self.inner
             .cpu_spike_config
@@ -85,61 +71,117 @@ impl RuntimeState {
             .lock()
             .expect("poisoned cpu history lock");
         let samples = history.entry(pid).or_default();
-        samples.push_back(sample);
-        while samples.len() > 120 {
-            samples.pop_front();
-        }
+        push_and_check_spike(
+            samples,
+            sample,
+            config.threshold_pct,
+            config.min_consecutive_samples,
+            config.deviation_ratio,
+            5.0,
+        )
+    }
 
-        if samples.len() < config.min_consecutive_samples {
-            return false;
-        }
+    pub fn io_spike_config(&self) -> IoSpikeConfig {
+        self.inner
+            .io_spike_config
+            .read()
+            .expect("poisoned io spike config lock")
+            .clone()
+    }
 
-        let recent: Vec<f32> = samples
-            .iter()
-            .rev()
-            .take(config.min_consecutive_samples)
-            .copied()
-            .collect();
+    /// Tracks combined disk read and write throughput per process.
+    pub fn update_io_and_check_spike(&self, pid: u32, bytes_per_sec: f32) -> bool {
+        let config = self.io_spike_config();
+        let mut history = self
+            .inner
+            .io_history
+            .lock()
+            .expect("poisoned io history lock");
+        let samples = history.entry(pid).or_default();
+        push_and_check_spike(
+            samples,
+            bytes_per_sec,
+            config.threshold_bytes_per_sec,
+            config.min_consecutive_samples,
+            config.deviation_ratio,
+            config.threshold_bytes_per_sec * 0.1,
+        )
+    }
 
-        if !recent.iter().all(|value| *value >= config.threshold_pct) {
-            return false;
-        }
+    pub fn memory_growth_config(&self) -> MemoryGrowthConfig {
+        self.inner
+            .memory_growth_config
+            .read()
+            .expect("poisoned memory growth config lock")
+            .clone()
+    }
 
-        let recent_avg = recent.iter().sum::<f32>() / recent.len() as f32;
-        let prior_len = samples.len().saturating_sub(config.min_consecutive_samples);
-        if prior_len < 5 {
-            return recent_avg > config.threshold_pct + 5.0;
+    /// Records a memory sample and reports sustained growth over the configured
+    /// window. The window is cleared after a hit so the same leak is only
+    /// reported again once a fresh window has been observed.
+    pub fn update_memory_and_check_growth(
+        &self,
+        pid: u32,
+        memory_mb: f32,
+        sampled_at: Instant,
+    ) -> Option<MemoryGrowthObservation> {
+        let config = self.memory_growth_config();
+        let mut history = self
+            .inner
+            .memory_history
+            .lock()
+            .expect("poisoned memory history lock");
+        let samples = history.entry(pid).or_default();
+        samples.push_back((sampled_at, memory_mb));
+        while samples.len() > config.window_samples.max(2) {
+            samples.pop_front();
         }
 
-        let prior_avg = samples.iter().take(prior_len).sum::<f32>() / prior_len as f32;
-        recent_avg > prior_avg * config.deviation_ratio
+        let first_sample = samples.front()?.0;
+        let series: Vec<(f32, f32)> = samples
+            .iter()
+            .map(|(instant, value)| (instant.duration_since(first_sample).as_secs_f32(), *value))
+            .collect();
+        let observation = detect_memory_growth(&series, &config)?;
+        samples.clear();
+        Some(observation)
     }
 
-    pub fn prune_cpu_history(&self, live_pids: &[u32]) {
+    pub fn prune_metric_history(&self, live_pids: &[u32]) {
         let live: HashSet<u32> = live_pids.iter().copied().collect();
-        let mut history = self
-            .inner
+        self.inner
             .cpu_history
             .lock()
-            .expect("poisoned cpu history lock");
-        history.retain(|pid, _| live.contains(pid));
+            .expect("poisoned cpu history lock")
+            .retain(|pid, _| live.contains(pid));
+        self.inner
+            .io_history
+            .lock()
+            .expect("poisoned io history lock")
+            .retain(|pid, _| live.contains(pid));
+        self.inner
+            .memory_history
+            .lock()
+            .expect("poisoned memory history lock")
+            .retain(|pid, _| live.contains(pid));
+        self.prune_prevalence_runs(&live);
     }
 
-    pub fn get_cached_signature(&self, path: &str) -> Option<bool> {
+    pub fn get_cached_signature(&self, path: &str) -> Option<Option<BinarySignature>> {
         self.inner
             .signature_cache
             .lock()
             .expect("poisoned signature cache lock")
             .get(path)
-            .copied()
+            .cloned()
     }
 
-    pub fn put_cached_signature(&self, path: String, is_signed: bool) {
+    pub fn put_cached_signature(&self, path: String, signature: Option<BinarySignature>) {
         self.inner
             .signature_cache
             .lock()
             .expect("poisoned signature cache lock")
-            .insert(path, is_signed);
+            .insert(path, signature);
     }
 
     fn update_usage_history(&self) {
@@ -160,10 +202,7 @@ impl RuntimeState {
         let live_pids: HashSet<u32> = metrics.iter().map(|metric| metric.pid).collect();
 
         for metric in metrics {
-            let app_key = metric
-                .exe_path
-                .clone()
-                .unwrap_or_else(|| metric.name.to_lowercase());
+            let app_key = app_key(&metric);
             let entry = usage.entry(app_key.clone()).or_insert_with(|| AppUsageEntry {
                 app_key: app_key.clone(),
                 name: metric.name.clone(),
@@ -189,3 +228,153 @@ impl RuntimeState {
         *known_pids = live_pids;
     }
 }
+
+/// A spike needs `min_consecutive` samples at or above `threshold` whose
+/// average also exceeds the process' own earlier baseline by `deviation_ratio`.
+/// Until enough baseline exists the average must clear `threshold + warmup_margin`.
+fn push_and_check_spike(
+    samples: &mut VecDeque<f32>,
+    sample: f32,
+    threshold: f32,
+    min_consecutive: usize,
+    deviation_ratio: f32,
+    warmup_margin: f32,
+) -> bool {
+    samples.push_back(sample);
+    while samples.len() > 120 {
+        samples.pop_front();
+    }
+
+    if samples.len() < min_consecutive {
+        return false;
+    }
+
+    let recent: Vec<f32> = samples
+        .iter()
+        .rev()
+        .take(min_consecutive)
+        .copied()
+        .collect();
+
+    if !recent.iter().all(|value| *value >= threshold) {
+        return false;
+    }
+
+    let recent_avg = recent.iter().sum::<f32>() / recent.len() as f32;
+    let prior_len = samples.len().saturating_sub(min_consecutive);
+    if prior_len < 5 {
+        return recent_avg > threshold + warmup_margin;
+    }
+
+    let prior_avg = samples.iter().take(prior_len).sum::<f32>() / prior_len as f32;
+    recent_avg > prior_avg * deviation_ratio
+}
+
+/// Fits a least-squares line through `(seconds, MB)` samples once a full
+/// window is available. Growth must also be mostly monotonic and large in
+/// absolute terms, which filters out allocator sawtooth patterns.
+fn detect_memory_growth(
+    series: &[(f32, f32)],
+    config: &MemoryGrowthConfig,
+) -> Option<MemoryGrowthObservation> {
+    if series.len() < config.window_samples.max(2) {
+        return None;
+    }
+
+    let (first_secs, first_mb) = *series.first()?;
+    let (last_secs, last_mb) = *series.last()?;
+    let window_secs = last_secs - first_secs;
+    if window_secs <= 0.0 || last_mb - first_mb < config.min_growth_mb {
+        return None;
+    }
+
+    let increasing_steps = series
+        .windows(2)
+        .filter(|pair| pair[1].1 >= pair[0].1)
+        .count();
+    if (increasing_steps as f32) < (series.len() - 1) as f32 * config.min_increasing_ratio {
+        return None;
+    }
+
+    let count = series.len() as f32;
+    let mean_x = series.iter().map(|(x, _)| *x).sum::<f32>() / count;
+    let mean_y = series.iter().map(|(_, y)| *y).sum::<f32>() / count;
+    let covariance: f32 = series
+        .iter()
+        .map(|(x, y)| (x - mean_x) * (y - mean_y))
+        .sum();
+    let variance: f32 = series.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+    if variance <= f32::EPSILON {
+        return None;
+    }
+
+    let slope_mb_per_min = covariance / variance * 60.0;
+    if slope_mb_per_min < config.min_slope_mb_per_min {
+        return None;
+    }
+
+    Some(MemoryGrowthObservation {
+        slope_mb_per_min,
+        window_secs,
+        series_mb: series.iter().map(|(_, value)| *value).collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use super::{detect_memory_growth, push_and_check_spike};
+    use crate::models::MemoryGrowthConfig;
+
+    const MB: f32 = 1024.0 * 1024.0;
+
+    #[test]
+    fn io_spike_requires_sustained_throughput_above_baseline() {
+        let mut samples = VecDeque::new();
+        for _ in 0..10 {
+            assert!(!push_and_check_spike(&mut samples, 2.0 * MB, 50.0 * MB, 5, 3.0, 5.0 * MB));
+        }
+        for _ in 0..4 {
+            assert!(!push_and_check_spike(&mut samples, 120.0 * MB, 50.0 * MB, 5, 3.0, 5.0 * MB));
+        }
+        assert!(push_and_check_spike(&mut samples, 120.0 * MB, 50.0 * MB, 5, 3.0, 5.0 * MB));
+
+        // A single quiet sample breaks the streak.
+        assert!(!push_and_check_spike(&mut samples, 0.0, 50.0 * MB, 5, 3.0, 5.0 * MB));
+    }
+
+    #[test]
+    fn spike_is_suppressed_when_baseline_is_already_high() {
+        let mut samples = VecDeque::new();
+        for _ in 0..20 {
+            push_and_check_spike(&mut samples, 95.0, 90.0, 10, 1.8, 5.0);
+        }
+
+        assert!(!push_and_check_spike(&mut samples, 96.0, 90.0, 10, 1.8, 5.0));
+    }
+
+    #[test]
+    fn memory_growth_detects_steady_leak_and_ignores_sawtooth() {
+        let config = MemoryGrowthConfig {
+            window_samples: 30,
+            min_slope_mb_per_min: 10.0,
+            min_growth_mb: 20.0,
+            min_increasing_ratio: 0.8,
+        };
+
+        let leak: Vec<(f32, f32)> = (0..30).map(|i| (i as f32 * 2.0, 200.0 + i as f32)).collect();
+        let observation = detect_memory_growth(&leak, &config).expect("leak detected");
+        assert!((observation.slope_mb_per_min - 30.0).abs() < 0.1);
+        assert_eq!(observation.series_mb.len(), 30);
+        assert!(detect_memory_growth(&leak[..20], &config).is_none());
+
+        let sawtooth: Vec<(f32, f32)> = (0..30)
+            .map(|i| (i as f32 * 2.0, 200.0 + (i % 4) as f32 * 20.0))
+            .collect();
+        assert!(detect_memory_growth(&sawtooth, &config).is_none());
+
+        let flat: Vec<(f32, f32)> = (0..30).map(|i| (i as f32 * 2.0, 512.0)).collect();
+        assert!(detect_memory_growth(&flat, &config).is_none());
+    }
+}
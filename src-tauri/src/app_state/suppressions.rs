@@ -0,0 +1,289 @@
+use anyhow::{bail, Result};
+use chrono::{DateTime, Utc};
+
+use crate::models::{Alert, ProcessIdentity, SuppressionRule};
+
+use super::RuntimeState;
+
+impl RuntimeState {
+    pub fn list_suppressions(&self) -> Vec<SuppressionRule> {
+        self.inner
+            .suppression_store
+            .lock()
+            .expect("poisoned suppression store lock")
+            .list()
+    }
+
+    pub fn create_suppression(&self, mut rule: SuppressionRule) -> Result<SuppressionRule> {
+        for field in [
+            &mut rule.alert_type,
+            &mut rule.process_path,
+            &mut rule.sha256,
+            &mut rule.parent,
+            &mut rule.evidence_contains,
+            &mut rule.expires_at,
+        ] {
+            *field = field
+                .take()
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty());
+        }
+        rule.reason = rule.reason.trim().to_string();
+        rule.author = rule.author.trim().to_string();
+        if rule.author.is_empty() {
+            rule.author = current_os_user().unwrap_or_default();
+        }
+        validate(&rule)?;
+
+        let now = Utc::now();
+        let mut store = self
+            .inner
+            .suppression_store
+            .lock()
+            .expect("poisoned suppression store lock");
+        let base = format!("suppression-{}", now.timestamp_millis());
+        rule.id = base.clone();
+        let mut suffix = 2;
+        while store.rules().iter().any(|existing| existing.id == rule.id) {
+            rule.id = format!("{base}-{suffix}");
+            suffix += 1;
+        }
+        rule.created_at = now.to_rfc3339();
+        rule.suppressed_count = 0;
+        rule.last_suppressed_at = None;
+        store.insert(rule.clone())?;
+        Ok(rule)
+    }
+
+    pub fn delete_suppression(&self, rule_id: &str) -> Result<bool> {
+        self.inner
+            .suppression_store
+            .lock()
+            .expect("poisoned suppression store lock")
+            .remove(rule_id)
+    }
+
+    /// True when an unexpired rule matches the alert; the rule's counter is
+    /// bumped once per duplicate window.
+    pub(super) fn suppress_alert(&self, alert: &Alert, window_seconds: i64) -> bool {
+        let (process, parent) = self.alert_process_context(alert);
+        let now = Utc::now();
+        let mut store = self
+            .inner
+            .suppression_store
+            .lock()
+            .expect("poisoned suppression store lock");
+        let Some(rule_id) = store
+            .rules()
+            .iter()
+            .find(|rule| matches(rule, alert, process.as_ref(), parent.as_ref(), now))
+            .map(|rule| rule.id.clone())
+        else {
+            return false;
+        };
+        store.record_hit(&rule_id, alert, now, window_seconds);
+        true
+    }
+
+    pub fn flush_suppression_hits(&self) -> Result<()> {
+        self.inner
+            .suppression_store
+            .lock()
+            .expect("poisoned suppression store lock")
+            .flush()
+    }
+
+    /// The alerted process and its parent, from the alert itself or the last
+    /// process snapshot.
+    fn alert_process_context(
+        &self,
+        alert: &Alert,
+    ) -> (Option<ProcessIdentity>, Option<ProcessIdentity>) {
+        let metrics = self
+            .inner
+            .process_metrics
+            .read()
+            .expect("poisoned process metrics lock");
+        let lookup = |pid: u32| {
+            metrics
+                .iter()
+                .find(|metric| metric.pid == pid)
+                .map(|metric| metric.identity())
+        };
+        let process = alert.process.clone().or_else(|| alert.pid.and_then(lookup));
+        let parent = process
+            .as_ref()
+            .and_then(|process| process.ppid)
+            .and_then(lookup);
+        (process, parent)
+    }
+}
+
+fn current_os_user() -> Option<String> {
+    std::env::var("USERNAME")
+        .or_else(|_| std::env::var("USER"))
+        .ok()
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+}
+
+/// A rule must pin a specific binary; one keyed only on the alert type or
+/// evidence would silence a whole alert class.
+fn validate(rule: &SuppressionRule) -> Result<()> {
+    if rule.process_path.is_none() && rule.sha256.is_none() {
+        bail!("suppression rule needs a process path or sha256");
+    }
+    if rule.reason.is_empty() {
+        bail!("suppression rule needs a reason");
+    }
+    if rule.author.is_empty() {
+        bail!("suppression rule needs an author");
+    }
+    if let Some(expires_at) = &rule.expires_at {
+        if DateTime::parse_from_rfc3339(expires_at).is_err() {
+            bail!("expiry must be an RFC 3339 timestamp");
+        }
+    }
+    Ok(())
+}
+
+fn matches(
+    rule: &SuppressionRule,
+    alert: &Alert,
+    process: Option<&ProcessIdentity>,
+    parent: Option<&ProcessIdentity>,
+    now: DateTime<Utc>,
+) -> bool {
+    let expired = rule
+        .expires_at
+        .as_deref()
+        .and_then(|value| DateTime::parse_from_rfc3339(value).ok())
+        .is_some_and(|expires_at| expires_at < now);
+    if expired {
+        return false;
+    }
+
+    let equals = |wanted: &Option<String>, actual: Option<&str>| {
+        wanted
+            .as_deref()
+            .is_none_or(|wanted| actual.is_some_and(|actual| actual.eq_ignore_ascii_case(wanted)))
+    };
+    let parent_matches = rule.parent.as_deref().is_none_or(|wanted| {
+        parent.is_some_and(|parent| {
+            parent.image_name.eq_ignore_ascii_case(wanted)
+                || parent
+                    .image_path
+                    .as_deref()
+                    .is_some_and(|path| path.eq_ignore_ascii_case(wanted))
+        })
+    });
+    let evidence_matches = rule.evidence_contains.as_deref().is_none_or(|wanted| {
+        let wanted = wanted.to_lowercase();
+        std::iter::once(&alert.description)
+            .chain(&alert.evidence)
+            .any(|text| text.to_lowercase().contains(&wanted))
+    });
+
+    equals(&rule.alert_type, Some(&alert.alert_type))
+        && equals(
+            &rule.process_path,
+            process.and_then(|process| process.image_path.as_deref()),
+        )
+        && equals(
+            &rule.sha256,
+            process.and_then(|process| process.sha256.as_deref()),
+        )
+        && parent_matches
+        && evidence_matches
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::app_state::{RuntimeState, StorePaths};
+    use crate::models::{Alert, AlertSeverity, AlertStatus, ProcessIdentity, SuppressionRule};
+    use crate::test_support::TempDir;
+
+    fn alert(id: &str, path: &str) -> Alert {
+        Alert {
+            id: id.to_string(),
+            alert_type: "suspicious_process".to_string(),
+            severity: AlertSeverity::Warn,
+            pid: Some(7),
+            title: format!("Alert {id}"),
+            description: "Unsigned binary in user profile".to_string(),
+            evidence: vec!["Outbound connection to 10.0.0.5:4444".to_string()],
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            status: AlertStatus::Active,
+            evidence_refs: Vec::new(),
+            disposition: None,
+            audit: Vec::new(),
+            process: Some(ProcessIdentity {
+                pid: 7,
+                ppid: None,
+                image_name: "tool.exe".to_string(),
+                image_path: Some(path.to_string()),
+                sha256: None,
+                cmdline: None,
+                user: None,
+                real_uid: None,
+                effective_uid: None,
+                real_gid: None,
+                effective_gid: None,
+            }),
+        }
+    }
+
+    fn rule(expires_at: Option<&str>) -> SuppressionRule {
+        SuppressionRule {
+            id: String::new(),
+            alert_type: Some("suspicious_process".to_string()),
+            process_path: Some("C:\\Tools\\TOOL.exe".to_string()),
+            sha256: None,
+            parent: None,
+            evidence_contains: Some("10.0.0.5".to_string()),
+            expires_at: expires_at.map(str::to_string),
+            reason: "Internal scanner".to_string(),
+            author: "soc".to_string(),
+            created_at: String::new(),
+            suppressed_count: 0,
+            last_suppressed_at: None,
+        }
+    }
+
+    #[test]
+    fn persistent_rules_suppress_matching_alerts_and_count_hits() {
+        let dir = TempDir::new("suppressions");
+        let state = RuntimeState::new(StorePaths::in_dir(&dir)).expect("runtime state");
+
+        let mut empty = rule(None);
+        empty.alert_type = None;
+        empty.process_path = Some("  ".to_string());
+        empty.evidence_contains = None;
+        assert!(state.create_suppression(empty).is_err());
+        let mut class_wide = rule(None);
+        class_wide.process_path = None;
+        assert!(state.create_suppression(class_wide).is_err());
+        state
+            .create_suppression(rule(Some("2000-01-01T00:00:00Z")))
+            .expect("expired rule");
+        let created = state.create_suppression(rule(None)).expect("create");
+
+        for _ in 0..3 {
+            assert!(!state
+                .add_alert_if_new(alert("a1", "C:\\tools\\tool.exe"))
+                .expect("add a1"));
+        }
+        assert!(state
+            .add_alert_if_new(alert("a2", "C:\\Other\\tool.exe"))
+            .expect("add a2"));
+        state.flush_suppression_hits().expect("flush hits");
+        drop(state);
+
+        let reloaded = RuntimeState::new(StorePaths::in_dir(&dir)).expect("reload state");
+        let rules = reloaded.list_suppressions();
+        let counts: Vec<u64> = rules.iter().map(|rule| rule.suppressed_count).collect();
+        assert_eq!(counts, vec![0, 1]);
+        assert!(reloaded.delete_suppression(&created.id).expect("delete"));
+        assert_eq!(reloaded.list_suppressions().len(), 1);
+    }
+}
@@ -0,0 +1,77 @@
+use crate::models::YaraMatch;
+
+use super::RuntimeState;
+
+impl RuntimeState {
+    /// Returns the YARA matches for the file at `path` once its content hash
+    /// has been scanned. Files that were never scanned are queued for the
+    /// background scanner and report no matches until it catches up.
+    pub fn yara_matches(&self, path: &str, sha256: Option<&str>) -> Vec<YaraMatch> {
+        if let Some(matches) = sha256.and_then(|hash| self.cached_yara_matches(hash)) {
+            return matches;
+        }
+        self.queue_yara_scan(path);
+        Vec::new()
+    }
+
+    /// Like [`Self::yara_matches`] for files that are not running, such as
+    /// startup entries, whose hash is only known once the scanner has seen them.
+    pub fn yara_matches_for_path(&self, path: &str) -> Vec<YaraMatch> {
+        let sha256 = self
+            .inner
+            .file_hashes
+            .read()
+            .expect("poisoned file hashes lock")
+            .get(path)
+            .cloned();
+        self.yara_matches(path, sha256.as_deref())
+    }
+
+    pub fn cached_yara_matches(&self, sha256: &str) -> Option<Vec<YaraMatch>> {
+        self.inner
+            .yara_results
+            .read()
+            .expect("poisoned yara results lock")
+            .get(sha256)
+            .cloned()
+    }
+
+    pub fn queue_yara_scan(&self, path: &str) {
+        if self.detection_rules().yara().is_empty() {
+            return;
+        }
+        let mut queue = self
+            .inner
+            .yara_queue
+            .lock()
+            .expect("poisoned yara queue lock");
+        if !queue.iter().any(|queued| queued == path) {
+            queue.push_back(path.to_string());
+        }
+    }
+
+    pub fn next_yara_job(&self) -> Option<String> {
+        self.inner
+            .yara_queue
+            .lock()
+            .expect("poisoned yara queue lock")
+            .pop_front()
+    }
+
+    pub fn complete_yara_scan(&self, sha256: &str, matches: Vec<YaraMatch>) {
+        self.inner
+            .yara_results
+            .write()
+            .expect("poisoned yara results lock")
+            .insert(sha256.to_string(), matches);
+    }
+
+    /// Drops every scan result so files are rescanned against a changed rule set.
+    pub(super) fn clear_yara_results(&self) {
+        self.inner
+            .yara_results
+            .write()
+            .expect("poisoned yara results lock")
+            .clear();
+    }
+}
@@ -0,0 +1,241 @@
+//! Per-application behavioural baselines. While an application is learning,
+//! every observation widens its profile; once learned, anything outside the
+//! profile becomes a scored deviation.
+
+use std::collections::BTreeSet;
+use std::net::SocketAddr;
+
+use crate::models::{AppBaseline, ValueRange};
+
+/// Sets are capped so chatty applications (browsers, updaters) do not grow
+/// without bound. A saturated set is treated as "anything goes".
+const MAX_VALUES_PER_SET: usize = 64;
+const RANGE_TOLERANCE_RATIO: f32 = 1.5;
+const CPU_TOLERANCE_PCT: f32 = 20.0;
+const MEMORY_TOLERANCE_MB: f32 = 256.0;
+
+pub struct BaselineObservation<'a> {
+    pub parent: Option<&'a str>,
+    pub children: &'a [String],
+    /// Non-loopback remote endpoints of the process' current connections.
+    pub remotes: &'a [SocketAddr],
+    pub user: Option<&'a str>,
+    pub cpu_pct: f32,
+    pub memory_mb: f32,
+}
+
+pub struct BaselineDeviation {
+    pub id: &'static str,
+    pub weight: u8,
+    pub reason: String,
+}
+
+pub fn learn(baseline: &mut AppBaseline, observation: &BaselineObservation<'_>) {
+    baseline.samples = baseline.samples.saturating_add(1);
+    if let Some(parent) = observation.parent {
+        insert_capped(&mut baseline.parents, parent.to_lowercase());
+    }
+    for child in observation.children {
+        insert_capped(&mut baseline.children, child.to_lowercase());
+    }
+    for remote in observation.remotes {
+        insert_capped(&mut baseline.remote_hosts, remote.ip().to_string());
+        insert_capped(&mut baseline.remote_ports, remote.port());
+    }
+    if let Some(user) = observation.user {
+        insert_capped(&mut baseline.users, user.to_lowercase());
+    }
+    widen(&mut baseline.cpu_pct, observation.cpu_pct);
+    widen(&mut baseline.memory_mb, observation.memory_mb);
+}
+
+pub fn deviations(
+    baseline: &AppBaseline,
+    observation: &BaselineObservation<'_>,
+) -> Vec<BaselineDeviation> {
+    let mut found = Vec::new();
+
+    if let Some(parent) = observation.parent {
+        if is_new(&baseline.parents, &parent.to_lowercase()) {
+            found.push(BaselineDeviation {
+                id: "new-parent",
+                weight: 25,
+                reason: format!("Launched by {parent}, a parent never seen for this app"),
+            });
+        }
+    }
+
+    let new_children: Vec<&str> = observation
+        .children
+        .iter()
+        .filter(|child| is_new(&baseline.children, &child.to_lowercase()))
+        .map(String::as_str)
+        .collect();
+    if !new_children.is_empty() {
+        found.push(BaselineDeviation {
+            id: "new-child",
+            weight: 25,
+            reason: format!(
+                "Spawned child binaries never seen for this app: {}",
+                new_children.join(", ")
+            ),
+        });
+    }
+
+    let mut new_ports: Vec<u16> = observation
+        .remotes
+        .iter()
+        .map(SocketAddr::port)
+        .filter(|port| is_new(&baseline.remote_ports, port))
+        .collect();
+    new_ports.sort_unstable();
+    new_ports.dedup();
+    if !new_ports.is_empty() {
+        let ports: Vec<String> = new_ports.iter().map(u16::to_string).collect();
+        found.push(BaselineDeviation {
+            id: "new-remote-port",
+            weight: 20,
+            reason: format!(
+                "Connected to remote ports never seen for this app: {}",
+                ports.join(", ")
+            ),
+        });
+    }
+
+    let new_hosts: Vec<String> = observation
+        .remotes
+        .iter()
+        .map(|remote| remote.ip().to_string())
+        .filter(|host| is_new(&baseline.remote_hosts, host))
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+    if !new_hosts.is_empty() {
+        found.push(BaselineDeviation {
+            id: "new-destination",
+            weight: 10,
+            reason: format!(
+                "Connected to destinations never seen for this app: {}",
+                new_hosts.join(", ")
+            ),
+        });
+    }
+
+    if let Some(user) = observation.user {
+        if is_new(&baseline.users, &user.to_lowercase()) {
+            found.push(BaselineDeviation {
+                id: "new-user",
+                weight: 30,
+                reason: format!("Running as {user}, an account never seen for this app"),
+            });
+        }
+    }
+
+    let cpu_pct = observation.cpu_pct;
+    if let Some(range) = baseline
+        .cpu_pct
+        .filter(|range| exceeds(range, cpu_pct, CPU_TOLERANCE_PCT))
+    {
+        found.push(BaselineDeviation {
+            id: "cpu-range",
+            weight: 10,
+            reason: format!(
+                "CPU {cpu_pct:.0}% above the learned range ({:.0}-{:.0}%)",
+                range.min, range.max
+            ),
+        });
+    }
+    let memory_mb = observation.memory_mb;
+    if let Some(range) = baseline
+        .memory_mb
+        .filter(|range| exceeds(range, memory_mb, MEMORY_TOLERANCE_MB))
+    {
+        found.push(BaselineDeviation {
+            id: "memory-range",
+            weight: 10,
+            reason: format!(
+                "Memory {memory_mb:.0} MB above the learned range ({:.0}-{:.0} MB)",
+                range.min, range.max
+            ),
+        });
+    }
+
+    found
+}
+
+fn insert_capped<T: Ord>(set: &mut BTreeSet<T>, value: T) {
+    if set.len() < MAX_VALUES_PER_SET {
+        set.insert(value);
+    }
+}
+
+fn is_new<T: Ord>(set: &BTreeSet<T>, value: &T) -> bool {
+    set.len() < MAX_VALUES_PER_SET && !set.contains(value)
+}
+
+fn widen(range: &mut Option<ValueRange>, value: f32) {
+    let current = range.get_or_insert(ValueRange {
+        min: value,
+        max: value,
+    });
+    current.min = current.min.min(value);
+    current.max = current.max.max(value);
+}
+
+fn exceeds(range: &ValueRange, value: f32, tolerance: f32) -> bool {
+    value > range.max * RANGE_TOLERANCE_RATIO && value - range.max >= tolerance
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{deviations, learn, BaselineObservation};
+    use crate::models::AppBaseline;
+
+    fn observation<'a>(
+        parent: &'a str,
+        children: &'a [String],
+        remotes: &'a [std::net::SocketAddr],
+        cpu_pct: f32,
+    ) -> BaselineObservation<'a> {
+        BaselineObservation {
+            parent: Some(parent),
+            children,
+            remotes,
+            user: Some("alice"),
+            cpu_pct,
+            memory_mb: 200.0,
+        }
+    }
+
+    #[test]
+    fn flags_only_behaviour_outside_the_learned_profile() {
+        let mut baseline = AppBaseline::default();
+        let children = vec!["helper.exe".to_string()];
+        let remotes = vec!["10.0.0.5:443".parse().expect("valid address")];
+        learn(
+            &mut baseline,
+            &observation("explorer.exe", &children, &remotes, 5.0),
+        );
+        learn(&mut baseline, &observation("Explorer.EXE", &[], &[], 12.0));
+
+        assert!(deviations(
+            &baseline,
+            &observation("EXPLORER.exe", &children, &remotes, 25.0)
+        )
+        .is_empty());
+
+        let odd_children = vec!["helper.exe".to_string(), "powershell.exe".to_string()];
+        let odd_remotes = vec!["10.0.0.5:4444".parse().expect("valid address")];
+        let found: Vec<&str> = deviations(
+            &baseline,
+            &observation("winword.exe", &odd_children, &odd_remotes, 80.0),
+        )
+        .iter()
+        .map(|deviation| deviation.id)
+        .collect();
+        assert_eq!(
+            found,
+            vec!["new-parent", "new-child", "new-remote-port", "cpu-range"]
+        );
+    }
+}
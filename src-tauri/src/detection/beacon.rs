@@ -0,0 +1,151 @@
+//! Detects command-and-control beaconing: an application opening connections
+//! to the same remote endpoint at a steady interval. Connections are only seen
+//! when the network collector polls, so intervals shorter than a few polls
+//! cannot be measured and are ignored.
+
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+/// Enough intervals to tell a schedule from a handful of coincidences.
+const MIN_CONNECTIONS: usize = 6;
+const MAX_CONNECTIONS: usize = 24;
+const MIN_INTERVAL_SECS: f64 = 10.0;
+const MAX_INTERVAL_SECS: f64 = 3600.0;
+/// Standard deviation relative to the mean interval.
+const MAX_JITTER_RATIO: f64 = 0.2;
+const IDLE_EXPIRY: Duration = Duration::from_secs(2 * 3600);
+
+#[derive(Debug, Clone)]
+pub struct BeaconStats {
+    pub remote: String,
+    pub connections: usize,
+    pub mean_interval_secs: f64,
+    pub stddev_secs: f64,
+    pub jitter_ratio: f64,
+    pub observed_secs: f64,
+}
+
+#[derive(Default)]
+struct Series {
+    seen: VecDeque<Instant>,
+    reported: bool,
+}
+
+#[derive(Default)]
+pub struct BeaconTracker {
+    series: HashMap<(String, String), Series>,
+}
+
+impl BeaconTracker {
+    /// Records a new connection from `app_key` to `remote`. Returns the
+    /// interval statistics the first time the series looks periodic; it is
+    /// reported again only after the pattern breaks and re-forms.
+    pub fn record(&mut self, app_key: &str, remote: &str, at: Instant) -> Option<BeaconStats> {
+        let series = self
+            .series
+            .entry((app_key.to_string(), remote.to_string()))
+            .or_default();
+        series.seen.push_back(at);
+        while series.seen.len() > MAX_CONNECTIONS {
+            series.seen.pop_front();
+        }
+
+        let stats = periodic_stats(remote, &series.seen);
+        if stats.is_none() {
+            series.reported = false;
+            return None;
+        }
+        if series.reported {
+            return None;
+        }
+        series.reported = true;
+        stats
+    }
+
+    pub fn prune(&mut self, now: Instant) {
+        self.series.retain(|_, series| {
+            series
+                .seen
+                .back()
+                .is_some_and(|last| now.duration_since(*last) <= IDLE_EXPIRY)
+        });
+    }
+}
+
+fn periodic_stats(remote: &str, seen: &VecDeque<Instant>) -> Option<BeaconStats> {
+    if seen.len() < MIN_CONNECTIONS {
+        return None;
+    }
+    let intervals: Vec<f64> = seen
+        .iter()
+        .zip(seen.iter().skip(1))
+        .map(|(earlier, later)| later.duration_since(*earlier).as_secs_f64())
+        .collect();
+    let mean = intervals.iter().sum::<f64>() / intervals.len() as f64;
+    if !(MIN_INTERVAL_SECS..=MAX_INTERVAL_SECS).contains(&mean) {
+        return None;
+    }
+    let variance = intervals
+        .iter()
+        .map(|interval| (interval - mean).powi(2))
+        .sum::<f64>()
+        / intervals.len() as f64;
+    let stddev = variance.sqrt();
+    let jitter_ratio = stddev / mean;
+    if jitter_ratio > MAX_JITTER_RATIO {
+        return None;
+    }
+
+    Some(BeaconStats {
+        remote: remote.to_string(),
+        connections: seen.len(),
+        mean_interval_secs: mean,
+        stddev_secs: stddev,
+        jitter_ratio,
+        observed_secs: intervals.iter().sum(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::BeaconTracker;
+
+    #[test]
+    fn reports_steady_intervals_once_and_ignores_irregular_traffic() {
+        let start = Instant::now();
+        let mut tracker = BeaconTracker::default();
+        let offsets = [0, 60, 118, 181, 240, 302, 360, 420];
+        let reported: Vec<usize> = offsets
+            .iter()
+            .enumerate()
+            .filter_map(|(index, secs)| {
+                tracker
+                    .record(
+                        "agent.exe",
+                        "203.0.113.9:443",
+                        start + Duration::from_secs(*secs),
+                    )
+                    .map(|_| index)
+            })
+            .collect();
+        assert_eq!(reported, vec![5]);
+
+        let mut tracker = BeaconTracker::default();
+        let mut last = None;
+        for secs in [0, 60, 120, 180, 240, 300] {
+            last = tracker.record("agent.exe", "x:443", start + Duration::from_secs(secs));
+        }
+        let stats = last.expect("periodic series");
+        assert_eq!(stats.connections, 6);
+        assert!((stats.mean_interval_secs - 60.0).abs() < 0.01);
+        assert!(stats.jitter_ratio < 0.01);
+
+        let mut tracker = BeaconTracker::default();
+        for secs in [0, 15, 200, 230, 600, 610, 900] {
+            let found = tracker.record("chrome.exe", "x:443", start + Duration::from_secs(secs));
+            assert!(found.is_none());
+        }
+    }
+}
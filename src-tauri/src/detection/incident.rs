@@ -0,0 +1,391 @@
+//! Groups alerts that belong to the same malicious chain into incidents. Two
+//! alerts are linked when they were raised within [`INCIDENT_WINDOW_MINUTES`]
+//! of each other and their processes share a lineage, a binary hash or an
+//! image path; incidents are the connected groups of linked alerts.
+
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::models::{Alert, AlertSeverity, Incident, IncidentProcess, ProcessIdentity};
+
+use super::lineage::{self, Ancestor};
+
+const INCIDENT_WINDOW_MINUTES: i64 = 30;
+/// Incidents spanning this many distinct alert types are raised to critical.
+const ESCALATION_ALERT_TYPES: usize = 3;
+
+pub struct IncidentMember<'a> {
+    pub alert: &'a Alert,
+    pub process: Option<ProcessIdentity>,
+    pub at: DateTime<Utc>,
+}
+
+/// Clusters `members` and builds one incident per group, newest first.
+/// `processes` is used to walk lineage between alerted processes; the alerted
+/// processes themselves are added to it since they may have exited.
+pub fn build_incidents(
+    members: &[IncidentMember<'_>],
+    processes: &HashMap<u32, Ancestor>,
+) -> Vec<Incident> {
+    let mut known = processes.clone();
+    for process in members.iter().filter_map(|member| member.process.as_ref()) {
+        known.entry(process.pid).or_insert_with(|| Ancestor {
+            pid: process.pid,
+            ppid: process.ppid,
+            name: process.image_name.clone(),
+            path: process.image_path.clone(),
+            cmdline: process.cmdline.clone(),
+            user: process.user.clone(),
+        });
+    }
+    let processes = &known;
+    let lineages: Vec<Vec<u32>> = members
+        .iter()
+        .map(|member| match &member.process {
+            Some(process) => std::iter::once(process.pid)
+                .chain(
+                    lineage::ancestor_chain(process.ppid, processes)
+                        .into_iter()
+                        .map(|ancestor| ancestor.pid),
+                )
+                .collect(),
+            None => Vec::new(),
+        })
+        .collect();
+
+    let mut order: Vec<usize> = (0..members.len()).collect();
+    order.sort_by_key(|index| members[*index].at);
+    let window = Duration::minutes(INCIDENT_WINDOW_MINUTES);
+    let mut groups = UnionFind::new(members.len());
+    for (position, &left) in order.iter().enumerate() {
+        for &right in &order[position + 1..] {
+            if members[right].at - members[left].at > window {
+                break;
+            }
+            if linked(
+                &members[left],
+                &members[right],
+                &lineages[left],
+                &lineages[right],
+            ) {
+                groups.union(left, right);
+            }
+        }
+    }
+
+    let mut clusters: HashMap<usize, Vec<usize>> = HashMap::new();
+    for index in order {
+        clusters.entry(groups.find(index)).or_default().push(index);
+    }
+    let mut incidents: Vec<Incident> = clusters
+        .into_values()
+        .map(|indices| {
+            let group: Vec<&IncidentMember<'_>> =
+                indices.iter().map(|index| &members[*index]).collect();
+            build_incident(&group, processes)
+        })
+        .collect();
+    incidents.sort_by(|a, b| b.last_seen.cmp(&a.last_seen));
+    incidents
+}
+
+fn linked(
+    left: &IncidentMember<'_>,
+    right: &IncidentMember<'_>,
+    left_lineage: &[u32],
+    right_lineage: &[u32],
+) -> bool {
+    let (Some(a), Some(b)) = (&left.process, &right.process) else {
+        return false;
+    };
+    let same_lineage = left_lineage.contains(&b.pid) || right_lineage.contains(&a.pid);
+    let same_hash = a.sha256.is_some() && a.sha256 == b.sha256;
+    let same_path = a
+        .image_path
+        .as_deref()
+        .zip(b.image_path.as_deref())
+        .is_some_and(|(a, b)| a.eq_ignore_ascii_case(b));
+    same_lineage || same_hash || same_path
+}
+
+fn build_incident(group: &[&IncidentMember<'_>], processes: &HashMap<u32, Ancestor>) -> Incident {
+    let first = group[0];
+    let last = group[group.len() - 1];
+    let alerts: Vec<Alert> = group.iter().map(|member| member.alert.clone()).collect();
+    let severity = aggregate_severity(&alerts);
+    let lead = alerts
+        .iter()
+        .find(|alert| severity_rank(&alert.severity) == severity_rank(&severity))
+        .unwrap_or(&alerts[0]);
+    let title = match alerts.len() {
+        1 => lead.title.clone(),
+        count => format!("{} (+{} related alerts)", lead.title, count - 1),
+    };
+
+    Incident {
+        id: format!("incident-{}", first.alert.id),
+        title,
+        severity,
+        open: alerts.iter().any(|alert| alert.status.is_open()),
+        first_seen: first.alert.timestamp.clone(),
+        last_seen: last.alert.timestamp.clone(),
+        processes: process_tree(group, processes),
+        alerts,
+        timeline: Vec::new(),
+    }
+}
+
+/// The most severe member, raised to critical when the incident spans
+/// several kinds of alert.
+pub fn aggregate_severity(alerts: &[Alert]) -> AlertSeverity {
+    let highest = alerts
+        .iter()
+        .map(|alert| alert.severity.clone())
+        .max_by_key(severity_rank)
+        .unwrap_or(AlertSeverity::Info);
+    let kinds: HashSet<&str> = alerts
+        .iter()
+        .map(|alert| alert.alert_type.as_str())
+        .collect();
+    if kinds.len() >= ESCALATION_ALERT_TYPES {
+        AlertSeverity::Critical
+    } else {
+        highest
+    }
+}
+
+fn severity_rank(severity: &AlertSeverity) -> u8 {
+    match severity {
+        AlertSeverity::Info => 0,
+        AlertSeverity::Warn => 1,
+        AlertSeverity::Critical => 2,
+    }
+}
+
+/// Alerted processes plus the ancestors that connect one to another.
+fn process_tree(
+    group: &[&IncidentMember<'_>],
+    processes: &HashMap<u32, Ancestor>,
+) -> Vec<IncidentProcess> {
+    let mut nodes: HashMap<u32, IncidentProcess> = HashMap::new();
+    for member in group {
+        let Some(process) = &member.process else {
+            continue;
+        };
+        nodes
+            .entry(process.pid)
+            .or_insert_with(|| IncidentProcess {
+                pid: process.pid,
+                ppid: process.ppid,
+                name: process.image_name.clone(),
+                exe_path: process.image_path.clone(),
+                sha256: process.sha256.clone(),
+                alert_ids: Vec::new(),
+                children: Vec::new(),
+            })
+            .alert_ids
+            .push(member.alert.id.clone());
+    }
+
+    let alerted: HashSet<u32> = nodes.keys().copied().collect();
+    let mut connectors = Vec::new();
+    for node in nodes.values() {
+        let chain = lineage::ancestor_chain(node.ppid, processes);
+        if let Some(end) = chain
+            .iter()
+            .position(|ancestor| alerted.contains(&ancestor.pid))
+        {
+            connectors.extend(chain.into_iter().take(end));
+        }
+    }
+    for ancestor in connectors {
+        nodes
+            .entry(ancestor.pid)
+            .or_insert_with(|| IncidentProcess {
+                pid: ancestor.pid,
+                ppid: ancestor.ppid,
+                name: ancestor.name,
+                exe_path: ancestor.path,
+                sha256: None,
+                alert_ids: Vec::new(),
+                children: Vec::new(),
+            });
+    }
+
+    let mut children_by_parent: HashMap<u32, Vec<u32>> = HashMap::new();
+    let mut roots = Vec::new();
+    for node in nodes.values() {
+        match node
+            .ppid
+            .filter(|ppid| *ppid != node.pid && nodes.contains_key(ppid))
+        {
+            Some(ppid) => children_by_parent.entry(ppid).or_default().push(node.pid),
+            None => roots.push(node.pid),
+        }
+    }
+    roots.sort_unstable();
+    roots
+        .into_iter()
+        .filter_map(|pid| attach_children(pid, &mut nodes, &children_by_parent))
+        .collect()
+}
+
+fn attach_children(
+    pid: u32,
+    nodes: &mut HashMap<u32, IncidentProcess>,
+    children_by_parent: &HashMap<u32, Vec<u32>>,
+) -> Option<IncidentProcess> {
+    let mut node = nodes.remove(&pid)?;
+    let mut children = children_by_parent.get(&pid).cloned().unwrap_or_default();
+    children.sort_unstable();
+    node.children = children
+        .into_iter()
+        .filter_map(|child| attach_children(child, nodes, children_by_parent))
+        .collect();
+    Some(node)
+}
+
+struct UnionFind {
+    parents: Vec<usize>,
+}
+
+impl UnionFind {
+    fn new(len: usize) -> Self {
+        Self {
+            parents: (0..len).collect(),
+        }
+    }
+
+    fn find(&mut self, index: usize) -> usize {
+        let mut root = index;
+        while self.parents[root] != root {
+            root = self.parents[root];
+        }
+        let mut current = index;
+        while self.parents[current] != root {
+            let next = self.parents[current];
+            self.parents[current] = root;
+            current = next;
+        }
+        root
+    }
+
+    fn union(&mut self, left: usize, right: usize) {
+        let (left, right) = (self.find(left), self.find(right));
+        if left != right {
+            self.parents[right] = left;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use chrono::{Duration, Utc};
+
+    use super::{build_incidents, IncidentMember};
+    use crate::detection::lineage::Ancestor;
+    use crate::models::{Alert, AlertSeverity, AlertStatus, ProcessIdentity};
+
+    fn alert(id: &str, alert_type: &str, severity: AlertSeverity) -> Alert {
+        Alert {
+            id: id.to_string(),
+            alert_type: alert_type.to_string(),
+            severity,
+            pid: None,
+            title: id.to_string(),
+            description: String::new(),
+            evidence: Vec::new(),
+            timestamp: id.to_string(),
+            status: AlertStatus::Active,
+            evidence_refs: Vec::new(),
+            disposition: None,
+            audit: Vec::new(),
+            process: None,
+        }
+    }
+
+    fn process(pid: u32, ppid: u32, path: &str) -> ProcessIdentity {
+        ProcessIdentity {
+            pid,
+            ppid: Some(ppid),
+            image_name: path.rsplit('\\').next().unwrap_or(path).to_string(),
+            image_path: Some(path.to_string()),
+            sha256: None,
+            cmdline: None,
+            user: None,
+            real_uid: None,
+            effective_uid: None,
+            real_gid: None,
+            effective_gid: None,
+        }
+    }
+
+    #[test]
+    fn clusters_a_chain_through_lineage_and_shared_binary() {
+        let processes: HashMap<u32, Ancestor> = [(20, 10), (21, 20), (30, 1)]
+            .into_iter()
+            .map(|(pid, ppid)| {
+                let ancestor = Ancestor {
+                    pid,
+                    ppid: Some(ppid),
+                    name: format!("p{pid}.exe"),
+                    path: None,
+                    cmdline: None,
+                    user: None,
+                };
+                (pid, ancestor)
+            })
+            .collect();
+        let alerts = [
+            alert("a1", "suspicious_process", AlertSeverity::Warn),
+            alert("a2", "cpu_spike", AlertSeverity::Info),
+            alert("a3", "correlated_threat", AlertSeverity::Warn),
+            alert("a4", "suspicious_process", AlertSeverity::Warn),
+            alert("a5", "suspicious_process", AlertSeverity::Warn),
+        ];
+        let start = Utc::now();
+        let members = [
+            (&alerts[0], process(10, 1, "C:\\Users\\a\\drop.exe"), 0),
+            (&alerts[1], process(21, 20, "C:\\Tools\\miner.exe"), 5),
+            (&alerts[2], process(40, 2, "C:\\Users\\a\\DROP.exe"), 20),
+            (&alerts[3], process(30, 1, "C:\\Tools\\editor.exe"), 6),
+            (&alerts[4], process(41, 2, "C:\\Users\\a\\drop.exe"), 90),
+        ]
+        .into_iter()
+        .map(|(alert, process, minutes)| IncidentMember {
+            alert,
+            process: Some(process),
+            at: start + Duration::minutes(minutes),
+        })
+        .collect::<Vec<_>>();
+
+        let incidents = build_incidents(&members, &processes);
+        let mut groups: Vec<Vec<&str>> = incidents
+            .iter()
+            .map(|incident| {
+                incident
+                    .alerts
+                    .iter()
+                    .map(|alert| alert.id.as_str())
+                    .collect()
+            })
+            .collect();
+        groups.sort();
+        assert_eq!(groups, vec![vec!["a1", "a2", "a3"], vec!["a4"], vec!["a5"]]);
+
+        let chain = incidents
+            .iter()
+            .find(|incident| incident.alerts.len() == 3)
+            .expect("chain incident");
+        assert_eq!(chain.id, "incident-a1");
+        assert!(matches!(chain.severity, AlertSeverity::Critical));
+        let roots: Vec<u32> = chain.processes.iter().map(|node| node.pid).collect();
+        assert_eq!(roots, vec![10, 40]);
+        let connector = &chain.processes[0].children[0];
+        assert_eq!((connector.pid, connector.alert_ids.len()), (20, 0));
+        assert_eq!(connector.children[0].alert_ids, vec!["a2".to_string()]);
+    }
+}
@@ -0,0 +1,108 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::models::ProcessMetric;
+
+/// Deep enough for real attack chains while bounding work on odd trees.
+const MAX_LINEAGE_DEPTH: usize = 16;
+
+#[derive(Debug, Clone)]
+pub struct Ancestor {
+    pub pid: u32,
+    pub ppid: Option<u32>,
+    pub name: String,
+    pub path: Option<String>,
+    pub cmdline: Option<String>,
+    pub user: Option<String>,
+}
+
+impl From<&ProcessMetric> for Ancestor {
+    fn from(metric: &ProcessMetric) -> Self {
+        Self {
+            pid: metric.pid,
+            ppid: metric.ppid,
+            name: metric.name.clone(),
+            path: metric.exe_path.clone(),
+            cmdline: metric.cmdline.clone(),
+            user: metric.user.clone(),
+        }
+    }
+}
+
+/// Walks `ppid` links from `ppid` upwards and returns the ancestors nearest
+/// first. Stops at unknown pids, cycles left behind by pid reuse, and
+/// `MAX_LINEAGE_DEPTH`.
+pub fn ancestor_chain(ppid: Option<u32>, processes: &HashMap<u32, Ancestor>) -> Vec<Ancestor> {
+    let mut chain = Vec::new();
+    let mut visited = HashSet::new();
+    let mut next = ppid;
+    while let Some(pid) = next {
+        if chain.len() >= MAX_LINEAGE_DEPTH || !visited.insert(pid) {
+            break;
+        }
+        let Some(ancestor) = processes.get(&pid) else {
+            break;
+        };
+        chain.push(ancestor.clone());
+        next = ancestor.ppid.filter(|parent| *parent != pid);
+    }
+    chain
+}
+
+/// Renders the chain oldest first, ending with the process itself.
+pub fn describe_chain(ancestors: &[Ancestor], metric: &ProcessMetric) -> String {
+    ancestors
+        .iter()
+        .rev()
+        .map(|ancestor| format!("{} (PID {})", ancestor.name, ancestor.pid))
+        .chain(std::iter::once(format!(
+            "{} (PID {})",
+            metric.name, metric.pid
+        )))
+        .collect::<Vec<_>>()
+        .join(" → ")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::{ancestor_chain, describe_chain, Ancestor};
+    use crate::models::ProcessMetric;
+
+    fn metric(pid: u32, ppid: Option<u32>, name: &str) -> ProcessMetric {
+        ProcessMetric {
+            pid,
+            ppid,
+            name: name.to_string(),
+            ..ProcessMetric::default()
+        }
+    }
+
+    #[test]
+    fn ancestor_chain_walks_up_and_stops_on_cycles() {
+        let metrics = [
+            metric(1, Some(1), "init"),
+            metric(10, Some(1), "chrome.exe"),
+            metric(20, Some(10), "cmd.exe"),
+            metric(30, Some(20), "powershell.exe"),
+            metric(40, Some(41), "a"),
+            metric(41, Some(40), "b"),
+        ];
+        let processes: HashMap<u32, Ancestor> = metrics
+            .iter()
+            .map(|metric| (metric.pid, Ancestor::from(metric)))
+            .collect();
+
+        let leaf = metric(50, Some(30), "tool.exe");
+        let chain = ancestor_chain(leaf.ppid, &processes);
+        let pids: Vec<u32> = chain.iter().map(|ancestor| ancestor.pid).collect();
+        assert_eq!(pids, vec![30, 20, 10, 1]);
+        assert_eq!(
+            describe_chain(&chain[..3], &leaf),
+            "chrome.exe (PID 10) → cmd.exe (PID 20) → powershell.exe (PID 30) → tool.exe (PID 50)"
+        );
+
+        assert_eq!(ancestor_chain(Some(40), &processes).len(), 2);
+        assert!(ancestor_chain(Some(99), &processes).is_empty());
+    }
+}
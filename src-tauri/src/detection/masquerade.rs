@@ -0,0 +1,453 @@
+//! Flags processes that pose as well-known system binaries: a protected name
+//! running from the wrong place, under the wrong parent or without the expected
+//! signer, and names crafted to look like a protected one (`lsasss.exe`,
+//! `svch0st.exe`, Cyrillic `ѕvchost.exe`).
+
+use crate::models::ProcessMetric;
+
+use super::lineage::Ancestor;
+
+const PATH_MISMATCH_WEIGHT: u8 = 45;
+const PARENT_MISMATCH_WEIGHT: u8 = 30;
+const SIGNER_MISMATCH_WEIGHT: u8 = 40;
+const TYPOSQUAT_WEIGHT: u8 = 40;
+const HOMOGLYPH_WEIGHT: u8 = 50;
+/// Shorter names (`sshd`, `dwm`) sit one edit away from too many real tools.
+const MIN_TYPOSQUAT_STEM: usize = 5;
+
+struct ProtectedBinary {
+    name: &'static str,
+    /// Windows paths are stored without the drive letter.
+    paths: &'static [&'static str],
+    /// Only checked while the parent is still running.
+    parents: &'static [&'static str],
+    signer: Option<&'static str>,
+}
+
+const fn windows(
+    name: &'static str,
+    paths: &'static [&'static str],
+    parents: &'static [&'static str],
+) -> ProtectedBinary {
+    ProtectedBinary {
+        name,
+        paths,
+        parents,
+        signer: Some("microsoft"),
+    }
+}
+
+const fn linux(
+    name: &'static str,
+    paths: &'static [&'static str],
+    parents: &'static [&'static str],
+) -> ProtectedBinary {
+    ProtectedBinary {
+        name,
+        paths,
+        parents,
+        signer: None,
+    }
+}
+
+const PROTECTED_BINARIES: &[ProtectedBinary] = &[
+    windows("system", &[], &[]),
+    windows("registry", &[], &["system"]),
+    windows(
+        "smss.exe",
+        &[r"\windows\system32\smss.exe"],
+        &["system", "smss.exe"],
+    ),
+    windows(
+        "csrss.exe",
+        &[r"\windows\system32\csrss.exe"],
+        &["smss.exe"],
+    ),
+    windows(
+        "wininit.exe",
+        &[r"\windows\system32\wininit.exe"],
+        &["smss.exe"],
+    ),
+    windows(
+        "services.exe",
+        &[r"\windows\system32\services.exe"],
+        &["wininit.exe"],
+    ),
+    windows(
+        "lsass.exe",
+        &[r"\windows\system32\lsass.exe"],
+        &["wininit.exe"],
+    ),
+    windows(
+        "lsaiso.exe",
+        &[r"\windows\system32\lsaiso.exe"],
+        &["wininit.exe"],
+    ),
+    windows(
+        "winlogon.exe",
+        &[r"\windows\system32\winlogon.exe"],
+        &["smss.exe"],
+    ),
+    windows("explorer.exe", &[r"\windows\explorer.exe"], &[]),
+    windows(
+        "dwm.exe",
+        &[r"\windows\system32\dwm.exe"],
+        &["winlogon.exe"],
+    ),
+    windows(
+        "userinit.exe",
+        &[r"\windows\system32\userinit.exe"],
+        &["winlogon.exe"],
+    ),
+    windows(
+        "svchost.exe",
+        &[
+            r"\windows\system32\svchost.exe",
+            r"\windows\syswow64\svchost.exe",
+        ],
+        &["services.exe", "msmpeng.exe"],
+    ),
+    windows(
+        "taskhostw.exe",
+        &[r"\windows\system32\taskhostw.exe"],
+        &["svchost.exe"],
+    ),
+    windows(
+        "runtimebroker.exe",
+        &[r"\windows\system32\runtimebroker.exe"],
+        &["svchost.exe"],
+    ),
+    windows(
+        "spoolsv.exe",
+        &[r"\windows\system32\spoolsv.exe"],
+        &["services.exe"],
+    ),
+    windows("conhost.exe", &[r"\windows\system32\conhost.exe"], &[]),
+    windows(
+        "rundll32.exe",
+        &[
+            r"\windows\system32\rundll32.exe",
+            r"\windows\syswow64\rundll32.exe",
+        ],
+        &[],
+    ),
+    linux(
+        "systemd",
+        &["/usr/lib/systemd/systemd", "/lib/systemd/systemd"],
+        &[],
+    ),
+    linux(
+        "systemd-logind",
+        &[
+            "/usr/lib/systemd/systemd-logind",
+            "/lib/systemd/systemd-logind",
+        ],
+        &["systemd"],
+    ),
+    linux(
+        "sshd",
+        &["/usr/sbin/sshd", "/usr/bin/sshd"],
+        &["systemd", "sshd"],
+    ),
+    linux("cron", &["/usr/sbin/cron"], &["systemd", "cron"]),
+    linux("crond", &["/usr/sbin/crond"], &["systemd", "crond"]),
+    linux("dbus-daemon", &["/usr/bin/dbus-daemon"], &[]),
+    linux("rsyslogd", &["/usr/sbin/rsyslogd"], &["systemd"]),
+];
+
+/// Characters that render like ASCII letters in common fonts.
+const HOMOGLYPHS: &[(char, char)] = &[
+    ('а', 'a'),
+    ('е', 'e'),
+    ('о', 'o'),
+    ('р', 'p'),
+    ('с', 'c'),
+    ('х', 'x'),
+    ('у', 'y'),
+    ('і', 'i'),
+    ('ј', 'j'),
+    ('ѕ', 's'),
+    ('һ', 'h'),
+    ('ԁ', 'd'),
+    ('ӏ', 'l'),
+    ('α', 'a'),
+    ('ε', 'e'),
+    ('ι', 'i'),
+    ('κ', 'k'),
+    ('ν', 'v'),
+    ('ο', 'o'),
+    ('ρ', 'p'),
+    ('τ', 't'),
+    ('υ', 'u'),
+    ('χ', 'x'),
+    ('ı', 'i'),
+];
+
+pub struct MasqueradeFinding {
+    pub id: &'static str,
+    pub weight: u8,
+    pub reason: String,
+    pub detail: String,
+}
+
+pub fn inspect(metric: &ProcessMetric, parent: Option<&Ancestor>) -> Vec<MasqueradeFinding> {
+    let name = metric.name.trim().to_lowercase();
+    match PROTECTED_BINARIES.iter().find(|binary| binary.name == name) {
+        Some(binary) => inspect_protected(binary, metric, parent),
+        None => inspect_lookalike(&metric.name, &name).into_iter().collect(),
+    }
+}
+
+fn inspect_protected(
+    binary: &ProtectedBinary,
+    metric: &ProcessMetric,
+    parent: Option<&Ancestor>,
+) -> Vec<MasqueradeFinding> {
+    let mut findings = Vec::new();
+
+    if let Some(path) = metric.exe_path.as_deref() {
+        let normalized = normalize_path(path);
+        if !binary.paths.is_empty() && !binary.paths.contains(&normalized.as_str()) {
+            findings.push(MasqueradeFinding {
+                id: "path-mismatch",
+                weight: PATH_MISMATCH_WEIGHT,
+                reason: format!("{} running outside its system location", binary.name),
+                detail: format!("{path} (expected {})", binary.paths.join(" or ")),
+            });
+        }
+    }
+
+    if let Some(parent) = parent {
+        let parent_name = parent.name.to_lowercase();
+        if !binary.parents.is_empty() && !binary.parents.contains(&parent_name.as_str()) {
+            findings.push(MasqueradeFinding {
+                id: "parent-mismatch",
+                weight: PARENT_MISMATCH_WEIGHT,
+                reason: format!(
+                    "{} launched by unexpected parent {}",
+                    binary.name, parent.name
+                ),
+                detail: format!(
+                    "{} (PID {}) (expected {})",
+                    parent.name,
+                    parent.pid,
+                    binary.parents.join(" or ")
+                ),
+            });
+        }
+    }
+
+    if let (Some(expected), Some(signature)) = (binary.signer, metric.signature.as_ref()) {
+        let subject = signature
+            .signer
+            .as_ref()
+            .map(|signer| signer.subject.as_str());
+        // The subject is attacker-controlled until the signature is verified.
+        let mismatch = !signature.is_signed()
+            || subject.is_some_and(|subject| !subject.to_lowercase().contains(expected));
+        if mismatch {
+            findings.push(MasqueradeFinding {
+                id: "signer-mismatch",
+                weight: SIGNER_MISMATCH_WEIGHT,
+                reason: format!("{} is not signed by {expected}", binary.name),
+                detail: format!(
+                    "{:?} signature, signer {}",
+                    signature.status,
+                    subject.unwrap_or("unknown")
+                ),
+            });
+        }
+    }
+
+    findings
+}
+
+fn inspect_lookalike(original: &str, name: &str) -> Option<MasqueradeFinding> {
+    let skeleton = skeleton(name);
+    if skeleton != name {
+        if let Some(binary) = PROTECTED_BINARIES
+            .iter()
+            .find(|binary| binary.name == skeleton || is_one_edit_away(&skeleton, binary.name))
+        {
+            return Some(MasqueradeFinding {
+                id: "homoglyph",
+                weight: HOMOGLYPH_WEIGHT,
+                reason: format!("Name uses look-alike characters to imitate {}", binary.name),
+                detail: format!(
+                    "{} (code points: {})",
+                    original.trim(),
+                    code_points(original)
+                ),
+            });
+        }
+    }
+
+    PROTECTED_BINARIES
+        .iter()
+        .find(|binary| is_one_edit_away(name, binary.name))
+        .map(|binary| MasqueradeFinding {
+            id: "typosquat",
+            weight: TYPOSQUAT_WEIGHT,
+            reason: format!(
+                "Name is one edit away from protected binary {}",
+                binary.name
+            ),
+            detail: format!("{} ~ {}", original.trim(), binary.name),
+        })
+}
+
+fn is_one_edit_away(name: &str, protected: &str) -> bool {
+    let stem = protected.strip_suffix(".exe").unwrap_or(protected);
+    stem.chars().count() >= MIN_TYPOSQUAT_STEM && edit_distance(name, protected) == 1
+}
+
+/// Maps look-alike letters to ASCII and drops invisible formatting characters
+/// such as zero-width joiners and bidi overrides.
+fn skeleton(name: &str) -> String {
+    name.chars()
+        .filter(|ch| !matches!(ch, '\u{200b}'..='\u{200f}' | '\u{202a}'..='\u{202e}' | '\u{feff}'))
+        .map(|ch| {
+            HOMOGLYPHS
+                .iter()
+                .find(|(glyph, _)| *glyph == ch)
+                .map(|(_, ascii)| *ascii)
+                .unwrap_or(ch)
+        })
+        .collect()
+}
+
+fn code_points(name: &str) -> String {
+    name.trim()
+        .chars()
+        .filter(|ch| !ch.is_ascii())
+        .map(|ch| format!("U+{:04X}", ch as u32))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn edit_distance(left: &str, right: &str) -> usize {
+    let right: Vec<char> = right.chars().collect();
+    let mut previous: Vec<usize> = (0..=right.len()).collect();
+    for (i, left_char) in left.chars().enumerate() {
+        let mut current = vec![i + 1; right.len() + 1];
+        for (j, right_char) in right.iter().enumerate() {
+            let substitution = previous[j] + usize::from(left_char != *right_char);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[right.len()]
+}
+
+/// Lowercases, drops the `\\?\` prefix and the drive letter so Windows paths
+/// compare regardless of the system drive.
+fn normalize_path(path: &str) -> String {
+    let lower = path.trim().to_lowercase();
+    if lower.starts_with('/') {
+        return lower;
+    }
+    let lower = lower.replace('/', "\\");
+    let lower = lower.strip_prefix(r"\\?\").unwrap_or(&lower);
+    let bytes = lower.as_bytes();
+    if bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':' {
+        return lower[2..].to_string();
+    }
+    lower.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{inspect, PROTECTED_BINARIES};
+    use crate::detection::lineage::Ancestor;
+    use crate::models::{BinarySignature, ProcessMetric, SignatureStatus, SignerDetails};
+    use crate::response_engine::CRITICAL_PROCESS_NAMES;
+
+    fn metric(name: &str, path: Option<&str>) -> ProcessMetric {
+        ProcessMetric {
+            pid: 500,
+            name: name.to_string(),
+            exe_path: path.map(str::to_string),
+            ..ProcessMetric::default()
+        }
+    }
+
+    fn parent(name: &str) -> Ancestor {
+        Ancestor::from(&ProcessMetric {
+            pid: 400,
+            name: name.to_string(),
+            ..ProcessMetric::default()
+        })
+    }
+
+    fn ids(metric: &ProcessMetric, parent: Option<&Ancestor>) -> Vec<&'static str> {
+        inspect(metric, parent)
+            .iter()
+            .map(|finding| finding.id)
+            .collect()
+    }
+
+    #[test]
+    fn every_critical_process_is_protected() {
+        for name in CRITICAL_PROCESS_NAMES {
+            assert!(
+                PROTECTED_BINARIES.iter().any(|binary| binary.name == *name),
+                "{name}"
+            );
+        }
+    }
+
+    #[test]
+    fn flags_protected_binaries_out_of_place() {
+        let services = parent("services.exe");
+        let genuine = metric("svchost.exe", Some(r"\\?\D:\Windows\System32\svchost.exe"));
+        assert!(ids(&genuine, Some(&services)).is_empty());
+
+        let dropped = metric(
+            "svchost.exe",
+            Some(r"C:\Users\bob\AppData\Roaming\svchost.exe"),
+        );
+        assert_eq!(
+            ids(&dropped, Some(&parent("winword.exe"))),
+            vec!["path-mismatch", "parent-mismatch"]
+        );
+
+        let mut resigned = metric("lsass.exe", Some(r"C:\Windows\System32\lsass.exe"));
+        resigned.signature = Some(BinarySignature {
+            status: SignatureStatus::Valid,
+            signer: Some(SignerDetails {
+                subject: "CN=Totally Legit Ltd".to_string(),
+                ..SignerDetails::default()
+            }),
+            verified: true,
+        });
+        assert_eq!(ids(&resigned, None), vec!["signer-mismatch"]);
+
+        let mut self_signed = metric("lsass.exe", Some(r"C:\Windows\System32\lsass.exe"));
+        self_signed.signature = Some(BinarySignature {
+            status: SignatureStatus::Valid,
+            signer: Some(SignerDetails {
+                subject: "CN=Microsoft Windows".to_string(),
+                ..SignerDetails::default()
+            }),
+            verified: false,
+        });
+        assert_eq!(ids(&self_signed, None), vec!["signer-mismatch"]);
+
+        let sshd = metric("sshd", Some("/tmp/.x/sshd"));
+        assert_eq!(ids(&sshd, Some(&parent("systemd"))), vec!["path-mismatch"]);
+    }
+
+    #[test]
+    fn flags_typosquats_and_homoglyphs() {
+        assert_eq!(ids(&metric("lsasss.exe", None), None), vec!["typosquat"]);
+        assert_eq!(ids(&metric("svch0st.exe", None), None), vec!["typosquat"]);
+        assert_eq!(ids(&metric("ѕvchost.exe", None), None), vec!["homoglyph"]);
+        assert_eq!(
+            ids(&metric("lsass\u{200b}.exe", None), None),
+            vec!["homoglyph"]
+        );
+        assert!(ids(&metric("ssh", None), None).is_empty());
+        assert!(ids(&metric("iexplore.exe", None), None).is_empty());
+        assert!(ids(&metric("firefox.exe", None), None).is_empty());
+    }
+}
@@ -1,81 +1,147 @@
+pub mod baseline;
+pub mod beacon;
+pub mod cmdline;
+pub mod incident;
+pub mod lineage;
+pub mod masquerade;
+pub mod prevalence;
+pub mod rules;
+pub mod sequence;
+pub mod sigma;
+pub mod yara;
+
 use chrono::Utc;
 
 use crate::models::{
-    Alert, AlertSeverity, AlertStatus, DetectionProfile, ProcessMetric, RiskLevel,
-    SuspicionAssessment, ThreatVerdict, TrustLevel,
+    Alert, AlertSeverity, AlertStatus, DetectionProfile, MemoryGrowthObservation, ProcessMetric,
+    RiskLevel, SuspicionAssessment, ThreatVerdict, TrustLevel, VerdictThresholds, YaraMatch,
 };
 
-const SCRIPT_HOSTS: &[&str] = &[
-    "powershell.exe",
-    "cmd.exe",
-    "wscript.exe",
-    "cscript.exe",
-    "rundll32.exe",
-    "mshta.exe",
-];
-
-const OFFICE_PARENTS: &[&str] = &[
-    "winword.exe",
-    "excel.exe",
-    "powerpnt.exe",
-    "outlook.exe",
-    "acrord32.exe",
-];
+use self::baseline::BaselineDeviation;
+use self::beacon::BeaconStats;
+use self::lineage::Ancestor;
+use self::prevalence::PrevalenceSignal;
+use self::rules::{RuleInput, RuleSet};
+use self::sequence::CompletedSequence;
+
+/// What the monitoring loop observed about a process this tick, beyond the
+/// metric itself.
+pub struct ProcessSignals<'a> {
+    /// Nearest first.
+    pub ancestors: &'a [Ancestor],
+    pub is_signed: Option<bool>,
+    pub cpu_spike: bool,
+    pub io_spike: bool,
+    pub yara_matches: &'a [YaraMatch],
+    /// Empty while the application's baseline is still learning.
+    pub baseline_deviations: &'a [BaselineDeviation],
+    pub prevalence: &'a [PrevalenceSignal],
+}
 
 pub fn assess_process(
     metric: &ProcessMetric,
-    parent_name: Option<&str>,
-    is_signed: Option<bool>,
-    cpu_spike: bool,
+    signals: &ProcessSignals<'_>,
     profile: &DetectionProfile,
+    rules: &RuleSet,
 ) -> SuspicionAssessment {
-    let mut reasons = Vec::new();
-    let mut score: u8 = 0;
-    let name = metric.name.to_lowercase();
-    let parent = parent_name.unwrap_or_default().to_lowercase();
-
-    if let Some(path) = &metric.exe_path {
-        let path_lower = path.to_lowercase();
-        if path_lower.contains("\\appdata\\local\\temp")
-            || path_lower.contains("\\windows\\temp")
-            || path_lower.contains("\\temp\\")
-        {
-            score = score.saturating_add(45);
-            reasons.push("Executable running from temporary directory".to_string());
+    let mut score = Score {
+        profile,
+        total: 0,
+        reasons: Vec::new(),
+    };
+    let mut evidence = Vec::new();
+
+    let input = RuleInput {
+        name: &metric.name,
+        path: metric.exe_path.as_deref(),
+        parent: signals.ancestors.first().map(|parent| parent.name.as_str()),
+        cmdline: metric.cmdline.as_deref(),
+        user: metric.user.as_deref(),
+        is_signed: signals.is_signed,
+        ancestors: signals.ancestors,
+    };
+    for rule in rules.evaluate(&input) {
+        score.add(&rule.id, rule.weight, rule.reason.clone());
+    }
+
+    if let Some(cmdline) = metric.cmdline.as_deref() {
+        let analysis = cmdline::analyze(cmdline);
+        for indicator in &analysis.indicators {
+            let origin = if indicator.in_payload {
+                " (in decoded payload)"
+            } else {
+                ""
+            };
+            let key = format!("cmdline:{}", indicator.id);
+            if score.add(&key, indicator.weight, format!("{}{origin}", indicator.reason)) {
+                evidence.push(format!("{key}: {}", indicator.matched));
+            }
+        }
+        if let Some(payload) = analysis.decoded_payload {
+            evidence.push(format!("decoded_payload: {payload}"));
         }
+    }
 
-        if path_lower.contains("\\appdata\\roaming\\")
-            && SCRIPT_HOSTS.iter().any(|host| host == &name)
-        {
-            score = score.saturating_add(30);
-            reasons.push("Script host launched from roaming profile path".to_string());
+    for finding in masquerade::inspect(metric, signals.ancestors.first()) {
+        let key = format!("masquerade:{}", finding.id);
+        if score.add(&key, finding.weight, finding.reason) {
+            evidence.push(format!("{key}: {}", finding.detail));
         }
     }
 
-    if SCRIPT_HOSTS.iter().any(|host| host == &name) && OFFICE_PARENTS.iter().any(|p| p == &parent) {
-        score = score.saturating_add(40);
-        reasons.push("Suspicious parent-child relation: office app spawning script host".to_string());
+    for hit in signals.yara_matches {
+        score.add(&format!("yara:{}", hit.rule), hit.weight, hit.reason.clone());
     }
 
-    if is_signed == Some(false) {
-        score = score.saturating_add(35);
-        reasons.push("Binary is unsigned or signature is invalid".to_string());
+    for deviation in signals.baseline_deviations {
+        let key = format!("baseline:{}", deviation.id);
+        if score.add(&key, deviation.weight, deviation.reason.clone()) {
+            evidence.push(key);
+        }
     }
 
-    if cpu_spike {
-        score = score.saturating_add(12);
-        reasons.push("Sustained CPU spike above baseline (performance anomaly)".to_string());
+    for signal in signals.prevalence {
+        let key = format!("prevalence:{}", signal.id);
+        if score.add(&key, signal.weight, signal.reason.clone()) {
+            evidence.push(key);
+        }
     }
 
-    let (suspicious_threshold, unknown_threshold) = match profile {
-        DetectionProfile::Conservative => (85, 45),
-        DetectionProfile::Balanced => (70, 35),
-        DetectionProfile::Aggressive => (55, 25),
-    };
+    if signals.cpu_spike {
+        score.add(
+            "cpu-spike",
+            12,
+            "Sustained CPU spike above baseline (performance anomaly)".to_string(),
+        );
+    }
 
-    let level = if score >= suspicious_threshold {
+    if signals.io_spike {
+        score.add(
+            "io-spike",
+            15,
+            format!(
+                "Sustained disk I/O burst above baseline ({} read, {} written per second)",
+                format_bytes(metric.disk_read_bytes_per_sec),
+                format_bytes(metric.disk_write_bytes_per_sec)
+            ),
+        );
+    }
+
+    let Score {
+        total: score,
+        reasons,
+        ..
+    } = score;
+    if !reasons.is_empty() && !signals.ancestors.is_empty() {
+        evidence.push(format!(
+            "lineage: {}",
+            lineage::describe_chain(signals.ancestors, metric)
+        ));
+    }
+
+    let level = if score >= profile.suspicious_threshold {
         RiskLevel::Suspicious
-    } else if score >= unknown_threshold {
+    } else if score >= profile.unknown_threshold {
         RiskLevel::Unknown
     } else {
         RiskLevel::Legitimate
@@ -87,11 +153,17 @@ pub fn assess_process(
         score,
         reasons,
         confidence,
+        evidence,
     }
 }
 
-pub fn build_alert(metric: &ProcessMetric, assessment: &SuspicionAssessment, cpu_spike: bool) -> Option<Alert> {
-    if assessment.level != RiskLevel::Suspicious && !cpu_spike {
+pub fn build_alert(
+    metric: &ProcessMetric,
+    assessment: &SuspicionAssessment,
+    cpu_spike: bool,
+    io_spike: bool,
+) -> Option<Alert> {
+    if assessment.level != RiskLevel::Suspicious && !cpu_spike && !io_spike {
         return None;
     }
 
@@ -105,6 +177,16 @@ pub fn build_alert(metric: &ProcessMetric, assessment: &SuspicionAssessment, cpu
             ),
             "cpu_spike".to_string(),
         )
+    } else if io_spike && assessment.level != RiskLevel::Suspicious {
+        (
+            AlertSeverity::Warn,
+            format!("High disk I/O sustained in {}", metric.name),
+            format!(
+                "Process {} (PID {}) kept disk throughput above the configured threshold for multiple samples",
+                metric.name, metric.pid
+            ),
+            "io_spike".to_string(),
+        )
     } else {
         (
             AlertSeverity::Critical,
@@ -124,52 +206,218 @@ pub fn build_alert(metric: &ProcessMetric, assessment: &SuspicionAssessment, cpu
         pid: Some(metric.pid),
         title,
         description,
-        evidence: assessment.reasons.clone(),
+        evidence: assessment
+            .reasons
+            .iter()
+            .chain(&assessment.evidence)
+            .cloned()
+            .collect(),
         timestamp: Utc::now().to_rfc3339(),
         status: AlertStatus::Active,
+        disposition: None,
+        audit: Vec::new(),
+        process: None,
+        evidence_refs: Vec::new(),
     })
 }
 
+const MEMORY_SERIES_EVIDENCE_POINTS: usize = 12;
+
+pub fn build_memory_growth_alert(metric: &ProcessMetric, observation: &MemoryGrowthObservation) -> Alert {
+    let first = observation.series_mb.first().copied().unwrap_or_default();
+    let last = observation.series_mb.last().copied().unwrap_or_default();
+    let step = observation
+        .series_mb
+        .len()
+        .div_ceil(MEMORY_SERIES_EVIDENCE_POINTS)
+        .max(1);
+    let mut series: Vec<String> = observation
+        .series_mb
+        .iter()
+        .step_by(step)
+        .map(|value| format!("{:.1}", value))
+        .collect();
+    if (observation.series_mb.len() - 1) % step != 0 {
+        series.push(format!("{:.1}", last));
+    }
+
+    Alert {
+        id: format!("memory_growth-{}-{}", metric.pid, Utc::now().timestamp_millis()),
+        alert_type: "memory_growth".to_string(),
+        severity: AlertSeverity::Warn,
+        pid: Some(metric.pid),
+        title: format!("Sustained memory growth in {}", metric.name),
+        description: format!(
+            "Process {} (PID {}) grew from {:.1} MB to {:.1} MB over {:.0}s",
+            metric.name, metric.pid, first, last, observation.window_secs
+        ),
+        evidence: vec![
+            format!("Growth rate {:.1} MB/min (linear regression)", observation.slope_mb_per_min),
+            format!("Observed series (MB): {}", series.join(", ")),
+        ],
+        timestamp: Utc::now().to_rfc3339(),
+        status: AlertStatus::Active,
+        disposition: None,
+        audit: Vec::new(),
+        process: None,
+        evidence_refs: Vec::new(),
+    }
+}
+
+pub fn build_beaconing_alert(metric: &ProcessMetric, stats: &BeaconStats) -> Alert {
+    Alert {
+        id: format!("beaconing-{}-{}", metric.pid, Utc::now().timestamp_millis()),
+        alert_type: "beaconing".to_string(),
+        severity: AlertSeverity::Warn,
+        pid: Some(metric.pid),
+        title: format!("Periodic beaconing from {} to {}", metric.name, stats.remote),
+        description: format!(
+            "Process {} (PID {}) opened {} connections to {} roughly every {:.0}s",
+            metric.name, metric.pid, stats.connections, stats.remote, stats.mean_interval_secs
+        ),
+        evidence: vec![
+            format!("Remote endpoint: {}", stats.remote),
+            format!(
+                "Mean interval {:.1}s, standard deviation {:.1}s (jitter {:.1}%)",
+                stats.mean_interval_secs,
+                stats.stddev_secs,
+                stats.jitter_ratio * 100.0
+            ),
+            format!(
+                "{} connections over {:.0}s",
+                stats.connections, stats.observed_secs
+            ),
+        ],
+        timestamp: Utc::now().to_rfc3339(),
+        status: AlertStatus::Active,
+        disposition: None,
+        audit: Vec::new(),
+        process: None,
+        evidence_refs: Vec::new(),
+    }
+}
+
+/// Alert for a completed event sequence; `evidence_refs` cites every event
+/// that satisfied a step, in order.
+pub fn build_sequence_alert(
+    done: &CompletedSequence,
+    severity: AlertSeverity,
+    process_name: Option<&str>,
+) -> Alert {
+    let pid = done.pids.first().copied();
+    let subject = match (process_name, pid) {
+        (Some(name), Some(pid)) => format!(" ({name}, PID {pid})"),
+        (None, Some(pid)) => format!(" (PID {pid})"),
+        _ => String::new(),
+    };
+    let mut evidence = vec![format!("sequence:{}", done.rule_id)];
+    evidence.extend(
+        done.steps
+            .iter()
+            .enumerate()
+            .map(|(index, step)| format!("step {}: {step}", index + 1)),
+    );
+    Alert {
+        id: format!(
+            "correlated_sequence-{}-{}",
+            done.rule_id,
+            Utc::now().timestamp_millis()
+        ),
+        alert_type: "correlated_sequence".to_string(),
+        severity,
+        pid,
+        title: format!("{}{subject}", done.title),
+        description: done.description.clone().unwrap_or_else(|| done.title.clone()),
+        evidence,
+        timestamp: Utc::now().to_rfc3339(),
+        status: AlertStatus::Active,
+        disposition: None,
+        audit: Vec::new(),
+        process: None,
+        evidence_refs: done.event_ids.clone(),
+    }
+}
+
+/// Running total for [`assess_process`], applying the profile's per-rule
+/// weight overrides.
+struct Score<'a> {
+    profile: &'a DetectionProfile,
+    total: u8,
+    reasons: Vec<String>,
+}
+
+impl Score<'_> {
+    /// Returns false when the profile disables the check.
+    fn add(&mut self, key: &str, default_weight: u8, reason: String) -> bool {
+        let weight = self.profile.weight(key, default_weight);
+        if weight == 0 {
+            return false;
+        }
+        self.total = self.total.saturating_add(weight);
+        self.reasons.push(reason);
+        true
+    }
+}
+
+fn format_bytes(bytes_per_sec: f32) -> String {
+    let mb = bytes_per_sec / 1024.0 / 1024.0;
+    if mb >= 1.0 {
+        format!("{:.1} MB", mb)
+    } else {
+        format!("{:.0} KB", bytes_per_sec / 1024.0)
+    }
+}
+
 pub fn classify_threat_verdict(
     score: u8,
     base_level: &RiskLevel,
     trust_level: &TrustLevel,
     correlation_count: usize,
     internal_process: bool,
+    cutoffs: &VerdictThresholds,
 ) -> ThreatVerdict {
     if internal_process {
         return ThreatVerdict::Benign;
     }
 
     if *base_level == RiskLevel::Legitimate {
-        if score >= 55 {
+        if score >= cutoffs.legitimate_low_risk {
             return ThreatVerdict::LowRisk;
         }
         return ThreatVerdict::Benign;
     }
 
+    let suspicious = *base_level == RiskLevel::Suspicious;
     let untrusted = *trust_level == TrustLevel::Unknown;
-    if score >= 95 && *base_level == RiskLevel::Suspicious && untrusted && correlation_count >= 2 {
+    if score >= cutoffs.confirmed_malicious
+        && suspicious
+        && untrusted
+        && correlation_count >= cutoffs.confirmed_min_correlations
+    {
         return ThreatVerdict::ConfirmedMalicious;
     }
-    if score >= 86 && *base_level == RiskLevel::Suspicious && untrusted && correlation_count >= 1 {
+    if score >= cutoffs.likely_malicious
+        && suspicious
+        && untrusted
+        && correlation_count >= cutoffs.likely_min_correlations
+    {
         return ThreatVerdict::LikelyMalicious;
     }
-    if score >= 70 && *base_level == RiskLevel::Suspicious {
+    if score >= cutoffs.suspicious && suspicious {
         return ThreatVerdict::Suspicious;
     }
-    if score >= 35 {
+    if score >= cutoffs.low_risk {
         return ThreatVerdict::LowRisk;
     }
     ThreatVerdict::Benign
 }
 
-pub fn compute_risk_score(base_score: u8, correlation_bonuses: &[u8]) -> u8 {
+pub fn compute_risk_score(base_score: u8, correlation_bonuses: &[u8], correlation_cap: u8) -> u8 {
     let correlation_total: u16 = correlation_bonuses
         .iter()
         .map(|bonus| *bonus as u16)
         .sum::<u16>()
-        .min(22);
+        .min(correlation_cap as u16);
     let total = (base_score as u16).saturating_add(correlation_total);
     total.min(100) as u8
 }
@@ -179,6 +427,7 @@ pub fn build_correlated_alert(
     score: u8,
     verdict: &ThreatVerdict,
     correlation_reasons: &[String],
+    evidence_refs: &[String],
 ) -> Option<Alert> {
     if score < 88
         || correlation_reasons.len() < 2
@@ -212,5 +461,9 @@ pub fn build_correlated_alert(
         evidence,
         timestamp: Utc::now().to_rfc3339(),
         status: AlertStatus::Active,
+        disposition: None,
+        audit: Vec::new(),
+        process: None,
+        evidence_refs: evidence_refs.to_vec(),
     })
 }
@@ -0,0 +1,129 @@
+//! Scores how established a binary is on this host: new arrivals, binaries
+//! that almost never run, and files executed right after being written.
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::models::BinaryPrevalence;
+
+const FIRST_SEEN_WEIGHT: u8 = 15;
+const RARE_WEIGHT: u8 = 8;
+const RECENTLY_DROPPED_WEIGHT: u8 = 20;
+const RARE_MAX_RUNS: u64 = 3;
+/// A binary written this close to its first execution was likely dropped and run.
+const DROP_TO_RUN_MINUTES: i64 = 10;
+const DROPPED_SIGNAL_DAYS: i64 = 7;
+
+pub struct PrevalenceSignal {
+    pub id: &'static str,
+    pub weight: u8,
+    pub reason: String,
+}
+
+/// `recording_since` is when this host started recording prevalence; until a
+/// day has passed every binary looks new, so "first seen" and "rare" stay quiet.
+pub fn assess(
+    prevalence: &BinaryPrevalence,
+    recording_since: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> Vec<PrevalenceSignal> {
+    let mut signals = Vec::new();
+    let Some(first_seen) = parse(&prevalence.first_seen) else {
+        return signals;
+    };
+    let age = now.signed_duration_since(first_seen);
+    let established_host = first_seen.signed_duration_since(recording_since) >= Duration::days(1);
+
+    if established_host && age < Duration::days(1) {
+        signals.push(PrevalenceSignal {
+            id: "first-seen",
+            weight: FIRST_SEEN_WEIGHT,
+            reason: format!("Binary first seen on this host {} ago", format_age(age)),
+        });
+    } else if established_host && prevalence.run_count <= RARE_MAX_RUNS {
+        signals.push(PrevalenceSignal {
+            id: "rare",
+            weight: RARE_WEIGHT,
+            reason: format!(
+                "Rarely executed binary ({} runs since {})",
+                prevalence.run_count,
+                first_seen.format("%Y-%m-%d")
+            ),
+        });
+    }
+
+    let dropped = prevalence
+        .file_modified
+        .as_deref()
+        .and_then(parse)
+        .map(|modified| first_seen.signed_duration_since(modified))
+        .is_some_and(|gap| {
+            gap >= Duration::zero() && gap <= Duration::minutes(DROP_TO_RUN_MINUTES)
+        });
+    if dropped && age < Duration::days(DROPPED_SIGNAL_DAYS) {
+        signals.push(PrevalenceSignal {
+            id: "recently-dropped",
+            weight: RECENTLY_DROPPED_WEIGHT,
+            reason: format!(
+                "Binary was written under {DROP_TO_RUN_MINUTES} minutes before it first ran"
+            ),
+        });
+    }
+
+    signals
+}
+
+fn parse(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|value| value.with_timezone(&Utc))
+}
+
+fn format_age(age: Duration) -> String {
+    if age.num_hours() >= 1 {
+        format!("{}h", age.num_hours())
+    } else {
+        format!("{}m", age.num_minutes().max(1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, Utc};
+
+    use super::assess;
+    use crate::models::BinaryPrevalence;
+
+    #[test]
+    fn scores_new_rare_and_dropped_binaries() {
+        let now = Utc::now();
+        let since = now - Duration::days(30);
+        let entry = |first_seen_ago: Duration, runs: u64, written_before_run: Option<Duration>| {
+            let first_seen = now - first_seen_ago;
+            BinaryPrevalence {
+                first_seen: first_seen.to_rfc3339(),
+                run_count: runs,
+                file_modified: written_before_run.map(|gap| (first_seen - gap).to_rfc3339()),
+                ..BinaryPrevalence::default()
+            }
+        };
+        let ids = |prevalence: &BinaryPrevalence, since| {
+            assess(prevalence, since, now)
+                .iter()
+                .map(|signal| signal.id)
+                .collect::<Vec<_>>()
+        };
+
+        let dropped = entry(Duration::hours(2), 1, Some(Duration::minutes(1)));
+        assert_eq!(ids(&dropped, since), vec!["first-seen", "recently-dropped"]);
+        assert_eq!(
+            ids(&dropped, now - Duration::hours(3)),
+            vec!["recently-dropped"]
+        );
+
+        let rare = entry(Duration::days(20), 2, Some(Duration::days(90)));
+        assert_eq!(ids(&rare, since), vec!["rare"]);
+
+        let daily = entry(Duration::days(20), 400, Some(Duration::days(90)));
+        assert!(ids(&daily, since).is_empty());
+    }
+}
@@ -0,0 +1,673 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use regex::Regex;
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::sigma::{self, SigmaRuleSet};
+use super::yara::{self, YaraRuleSet};
+use super::lineage::Ancestor;
+use super::sequence::SequenceRuleSet;
+use crate::models::{DetectionRule, FieldMatcher, SequenceRule};
+
+pub const DEFAULT_PACK_FILE: &str = "default.yaml";
+const DEFAULT_PACK: &str = include_str!("default_rules.yaml");
+const RULE_EXTENSIONS: &[&str] = &["yaml", "yml", "json"];
+/// Sigma rules live in their own subdirectory since they use a different
+/// schema than the native rule files.
+pub const SIGMA_DIR: &str = "sigma";
+pub const YARA_DIR: &str = "yara";
+/// Event-sequence correlation rules, in the same file layout as native rules.
+pub const SEQUENCE_DIR: &str = "sequences";
+const DEFAULT_SEQUENCES: &str = include_str!("default_sequences.yaml");
+const YARA_EXTENSIONS: &[&str] = &["yar", "yara"];
+
+pub type RuleFingerprint = Vec<(PathBuf, u64, Option<SystemTime>)>;
+
+/// Process attributes a rule can look at. Text fields are compared
+/// case-insensitively; `ancestors` is ordered nearest first.
+pub struct RuleInput<'a> {
+    pub name: &'a str,
+    pub path: Option<&'a str>,
+    pub parent: Option<&'a str>,
+    pub cmdline: Option<&'a str>,
+    pub user: Option<&'a str>,
+    pub is_signed: Option<bool>,
+    pub ancestors: &'a [Ancestor],
+}
+
+#[derive(Default)]
+pub struct RuleSet {
+    rules: Vec<CompiledRule>,
+    sigma: SigmaRuleSet,
+    yara: YaraRuleSet,
+    sequences: SequenceRuleSet,
+}
+
+pub struct RuleLoad {
+    pub rules: RuleSet,
+    pub errors: Vec<String>,
+}
+
+struct CompiledRule {
+    rule: DetectionRule,
+    fields: Vec<(RuleField, CompiledMatcher)>,
+    lineage: Vec<Vec<(RuleField, CompiledMatcher)>>,
+}
+
+#[derive(Clone, Copy)]
+enum RuleField {
+    Name,
+    Path,
+    Parent,
+    Cmdline,
+    User,
+}
+
+struct CompiledMatcher {
+    equals: Vec<String>,
+    contains: Vec<String>,
+    starts_with: Vec<String>,
+    ends_with: Vec<String>,
+    regex: Vec<Regex>,
+}
+
+impl RuleSet {
+    /// The built-in pack, used until the rules directory has been read.
+    pub fn default_pack() -> Self {
+        let mut seen = HashSet::new();
+        let mut errors = Vec::new();
+        let mut rules = RuleSet::default();
+        rules.extend_from_source(DEFAULT_PACK, true, DEFAULT_PACK_FILE, &mut seen, &mut errors);
+        seen.clear();
+        rules.extend_sequences_from_source(
+            DEFAULT_SEQUENCES,
+            true,
+            DEFAULT_PACK_FILE,
+            &mut seen,
+            &mut errors,
+        );
+        debug_assert!(errors.is_empty(), "default rule pack is invalid: {errors:?}");
+        rules
+    }
+
+    pub fn definitions(&self) -> Vec<DetectionRule> {
+        self.rules.iter().map(|compiled| compiled.rule.clone()).collect()
+    }
+
+    pub fn sigma(&self) -> &SigmaRuleSet {
+        &self.sigma
+    }
+
+    pub fn yara(&self) -> &YaraRuleSet {
+        &self.yara
+    }
+
+    pub fn sequences(&self) -> &SequenceRuleSet {
+        &self.sequences
+    }
+
+    pub fn evaluate<'a>(&'a self, input: &RuleInput<'_>) -> Vec<&'a DetectionRule> {
+        let name = input.name.to_lowercase();
+        let path = input.path.map(str::to_lowercase);
+        let parent = input.parent.map(str::to_lowercase);
+        let cmdline = input.cmdline.map(str::to_lowercase);
+        let user = input.user.map(str::to_lowercase);
+
+        self.rules
+            .iter()
+            .filter(|compiled| compiled.rule.enabled)
+            .filter(|compiled| {
+                compiled
+                    .rule
+                    .conditions
+                    .signed
+                    .is_none_or(|expected| input.is_signed == Some(expected))
+            })
+            .filter(|compiled| {
+                compiled.fields.iter().all(|(field, matcher)| {
+                    let value = match field {
+                        RuleField::Name => Some(name.as_str()),
+                        RuleField::Path => path.as_deref(),
+                        RuleField::Parent => parent.as_deref(),
+                        RuleField::Cmdline => cmdline.as_deref(),
+                        RuleField::User => user.as_deref(),
+                    };
+                    value.is_some_and(|value| matcher.matches(value))
+                })
+            })
+            .filter(|compiled| lineage_matches(&compiled.lineage, input.ancestors))
+            .map(|compiled| &compiled.rule)
+            .collect()
+    }
+
+    fn extend_from_source(
+        &mut self,
+        source: &str,
+        is_yaml: bool,
+        source_name: &str,
+        seen: &mut HashSet<String>,
+        errors: &mut Vec<String>,
+    ) {
+        let items = rule_items(source, is_yaml, source_name, errors);
+        for (index, item) in items.into_iter().enumerate() {
+            let mut rule = match serde_json::from_value::<DetectionRule>(item) {
+                Ok(rule) => rule,
+                Err(err) => {
+                    errors.push(format!("{source_name}: rule #{}: {err}", index + 1));
+                    continue;
+                }
+            };
+            rule.source = source_name.to_string();
+            if !seen.insert(rule.id.clone()) {
+                errors.push(format!(
+                    "{source_name}: duplicate rule id `{}` ignored",
+                    rule.id
+                ));
+                continue;
+            }
+            match compile_rule(rule) {
+                Ok(compiled) => self.rules.push(compiled),
+                Err(err) => errors.push(format!("{source_name}: {err}")),
+            }
+        }
+    }
+
+    fn extend_sequences_from_source(
+        &mut self,
+        source: &str,
+        is_yaml: bool,
+        source_name: &str,
+        seen: &mut HashSet<String>,
+        errors: &mut Vec<String>,
+    ) {
+        let items = rule_items(source, is_yaml, source_name, errors);
+        for (index, item) in items.into_iter().enumerate() {
+            let mut rule = match serde_json::from_value::<SequenceRule>(item) {
+                Ok(rule) => rule,
+                Err(err) => {
+                    errors.push(format!("{source_name}: sequence #{}: {err}", index + 1));
+                    continue;
+                }
+            };
+            rule.source = source_name.to_string();
+            if let Err(err) = validate_sequence(&rule) {
+                errors.push(format!("{source_name}: {err}"));
+                continue;
+            }
+            if !seen.insert(rule.id.clone()) {
+                errors.push(format!(
+                    "{source_name}: duplicate sequence id `{}` ignored",
+                    rule.id
+                ));
+                continue;
+            }
+            self.sequences.push(rule);
+        }
+    }
+}
+
+/// The rule list of a file: either a top-level list or a `rules:` key.
+fn rule_items(
+    source: &str,
+    is_yaml: bool,
+    source_name: &str,
+    errors: &mut Vec<String>,
+) -> Vec<Value> {
+    let document = if is_yaml {
+        serde_yaml::from_str::<Value>(source).map_err(|err| err.to_string())
+    } else {
+        serde_json::from_str::<Value>(source).map_err(|err| err.to_string())
+    };
+    match document {
+        Ok(Value::Array(items)) => items,
+        Ok(Value::Object(mut map)) => match map.remove("rules") {
+            Some(Value::Array(items)) => items,
+            _ => {
+                errors.push(format!("{source_name}: expected a `rules` list"));
+                Vec::new()
+            }
+        },
+        Ok(Value::Null) => Vec::new(),
+        Ok(_) => {
+            errors.push(format!("{source_name}: expected a `rules` list"));
+            Vec::new()
+        }
+        Err(err) => {
+            errors.push(format!("{source_name}: {err}"));
+            Vec::new()
+        }
+    }
+}
+
+fn validate_sequence(rule: &SequenceRule) -> Result<(), String> {
+    if rule.id.trim().is_empty() {
+        return Err("sequence with empty id".to_string());
+    }
+    if rule.steps.is_empty() {
+        return Err(format!("sequence `{}` has no steps", rule.id));
+    }
+    if let Some(index) = rule.steps.iter().position(|step| step.event_types.is_empty()) {
+        return Err(format!(
+            "sequence `{}`: step #{} lists no event types",
+            rule.id,
+            index + 1
+        ));
+    }
+    if rule.window_secs == 0 {
+        return Err(format!("sequence `{}`: window_secs must be positive", rule.id));
+    }
+    Ok(())
+}
+
+impl CompiledMatcher {
+    fn new(rule_id: &str, matcher: &FieldMatcher) -> Result<Self, String> {
+        let lower = |values: &[String]| values.iter().map(|value| value.to_lowercase()).collect();
+        let regex = matcher
+            .regex
+            .iter()
+            .map(|pattern| {
+                Regex::new(&format!("(?i){pattern}"))
+                    .map_err(|err| format!("rule `{rule_id}`: invalid regex `{pattern}`: {err}"))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let compiled = Self {
+            equals: lower(&matcher.equals),
+            contains: lower(&matcher.contains),
+            starts_with: lower(&matcher.starts_with),
+            ends_with: lower(&matcher.ends_with),
+            regex,
+        };
+        if compiled.equals.is_empty()
+            && compiled.contains.is_empty()
+            && compiled.starts_with.is_empty()
+            && compiled.ends_with.is_empty()
+            && compiled.regex.is_empty()
+        {
+            return Err(format!("rule `{rule_id}`: field matcher has no patterns"));
+        }
+        Ok(compiled)
+    }
+
+    fn matches(&self, value: &str) -> bool {
+        self.equals.iter().any(|pattern| pattern == value)
+            || self.contains.iter().any(|pattern| value.contains(pattern.as_str()))
+            || self.starts_with.iter().any(|pattern| value.starts_with(pattern.as_str()))
+            || self.ends_with.iter().any(|pattern| value.ends_with(pattern.as_str()))
+            || self.regex.iter().any(|pattern| pattern.is_match(value))
+    }
+}
+
+/// Matches the steps as an ordered subsequence of the ancestors, oldest first.
+fn lineage_matches(steps: &[Vec<(RuleField, CompiledMatcher)>], ancestors: &[Ancestor]) -> bool {
+    let mut remaining = steps.iter().peekable();
+    for ancestor in ancestors.iter().rev() {
+        let Some(step) = remaining.peek() else {
+            break;
+        };
+        let step_matches = step.iter().all(|(field, matcher)| {
+            let value = match field {
+                RuleField::Name => Some(ancestor.name.as_str()),
+                RuleField::Path => ancestor.path.as_deref(),
+                RuleField::Cmdline => ancestor.cmdline.as_deref(),
+                RuleField::User => ancestor.user.as_deref(),
+                RuleField::Parent => None,
+            };
+            value.is_some_and(|value| matcher.matches(&value.to_lowercase()))
+        });
+        if step_matches {
+            remaining.next();
+        }
+    }
+    remaining.peek().is_none()
+}
+
+fn compile_rule(rule: DetectionRule) -> Result<CompiledRule, String> {
+    if rule.id.trim().is_empty() {
+        return Err("rule with empty id".to_string());
+    }
+    let conditions = &rule.conditions;
+    let candidates = [
+        (RuleField::Name, &conditions.name),
+        (RuleField::Path, &conditions.path),
+        (RuleField::Parent, &conditions.parent),
+        (RuleField::Cmdline, &conditions.cmdline),
+        (RuleField::User, &conditions.user),
+    ];
+    let mut fields = Vec::new();
+    for (field, matcher) in candidates {
+        if let Some(matcher) = matcher {
+            fields.push((field, CompiledMatcher::new(&rule.id, matcher)?));
+        }
+    }
+    let mut lineage = Vec::new();
+    for (index, step) in conditions.lineage.iter().enumerate() {
+        let candidates = [
+            (RuleField::Name, &step.name),
+            (RuleField::Path, &step.path),
+            (RuleField::Cmdline, &step.cmdline),
+            (RuleField::User, &step.user),
+        ];
+        let mut step_fields = Vec::new();
+        for (field, matcher) in candidates {
+            if let Some(matcher) = matcher {
+                step_fields.push((field, CompiledMatcher::new(&rule.id, matcher)?));
+            }
+        }
+        if step_fields.is_empty() {
+            return Err(format!("rule `{}`: lineage step #{} is empty", rule.id, index + 1));
+        }
+        lineage.push(step_fields);
+    }
+    if fields.is_empty() && lineage.is_empty() && conditions.signed.is_none() {
+        return Err(format!("rule `{}` has no match conditions", rule.id));
+    }
+    Ok(CompiledRule {
+        rule,
+        fields,
+        lineage,
+    })
+}
+
+/// Creates `dir` with the default pack when it does not exist yet. An
+/// existing directory is left alone so deleted or edited defaults stay that way.
+pub fn install_default_pack(dir: &Path) -> Result<bool, String> {
+    if dir.exists() {
+        return Ok(false);
+    }
+    fs::create_dir_all(dir)
+        .map_err(|err| format!("failed creating rules dir {}: {err}", dir.display()))?;
+    fs::write(dir.join(DEFAULT_PACK_FILE), DEFAULT_PACK)
+        .map_err(|err| format!("failed writing default rule pack: {err}"))?;
+    for subdir in [SIGMA_DIR, YARA_DIR, SEQUENCE_DIR] {
+        fs::create_dir_all(dir.join(subdir))
+            .map_err(|err| format!("failed creating {subdir} rules dir: {err}"))?;
+    }
+    fs::write(dir.join(SEQUENCE_DIR).join(DEFAULT_PACK_FILE), DEFAULT_SEQUENCES)
+        .map_err(|err| format!("failed writing default sequence pack: {err}"))?;
+    Ok(true)
+}
+
+pub fn fingerprint(dir: &Path) -> RuleFingerprint {
+    rule_files(dir, RULE_EXTENSIONS)
+        .into_iter()
+        .chain(rule_files(&dir.join(SIGMA_DIR), RULE_EXTENSIONS))
+        .chain(rule_files(&dir.join(YARA_DIR), YARA_EXTENSIONS))
+        .chain(rule_files(&dir.join(SEQUENCE_DIR), RULE_EXTENSIONS))
+        .map(|path| {
+            let metadata = fs::metadata(&path).ok();
+            let len = metadata.as_ref().map(|value| value.len()).unwrap_or(0);
+            let modified = metadata.and_then(|value| value.modified().ok());
+            (path, len, modified)
+        })
+        .collect()
+}
+
+/// Loads every rule file in `dir` in file-name order. Broken files or rules
+/// are skipped and reported; the rest still load.
+pub fn load_rule_dir(dir: &Path) -> RuleLoad {
+    let mut rules = RuleSet::default();
+    let mut seen = HashSet::new();
+    let mut errors = Vec::new();
+
+    for path in rule_files(dir, RULE_EXTENSIONS) {
+        let source_name = source_name(&path);
+        match fs::read_to_string(&path) {
+            Ok(source) => rules.extend_from_source(
+                &source,
+                is_yaml(&path),
+                &source_name,
+                &mut seen,
+                &mut errors,
+            ),
+            Err(err) => errors.push(format!("{source_name}: {err}")),
+        }
+    }
+
+    for path in rule_files(&dir.join(SIGMA_DIR), RULE_EXTENSIONS) {
+        let source_name = format!("{SIGMA_DIR}/{}", source_name(&path));
+        let documents = fs::read_to_string(&path)
+            .map_err(|err| err.to_string())
+            .and_then(|source| {
+                if is_yaml(&path) {
+                    yaml_documents(&source)
+                } else {
+                    serde_json::from_str::<Value>(&source)
+                        .map(|document| vec![document])
+                        .map_err(|err| err.to_string())
+                }
+            });
+        match documents {
+            Ok(documents) => {
+                let (compiled, compile_errors) = sigma::compile_documents(documents, &source_name);
+                compiled.into_iter().for_each(|rule| rules.sigma.push(rule));
+                errors.extend(compile_errors);
+            }
+            Err(err) => errors.push(format!("{source_name}: {err}")),
+        }
+    }
+
+    for path in rule_files(&dir.join(YARA_DIR), YARA_EXTENSIONS) {
+        let source_name = format!("{YARA_DIR}/{}", source_name(&path));
+        match fs::read_to_string(&path) {
+            Ok(source) => {
+                let (compiled, compile_errors) = yara::compile_source(&source, &source_name);
+                compiled.into_iter().for_each(|rule| rules.yara.push(rule));
+                errors.extend(compile_errors);
+            }
+            Err(err) => errors.push(format!("{source_name}: {err}")),
+        }
+    }
+
+    seen.clear();
+    for path in rule_files(&dir.join(SEQUENCE_DIR), RULE_EXTENSIONS) {
+        let source_name = format!("{SEQUENCE_DIR}/{}", source_name(&path));
+        match fs::read_to_string(&path) {
+            Ok(source) => rules.extend_sequences_from_source(
+                &source,
+                is_yaml(&path),
+                &source_name,
+                &mut seen,
+                &mut errors,
+            ),
+            Err(err) => errors.push(format!("{source_name}: {err}")),
+        }
+    }
+
+    RuleLoad { rules, errors }
+}
+
+fn source_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+/// Every non-empty document of a YAML stream, as JSON values so rule types
+/// only need serde derives.
+pub fn yaml_documents(source: &str) -> Result<Vec<Value>, String> {
+    serde_yaml::Deserializer::from_str(source)
+        .map(Value::deserialize)
+        .filter(|document| !matches!(document, Ok(Value::Null)))
+        .collect::<Result<_, _>>()
+        .map_err(|err| err.to_string())
+}
+
+fn is_yaml(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| !extension.eq_ignore_ascii_case("json"))
+}
+
+fn rule_files(dir: &Path, extensions: &[&str]) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .filter(|path| {
+            path.extension()
+                .and_then(|extension| extension.to_str())
+                .is_some_and(|extension| {
+                    extensions
+                        .iter()
+                        .any(|known| extension.eq_ignore_ascii_case(known))
+                })
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::{fingerprint, install_default_pack, load_rule_dir, RuleInput, RuleSet, SEQUENCE_DIR};
+    use crate::detection::lineage::Ancestor;
+    use crate::test_support::TempDir;
+
+    fn input<'a>(name: &'a str, path: &'a str, parent: &'a str) -> RuleInput<'a> {
+        RuleInput {
+            name,
+            path: Some(path),
+            parent: Some(parent),
+            cmdline: None,
+            user: None,
+            is_signed: Some(true),
+            ancestors: &[],
+        }
+    }
+
+    fn ancestor(pid: u32, name: &str) -> Ancestor {
+        Ancestor {
+            pid,
+            ppid: None,
+            name: name.to_string(),
+            path: None,
+            cmdline: None,
+            user: None,
+        }
+    }
+
+    #[test]
+    fn default_pack_reproduces_builtin_heuristics() {
+        let rules = RuleSet::default_pack();
+        assert_eq!(rules.definitions().len(), 5);
+
+        let hits = rules.evaluate(&input(
+            "PowerShell.exe",
+            "C:\\Users\\a\\AppData\\Roaming\\x\\powershell.exe",
+            "WINWORD.EXE",
+        ));
+        let ids: Vec<&str> = hits.iter().map(|rule| rule.id.as_str()).collect();
+        assert_eq!(ids, vec!["script-host-in-roaming", "office-spawns-script-host"]);
+        assert_eq!(hits.iter().map(|rule| rule.weight as u32).sum::<u32>(), 70);
+
+        let mut unsigned = input("tool.exe", "C:\\Users\\a\\AppData\\Local\\Temp\\tool.exe", "explorer.exe");
+        unsigned.is_signed = Some(false);
+        let ids: Vec<String> = rules.evaluate(&unsigned).iter().map(|rule| rule.id.clone()).collect();
+        assert_eq!(ids, vec!["exec-from-temp", "unsigned-binary"]);
+
+        unsigned.is_signed = None;
+        assert_eq!(rules.evaluate(&unsigned).len(), 1);
+    }
+
+    #[test]
+    fn lineage_steps_match_ordered_ancestors_at_any_depth() {
+        let rules = RuleSet::default_pack();
+        let chain_hits = |ancestors: &[Ancestor]| {
+            let mut leaf = input("tool.exe", "C:\\Tools\\tool.exe", &ancestors[0].name);
+            leaf.ancestors = ancestors;
+            rules
+                .evaluate(&leaf)
+                .iter()
+                .any(|rule| rule.id == "browser-shell-chain")
+        };
+
+        // Nearest first: powershell is the parent, chrome the oldest relevant ancestor.
+        let direct = [
+            ancestor(30, "powershell.exe"),
+            ancestor(20, "cmd.exe"),
+            ancestor(10, "chrome.exe"),
+        ];
+        assert!(chain_hits(&direct));
+
+        let with_gaps = [
+            ancestor(40, "conhost.exe"),
+            ancestor(30, "PowerShell.exe"),
+            ancestor(25, "cmd.exe"),
+            ancestor(20, "cmd.exe"),
+            ancestor(10, "msedge.exe"),
+            ancestor(1, "explorer.exe"),
+        ];
+        assert!(chain_hits(&with_gaps));
+
+        let wrong_order = [
+            ancestor(30, "cmd.exe"),
+            ancestor(20, "powershell.exe"),
+            ancestor(10, "chrome.exe"),
+        ];
+        assert!(!chain_hits(&wrong_order));
+    }
+
+    #[test]
+    fn rule_dir_loads_yaml_and_json_and_reports_errors() {
+        let root = TempDir::new("rules");
+        let dir = root.join("rules");
+
+        assert_eq!(install_default_pack(&dir), Ok(true));
+        assert_eq!(install_default_pack(&dir), Ok(false));
+        let customized = root.join("customized");
+        assert_eq!(install_default_pack(&customized), Ok(true));
+        fs::remove_dir_all(customized.join(SEQUENCE_DIR)).expect("delete sequences");
+        assert_eq!(install_default_pack(&customized), Ok(false));
+        assert!(!customized.join(SEQUENCE_DIR).exists());
+        fs::write(
+            dir.join("custom.json"),
+            r#"[{"id": "root-shell", "weight": 20, "reason": "Shell running as root",
+                 "match": {"name": {"equals": ["bash"]}, "user": {"equals": ["root"]}}},
+                {"id": "broken", "weight": 5, "reason": "x", "match": {"cmdline": {"regex": ["("]}}}]"#,
+        )
+        .expect("write json rules");
+        fs::write(dir.join("typo.yml"), "rules:\n  - id: typo\n    wieght: 3\n").expect("write yaml");
+        fs::write(dir.join("notes.txt"), "ignored").expect("write txt");
+        fs::write(
+            dir.join("sigma").join("encoded.yml"),
+            "title: Encoded PowerShell\nlogsource: {category: process_creation}\n\
+             detection:\n  selection:\n    CommandLine|contains: ' -enc '\n  condition: selection\n",
+        )
+        .expect("write sigma rule");
+
+        let load = load_rule_dir(&dir);
+        assert_eq!(load.rules.definitions().len(), 6);
+        assert_eq!(load.rules.sigma().summaries().len(), 1);
+        assert_eq!(load.rules.sigma().summaries()[0].source, "sigma/encoded.yml");
+        assert_eq!(load.rules.sequences().definitions().len(), 4);
+        assert_eq!(load.errors.len(), 2, "{:?}", load.errors);
+        assert!(load.errors[0].starts_with("custom.json: rule `broken`"));
+        assert!(load.errors[1].starts_with("typo.yml: rule #1"));
+
+        let root_shell = RuleInput {
+            name: "bash",
+            path: Some("/usr/bin/bash"),
+            parent: None,
+            cmdline: None,
+            user: Some("ROOT"),
+            is_signed: None,
+            ancestors: &[],
+        };
+        let hits = load.rules.evaluate(&root_shell);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].source, "custom.json");
+
+        let before = fingerprint(&dir);
+        fs::write(dir.join("typo.yml"), "rules: []\n").expect("fix yaml");
+        assert_ne!(before, fingerprint(&dir));
+        assert!(load_rule_dir(&dir).errors.len() == 1);
+    }
+}
@@ -0,0 +1,361 @@
+//! Ordered event-sequence correlation. Each declarative [`SequenceRule`] lists
+//! steps that must be observed in order within the rule's window; the partial
+//! matches live in a serializable [`SequenceState`] so they survive restarts.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::models::{AlertSeverity, EventEnvelope, SequenceRule, SequenceScope, SequenceStep};
+
+/// Upper bound on open partial matches; the oldest are dropped first.
+const MAX_PARTIALS: usize = 1024;
+
+#[derive(Default)]
+pub struct SequenceRuleSet {
+    rules: Vec<SequenceRule>,
+}
+
+impl SequenceRuleSet {
+    pub fn push(&mut self, rule: SequenceRule) {
+        self.rules.push(rule);
+    }
+
+    pub fn definitions(&self) -> Vec<SequenceRule> {
+        self.rules.clone()
+    }
+
+    fn get(&self, id: &str) -> Option<&SequenceRule> {
+        self.rules.iter().find(|rule| rule.id == id)
+    }
+}
+
+/// An event as seen by the engine. `ancestry` holds the pids above the
+/// event's process, nearest first.
+pub struct SequenceEvent<'a> {
+    pub event: &'a EventEnvelope,
+    pub ancestry: &'a [u32],
+    pub at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SequenceProgress {
+    pub rule_id: String,
+    pub next_step: usize,
+    pub pids: Vec<u32>,
+    pub event_ids: Vec<String>,
+    pub steps: Vec<String>,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompletedSequence {
+    pub rule_id: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub scope: SequenceScope,
+    pub bonus: u8,
+    pub alert: Option<AlertSeverity>,
+    pub pids: Vec<u32>,
+    pub event_ids: Vec<String>,
+    pub steps: Vec<String>,
+    pub completed_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SequenceState {
+    #[serde(default)]
+    pub partials: Vec<SequenceProgress>,
+    #[serde(default)]
+    pub completed: Vec<CompletedSequence>,
+}
+
+impl SequenceState {
+    /// Advances open sequences with the event and starts new ones. Returns the
+    /// sequences the event completed.
+    pub fn observe(
+        &mut self,
+        rules: &SequenceRuleSet,
+        input: &SequenceEvent<'_>,
+    ) -> Vec<CompletedSequence> {
+        self.prune(input.at);
+        let pid = input.event.process.as_ref().map(|process| process.pid);
+        let mut finished = Vec::new();
+
+        self.partials.retain_mut(|progress| {
+            let Some(rule) = rules.get(&progress.rule_id).filter(|rule| rule.enabled) else {
+                return false;
+            };
+            let Some(step) = rule.steps.get(progress.next_step) else {
+                return false;
+            };
+            if !in_scope(rule.scope, progress, pid, input.ancestry)
+                || !step_matches(step, input.event)
+            {
+                return true;
+            }
+            record_step(progress, pid, input.event);
+            if progress.next_step < rule.steps.len() {
+                return true;
+            }
+            finished.push(complete(rule, progress, input.at));
+            false
+        });
+
+        for rule in rules.rules.iter().filter(|rule| rule.enabled) {
+            let Some(first) = rule.steps.first() else {
+                continue;
+            };
+            if (rule.scope != SequenceScope::Host && pid.is_none())
+                || !step_matches(first, input.event)
+            {
+                continue;
+            }
+            let already_open = self.partials.iter().any(|progress| {
+                progress.rule_id == rule.id
+                    && progress.next_step == 1
+                    && (rule.scope == SequenceScope::Host || progress.pids.first() == pid.as_ref())
+            });
+            if already_open {
+                continue;
+            }
+            let mut progress = SequenceProgress {
+                rule_id: rule.id.clone(),
+                next_step: 0,
+                pids: Vec::new(),
+                event_ids: Vec::new(),
+                steps: Vec::new(),
+                expires_at: input.at + window(rule),
+            };
+            record_step(&mut progress, pid, input.event);
+            if rule.steps.len() == 1 {
+                finished.push(complete(rule, &progress, input.at));
+            } else {
+                self.partials.push(progress);
+            }
+        }
+        if self.partials.len() > MAX_PARTIALS {
+            let overflow = self.partials.len() - MAX_PARTIALS;
+            self.partials.drain(0..overflow);
+        }
+
+        for done in &finished {
+            self.completed
+                .retain(|existing| existing.rule_id != done.rule_id || existing.pids != done.pids);
+            self.completed.push(done.clone());
+        }
+        finished
+    }
+
+    /// Completed sequences, still inside their window, that involve `pid`.
+    /// Lineage-scoped sequences also involve descendants of their processes
+    /// (`ancestry` holds the pids above `pid`); host-scoped ones involve every process.
+    pub fn active_for(
+        &self,
+        pid: u32,
+        ancestry: &[u32],
+        now: DateTime<Utc>,
+    ) -> Vec<&CompletedSequence> {
+        self.completed
+            .iter()
+            .filter(|done| done.expires_at >= now)
+            .filter(|done| match done.scope {
+                SequenceScope::Host => true,
+                SequenceScope::Process => done.pids.contains(&pid),
+                SequenceScope::Lineage => done
+                    .pids
+                    .iter()
+                    .any(|member| *member == pid || ancestry.contains(member)),
+            })
+            .collect()
+    }
+
+    pub fn prune(&mut self, now: DateTime<Utc>) {
+        self.partials.retain(|progress| progress.expires_at >= now);
+        self.completed.retain(|done| done.expires_at >= now);
+    }
+}
+
+fn window(rule: &SequenceRule) -> Duration {
+    Duration::seconds(i64::try_from(rule.window_secs).unwrap_or(i64::MAX / 1000))
+}
+
+fn in_scope(
+    scope: SequenceScope,
+    progress: &SequenceProgress,
+    pid: Option<u32>,
+    ancestry: &[u32],
+) -> bool {
+    match (scope, pid) {
+        (SequenceScope::Host, _) => true,
+        (_, None) => false,
+        (SequenceScope::Process, Some(pid)) => progress.pids.first() == Some(&pid),
+        (SequenceScope::Lineage, Some(pid)) => progress
+            .pids
+            .iter()
+            .any(|member| *member == pid || ancestry.contains(member)),
+    }
+}
+
+fn step_matches(step: &SequenceStep, event: &EventEnvelope) -> bool {
+    let type_matches = step
+        .event_types
+        .iter()
+        .any(|pattern| match pattern.strip_suffix('*') {
+            Some(prefix) => event.event_type.starts_with(prefix),
+            None => event.event_type == *pattern,
+        });
+    let image_matches = step.image.as_deref().is_none_or(|image| {
+        event
+            .process
+            .as_ref()
+            .is_some_and(|process| process.image_name.eq_ignore_ascii_case(image))
+    });
+    let hit_matches = step
+        .rule_hit
+        .as_deref()
+        .is_none_or(|wanted| event.rule_hits.iter().any(|hit| hit == wanted));
+    type_matches && image_matches && hit_matches
+}
+
+fn record_step(progress: &mut SequenceProgress, pid: Option<u32>, event: &EventEnvelope) {
+    progress.next_step += 1;
+    if let Some(pid) = pid.filter(|pid| !progress.pids.contains(pid)) {
+        progress.pids.push(pid);
+    }
+    progress.event_ids.push(event.event_id.clone());
+    let actor = event
+        .process
+        .as_ref()
+        .map(|process| format!(" by {} (PID {})", process.image_name, process.pid))
+        .unwrap_or_default();
+    progress.steps.push(format!("{}{actor}", event.event_type));
+}
+
+fn complete(
+    rule: &SequenceRule,
+    progress: &SequenceProgress,
+    now: DateTime<Utc>,
+) -> CompletedSequence {
+    CompletedSequence {
+        rule_id: rule.id.clone(),
+        title: rule.title.clone(),
+        description: rule.description.clone(),
+        scope: rule.scope,
+        bonus: rule.bonus,
+        alert: rule.alert.clone(),
+        pids: progress.pids.clone(),
+        event_ids: progress.event_ids.clone(),
+        steps: progress.steps.clone(),
+        completed_at: now,
+        expires_at: now + window(rule),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, Utc};
+
+    use super::{SequenceEvent, SequenceRuleSet, SequenceState};
+    use crate::models::{
+        EventEnvelope, EventSeverity, ProcessIdentity, SequenceRule, SequenceScope, SequenceStep,
+    };
+
+    fn event(id: &str, event_type: &str, pid: Option<u32>) -> EventEnvelope {
+        EventEnvelope {
+            event_id: id.to_string(),
+            host_id: "host".to_string(),
+            timestamp_utc: String::new(),
+            event_type: event_type.to_string(),
+            sensor: "test".to_string(),
+            severity: EventSeverity::Info,
+            message: String::new(),
+            process: pid.map(|pid| ProcessIdentity {
+                pid,
+                ppid: None,
+                image_name: format!("proc{pid}.exe"),
+                image_path: None,
+                sha256: None,
+                cmdline: None,
+                user: None,
+                real_uid: None,
+                effective_uid: None,
+                real_gid: None,
+                effective_gid: None,
+            }),
+            network: None,
+            registry: None,
+            rule_hits: Vec::new(),
+            risk_score: None,
+            verdict: None,
+            evidence_refs: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn completes_ordered_steps_within_one_lineage_and_window() {
+        let step = |types: &[&str]| SequenceStep {
+            event_types: types.iter().map(|value| value.to_string()).collect(),
+            image: None,
+            rule_hit: None,
+        };
+        let mut rules = SequenceRuleSet::default();
+        rules.push(SequenceRule {
+            id: "start-persist-connect".to_string(),
+            title: "Dropper chain".to_string(),
+            description: None,
+            enabled: true,
+            window_secs: 60,
+            scope: SequenceScope::Lineage,
+            bonus: 10,
+            alert: None,
+            steps: vec![
+                step(&["process_started"]),
+                step(&["registry_value_*"]),
+                step(&["connection_opened"]),
+            ],
+            source: String::new(),
+        });
+
+        let start = Utc::now();
+        let mut state = SequenceState::default();
+        let feed = |state: &mut SequenceState,
+                    id: &str,
+                    event_type: &str,
+                    pid: u32,
+                    ancestry: &[u32],
+                    secs: i64| {
+            state
+                .observe(
+                    &rules,
+                    &SequenceEvent {
+                        event: &event(id, event_type, Some(pid)),
+                        ancestry,
+                        at: start + Duration::seconds(secs),
+                    },
+                )
+                .into_iter()
+                .map(|done| (done.event_ids, done.pids))
+                .collect::<Vec<_>>()
+        };
+
+        assert!(feed(&mut state, "e1", "process_started", 10, &[], 0).is_empty());
+        assert!(feed(&mut state, "e2", "connection_opened", 10, &[], 1).is_empty());
+        assert!(feed(&mut state, "e3", "registry_value_added", 99, &[], 2).is_empty());
+        assert!(feed(&mut state, "e4", "registry_value_added", 11, &[10], 3).is_empty());
+        assert_eq!(
+            feed(&mut state, "e5", "connection_opened", 12, &[11, 10], 4),
+            vec![(
+                vec!["e1".to_string(), "e4".to_string(), "e5".to_string()],
+                vec![10, 11, 12]
+            )]
+        );
+        let now = start + Duration::seconds(5);
+        assert_eq!(state.active_for(13, &[12, 11], now).len(), 1);
+        assert!(state.active_for(30, &[], now).is_empty());
+
+        assert!(feed(&mut state, "e6", "process_started", 20, &[], 10).is_empty());
+        assert!(feed(&mut state, "e7", "registry_value_changed", 20, &[], 11).is_empty());
+        assert!(feed(&mut state, "e8", "connection_opened", 20, &[], 90).is_empty());
+    }
+}
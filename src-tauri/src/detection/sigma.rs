@@ -0,0 +1,855 @@
+//! Compiles the subset of Sigma that maps onto Nyx events: `process_creation`
+//! and `network_connection` rules with selection maps, the `contains`,
+//! `startswith`, `endswith`, `re`, `cidr` and `all` modifiers, and boolean
+//! conditions including `1 of`/`all of`. Anything outside that subset is a
+//! load error so a rule never silently matches less than its author expects.
+
+use std::collections::BTreeMap;
+use std::net::{IpAddr, SocketAddr};
+
+use regex::Regex;
+use serde_json::Value;
+
+use crate::models::{EventEnvelope, EventSeverity, ProcessIdentity, SigmaRuleSummary};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SigmaCategory {
+    ProcessCreation,
+    NetworkConnection,
+}
+
+impl SigmaCategory {
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "process_creation" => Some(Self::ProcessCreation),
+            "network_connection" => Some(Self::NetworkConnection),
+            _ => None,
+        }
+    }
+
+    fn for_event(event_type: &str) -> Option<Self> {
+        match event_type {
+            "process_started" => Some(Self::ProcessCreation),
+            "connection_opened" => Some(Self::NetworkConnection),
+            _ => None,
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::ProcessCreation => "process_creation",
+            Self::NetworkConnection => "network_connection",
+        }
+    }
+
+    fn supports(&self, field: Field) -> bool {
+        match self {
+            Self::ProcessCreation => !matches!(
+                field,
+                Field::DestinationIp
+                    | Field::DestinationPort
+                    | Field::SourceIp
+                    | Field::SourcePort
+                    | Field::Protocol
+            ),
+            Self::NetworkConnection => !matches!(
+                field,
+                Field::ParentImage | Field::ParentCommandLine | Field::ParentProcessId
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Image,
+    CommandLine,
+    User,
+    ProcessId,
+    Hashes,
+    ParentImage,
+    ParentCommandLine,
+    ParentProcessId,
+    DestinationIp,
+    DestinationPort,
+    SourceIp,
+    SourcePort,
+    Protocol,
+}
+
+impl Field {
+    fn parse(name: &str) -> Option<Self> {
+        Some(match name {
+            "Image" => Self::Image,
+            "CommandLine" => Self::CommandLine,
+            "User" => Self::User,
+            "ProcessId" => Self::ProcessId,
+            "Hashes" => Self::Hashes,
+            "ParentImage" => Self::ParentImage,
+            "ParentCommandLine" => Self::ParentCommandLine,
+            "ParentProcessId" => Self::ParentProcessId,
+            "DestinationIp" => Self::DestinationIp,
+            "DestinationPort" => Self::DestinationPort,
+            "SourceIp" => Self::SourceIp,
+            "SourcePort" => Self::SourcePort,
+            "Protocol" => Self::Protocol,
+            _ => return None,
+        })
+    }
+}
+
+pub struct SigmaRule {
+    summary: SigmaRuleSummary,
+    category: SigmaCategory,
+    severity: EventSeverity,
+    selections: BTreeMap<String, Vec<Vec<FieldTest>>>,
+    condition: Condition,
+}
+
+struct FieldTest {
+    field: Field,
+    matchers: Vec<ValueMatcher>,
+    require_all: bool,
+}
+
+enum ValueMatcher {
+    Pattern(Regex),
+    Cidr(IpAddr, u8),
+    Null,
+}
+
+enum Condition {
+    Selection(String),
+    Not(Box<Condition>),
+    And(Vec<Condition>),
+    Or(Vec<Condition>),
+    AnyOf(Vec<String>),
+    AllOf(Vec<String>),
+}
+
+/// The event fields a Sigma rule can see. The parent is looked up by the
+/// caller since `EventEnvelope` only carries the parent pid.
+struct EventView<'a> {
+    process: Option<&'a ProcessIdentity>,
+    parent: Option<&'a ProcessIdentity>,
+    event: &'a EventEnvelope,
+}
+
+impl EventView<'_> {
+    fn value(&self, field: Field) -> Option<String> {
+        let process = self.process;
+        let parent = self.parent;
+        let network = self.event.network.as_ref();
+        let image = |identity: &ProcessIdentity| {
+            identity
+                .image_path
+                .clone()
+                .unwrap_or_else(|| identity.image_name.clone())
+        };
+        match field {
+            Field::Image => process.map(image),
+            Field::CommandLine => process.and_then(|value| value.cmdline.clone()),
+            Field::User => process.and_then(|value| value.user.clone()),
+            Field::ProcessId => process.map(|value| value.pid.to_string()),
+            Field::Hashes => process
+                .and_then(|value| value.sha256.as_ref())
+                .map(|hash| format!("SHA256={}", hash.to_uppercase())),
+            Field::ParentImage => parent.map(image),
+            Field::ParentCommandLine => parent.and_then(|value| value.cmdline.clone()),
+            Field::ParentProcessId => process
+                .and_then(|value| value.ppid)
+                .map(|ppid| ppid.to_string()),
+            Field::DestinationIp => network
+                .and_then(|value| value.remote_address.parse::<SocketAddr>().ok())
+                .map(|address| address.ip().to_string()),
+            Field::DestinationPort => network
+                .and_then(|value| value.remote_address.parse::<SocketAddr>().ok())
+                .map(|address| address.port().to_string()),
+            Field::SourceIp => network
+                .and_then(|value| value.local_address.parse::<SocketAddr>().ok())
+                .map(|address| address.ip().to_string()),
+            Field::SourcePort => network
+                .and_then(|value| value.local_address.parse::<SocketAddr>().ok())
+                .map(|address| address.port().to_string()),
+            Field::Protocol => network.map(|value| value.protocol.to_lowercase()),
+        }
+    }
+}
+
+impl SigmaRule {
+    pub fn summary(&self) -> &SigmaRuleSummary {
+        &self.summary
+    }
+
+    pub fn severity(&self) -> &EventSeverity {
+        &self.severity
+    }
+
+    fn matches(&self, view: &EventView<'_>) -> bool {
+        self.eval(&self.condition, view)
+    }
+
+    fn eval(&self, condition: &Condition, view: &EventView<'_>) -> bool {
+        match condition {
+            Condition::Selection(name) => self.selection_matches(name, view),
+            Condition::Not(inner) => !self.eval(inner, view),
+            Condition::And(items) => items.iter().all(|item| self.eval(item, view)),
+            Condition::Or(items) => items.iter().any(|item| self.eval(item, view)),
+            Condition::AnyOf(names) => names.iter().any(|name| self.selection_matches(name, view)),
+            Condition::AllOf(names) => names.iter().all(|name| self.selection_matches(name, view)),
+        }
+    }
+
+    fn selection_matches(&self, name: &str, view: &EventView<'_>) -> bool {
+        self.selections.get(name).is_some_and(|alternatives| {
+            alternatives.iter().any(|tests| {
+                tests
+                    .iter()
+                    .all(|test| test.matches(view.value(test.field).as_deref()))
+            })
+        })
+    }
+}
+
+impl FieldTest {
+    fn matches(&self, value: Option<&str>) -> bool {
+        let check = |matcher: &ValueMatcher| match (matcher, value) {
+            (ValueMatcher::Null, value) => value.is_none_or(str::is_empty),
+            (_, None) => false,
+            (ValueMatcher::Pattern(pattern), Some(value)) => pattern.is_match(value),
+            (ValueMatcher::Cidr(network, prefix), Some(value)) => value
+                .parse::<IpAddr>()
+                .is_ok_and(|address| ip_in_network(address, *network, *prefix)),
+        };
+        if self.require_all {
+            self.matchers.iter().all(check)
+        } else {
+            self.matchers.iter().any(check)
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct SigmaRuleSet {
+    rules: Vec<SigmaRule>,
+}
+
+impl SigmaRuleSet {
+    pub fn push(&mut self, rule: SigmaRule) {
+        self.rules.push(rule);
+    }
+
+    pub fn summaries(&self) -> Vec<SigmaRuleSummary> {
+        self.rules.iter().map(|rule| rule.summary.clone()).collect()
+    }
+
+    pub fn matches<'a>(
+        &'a self,
+        event: &EventEnvelope,
+        parent: Option<&ProcessIdentity>,
+    ) -> Vec<&'a SigmaRule> {
+        let Some(category) = SigmaCategory::for_event(&event.event_type) else {
+            return Vec::new();
+        };
+        let view = EventView {
+            process: event.process.as_ref(),
+            parent,
+            event,
+        };
+        self.rules
+            .iter()
+            .filter(|rule| rule.category == category && rule.matches(&view))
+            .collect()
+    }
+}
+
+/// Compiles every document of a Sigma YAML file. Each broken document is
+/// reported separately so one bad rule does not hide the rest of the file.
+pub fn compile_documents(documents: Vec<Value>, source: &str) -> (Vec<SigmaRule>, Vec<String>) {
+    let mut rules = Vec::new();
+    let mut errors = Vec::new();
+    for (index, document) in documents.iter().enumerate() {
+        match compile_rule(document, source) {
+            Ok(rule) => rules.push(rule),
+            Err(err) => {
+                let title = document
+                    .get("title")
+                    .and_then(Value::as_str)
+                    .map(str::to_string)
+                    .unwrap_or_else(|| format!("document #{}", index + 1));
+                errors.push(format!("{source}: {title}: {err}"));
+            }
+        }
+    }
+    (rules, errors)
+}
+
+fn compile_rule(document: &Value, source: &str) -> Result<SigmaRule, String> {
+    if document.get("action").is_some() {
+        return Err("rule collections (`action`) are not supported".to_string());
+    }
+    let title = document
+        .get("title")
+        .and_then(Value::as_str)
+        .ok_or("missing `title`")?
+        .to_string();
+    let id = document
+        .get("id")
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .unwrap_or_else(|| title.clone());
+
+    let category_name = document
+        .pointer("/logsource/category")
+        .and_then(Value::as_str)
+        .ok_or("missing `logsource.category`")?;
+    let category = SigmaCategory::parse(category_name)
+        .ok_or_else(|| format!("unsupported logsource category `{category_name}`"))?;
+
+    let level = document
+        .get("level")
+        .and_then(Value::as_str)
+        .unwrap_or("medium")
+        .to_lowercase();
+    let severity = match level.as_str() {
+        "informational" | "low" => EventSeverity::Info,
+        "medium" => EventSeverity::Warn,
+        "high" | "critical" => EventSeverity::Critical,
+        other => return Err(format!("unknown level `{other}`")),
+    };
+
+    let detection = document
+        .get("detection")
+        .and_then(Value::as_object)
+        .ok_or("missing `detection` map")?;
+    let mut selections = BTreeMap::new();
+    let mut condition_source = None;
+    for (name, body) in detection {
+        match name.as_str() {
+            "condition" => condition_source = Some(body),
+            "timeframe" => return Err("`timeframe` is not supported".to_string()),
+            _ => {
+                selections.insert(name.clone(), compile_selection(name, body, category)?);
+            }
+        }
+    }
+
+    let condition = match condition_source.ok_or("missing `detection.condition`")? {
+        Value::String(text) => parse_condition(text, &selections)?,
+        Value::Array(items) => Condition::Or(
+            items
+                .iter()
+                .map(|item| {
+                    item.as_str()
+                        .ok_or_else(|| "condition list must contain strings".to_string())
+                        .and_then(|text| parse_condition(text, &selections))
+                })
+                .collect::<Result<_, _>>()?,
+        ),
+        _ => return Err("`detection.condition` must be a string".to_string()),
+    };
+
+    Ok(SigmaRule {
+        summary: SigmaRuleSummary {
+            id,
+            title,
+            level,
+            category: category.as_str().to_string(),
+            source: source.to_string(),
+        },
+        category,
+        severity,
+        selections,
+        condition,
+    })
+}
+
+fn compile_selection(
+    name: &str,
+    body: &Value,
+    category: SigmaCategory,
+) -> Result<Vec<Vec<FieldTest>>, String> {
+    match body {
+        Value::Object(_) => Ok(vec![compile_field_map(name, body, category)?]),
+        Value::Array(items) if items.iter().all(Value::is_object) => items
+            .iter()
+            .map(|item| compile_field_map(name, item, category))
+            .collect(),
+        _ => Err(format!(
+            "selection `{name}`: keyword lists are not supported, use field maps"
+        )),
+    }
+}
+
+fn compile_field_map(
+    name: &str,
+    body: &Value,
+    category: SigmaCategory,
+) -> Result<Vec<FieldTest>, String> {
+    let map = body
+        .as_object()
+        .ok_or_else(|| format!("selection `{name}` must be a map"))?;
+    let mut tests = Vec::new();
+    for (key, values) in map {
+        let mut parts = key.split('|');
+        let field_name = parts.next().unwrap_or_default();
+        let field = Field::parse(field_name)
+            .filter(|field| category.supports(*field))
+            .ok_or_else(|| {
+                format!(
+                    "selection `{name}`: field `{field_name}` is not available for {}",
+                    category.as_str()
+                )
+            })?;
+
+        let mut kind = ValueKind::Exact;
+        let mut require_all = false;
+        for modifier in parts {
+            match modifier {
+                "contains" => kind = ValueKind::Contains,
+                "startswith" => kind = ValueKind::StartsWith,
+                "endswith" => kind = ValueKind::EndsWith,
+                "re" => kind = ValueKind::Regex,
+                "cidr" => kind = ValueKind::Cidr,
+                "all" => require_all = true,
+                other => {
+                    return Err(format!(
+                        "selection `{name}`: unsupported modifier `{other}` on `{field_name}`"
+                    ))
+                }
+            }
+        }
+
+        let raw_values = match values {
+            Value::Array(items) => items.iter().collect(),
+            single => vec![single],
+        };
+        let matchers = raw_values
+            .into_iter()
+            .map(|value| compile_value(value, kind))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|err| format!("selection `{name}`, field `{field_name}`: {err}"))?;
+        tests.push(FieldTest {
+            field,
+            matchers,
+            require_all,
+        });
+    }
+    Ok(tests)
+}
+
+#[derive(Clone, Copy)]
+enum ValueKind {
+    Exact,
+    Contains,
+    StartsWith,
+    EndsWith,
+    Regex,
+    Cidr,
+}
+
+fn compile_value(value: &Value, kind: ValueKind) -> Result<ValueMatcher, String> {
+    let text = match value {
+        Value::Null => return Ok(ValueMatcher::Null),
+        Value::String(text) => text.clone(),
+        Value::Number(number) => number.to_string(),
+        Value::Bool(flag) => flag.to_string(),
+        _ => return Err("values must be scalars".to_string()),
+    };
+    match kind {
+        ValueKind::Regex => Regex::new(&text)
+            .map(ValueMatcher::Pattern)
+            .map_err(|err| format!("invalid regex `{text}`: {err}")),
+        ValueKind::Cidr => parse_cidr(&text).ok_or_else(|| format!("invalid CIDR `{text}`")),
+        ValueKind::Exact => glob_to_regex(&text, false, false),
+        ValueKind::Contains => glob_to_regex(&text, true, true),
+        ValueKind::StartsWith => glob_to_regex(&text, false, true),
+        ValueKind::EndsWith => glob_to_regex(&text, true, false),
+    }
+}
+
+/// Sigma values are case-insensitive globs where `*` and `?` are wildcards
+/// and a backslash only escapes a following wildcard or backslash.
+fn glob_to_regex(value: &str, open_start: bool, open_end: bool) -> Result<ValueMatcher, String> {
+    let mut pattern = String::from("(?is)^");
+    if open_start {
+        pattern.push_str(".*");
+    }
+    let mut chars = value.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\\' if matches!(chars.peek(), Some('*' | '?' | '\\')) => {
+                let escaped = chars.next().unwrap_or('\\');
+                pattern.push_str(&regex::escape(&escaped.to_string()));
+            }
+            '*' => pattern.push_str(".*"),
+            '?' => pattern.push('.'),
+            other => pattern.push_str(&regex::escape(&other.to_string())),
+        }
+    }
+    if open_end {
+        pattern.push_str(".*");
+    }
+    pattern.push('$');
+    Regex::new(&pattern)
+        .map(ValueMatcher::Pattern)
+        .map_err(|err| format!("invalid value `{value}`: {err}"))
+}
+
+fn parse_cidr(text: &str) -> Option<ValueMatcher> {
+    let (address, prefix) = text.split_once('/').unwrap_or((text, ""));
+    let address: IpAddr = address.trim().parse().ok()?;
+    let max = if address.is_ipv4() { 32 } else { 128 };
+    let prefix = if prefix.is_empty() {
+        max
+    } else {
+        prefix
+            .trim()
+            .parse::<u8>()
+            .ok()
+            .filter(|value| *value <= max)?
+    };
+    Some(ValueMatcher::Cidr(address, prefix))
+}
+
+fn ip_in_network(address: IpAddr, network: IpAddr, prefix: u8) -> bool {
+    let (address, network, width) = match (address, network) {
+        (IpAddr::V4(address), IpAddr::V4(network)) => {
+            (u32::from(address) as u128, u32::from(network) as u128, 32)
+        }
+        (IpAddr::V6(address), IpAddr::V6(network)) => {
+            (u128::from(address), u128::from(network), 128)
+        }
+        _ => return false,
+    };
+    if prefix == 0 {
+        return true;
+    }
+    let shift = width - prefix as u32;
+    (address >> shift) == (network >> shift)
+}
+
+fn parse_condition(
+    text: &str,
+    selections: &BTreeMap<String, Vec<Vec<FieldTest>>>,
+) -> Result<Condition, String> {
+    if text.contains('|') {
+        return Err("aggregation conditions are not supported".to_string());
+    }
+    let spaced = text.replace('(', " ( ").replace(')', " ) ");
+    let tokens: Vec<&str> = spaced.split_whitespace().collect();
+    let mut parser = ConditionParser {
+        tokens,
+        pos: 0,
+        selections,
+    };
+    let condition = parser.or_expr()?;
+    if let Some(token) = parser.tokens.get(parser.pos) {
+        return Err(format!("unexpected `{token}` in condition"));
+    }
+    Ok(condition)
+}
+
+struct ConditionParser<'a> {
+    tokens: Vec<&'a str>,
+    pos: usize,
+    selections: &'a BTreeMap<String, Vec<Vec<FieldTest>>>,
+}
+
+impl<'a> ConditionParser<'a> {
+    fn peek_keyword(&self, keyword: &str) -> bool {
+        self.tokens
+            .get(self.pos)
+            .is_some_and(|token| token.eq_ignore_ascii_case(keyword))
+    }
+
+    fn next(&mut self) -> Result<&'a str, String> {
+        let token = self
+            .tokens
+            .get(self.pos)
+            .copied()
+            .ok_or("condition ended unexpectedly")?;
+        self.pos += 1;
+        Ok(token)
+    }
+
+    fn or_expr(&mut self) -> Result<Condition, String> {
+        let mut items = vec![self.and_expr()?];
+        while self.peek_keyword("or") {
+            self.pos += 1;
+            items.push(self.and_expr()?);
+        }
+        Ok(if items.len() == 1 {
+            items.remove(0)
+        } else {
+            Condition::Or(items)
+        })
+    }
+
+    fn and_expr(&mut self) -> Result<Condition, String> {
+        let mut items = vec![self.not_expr()?];
+        while self.peek_keyword("and") {
+            self.pos += 1;
+            items.push(self.not_expr()?);
+        }
+        Ok(if items.len() == 1 {
+            items.remove(0)
+        } else {
+            Condition::And(items)
+        })
+    }
+
+    fn not_expr(&mut self) -> Result<Condition, String> {
+        if self.peek_keyword("not") {
+            self.pos += 1;
+            return Ok(Condition::Not(Box::new(self.not_expr()?)));
+        }
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<Condition, String> {
+        let token = self.next()?;
+        if token == "(" {
+            let inner = self.or_expr()?;
+            if self.next()? != ")" {
+                return Err("missing `)` in condition".to_string());
+            }
+            return Ok(inner);
+        }
+
+        let quantifier = token.to_lowercase();
+        if matches!(quantifier.as_str(), "1" | "any" | "all") && self.peek_keyword("of") {
+            self.pos += 1;
+            let target = self.next()?;
+            let names = self.resolve_pattern(target)?;
+            return Ok(if quantifier == "all" {
+                Condition::AllOf(names)
+            } else {
+                Condition::AnyOf(names)
+            });
+        }
+
+        if self.selections.contains_key(token) {
+            Ok(Condition::Selection(token.to_string()))
+        } else {
+            Err(format!("condition references unknown selection `{token}`"))
+        }
+    }
+
+    fn resolve_pattern(&self, target: &str) -> Result<Vec<String>, String> {
+        let names: Vec<String> = if target == "them" {
+            self.selections
+                .keys()
+                .filter(|name| !name.starts_with('_'))
+                .cloned()
+                .collect()
+        } else if let Some(prefix) = target.strip_suffix('*') {
+            self.selections
+                .keys()
+                .filter(|name| name.starts_with(prefix))
+                .cloned()
+                .collect()
+        } else {
+            self.selections
+                .keys()
+                .filter(|name| name.as_str() == target)
+                .cloned()
+                .collect()
+        };
+        if names.is_empty() {
+            return Err(format!("`of {target}` matches no selection"));
+        }
+        Ok(names)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compile_documents, SigmaRuleSet};
+    use crate::detection::rules::yaml_documents;
+    use crate::models::{EventEnvelope, EventSeverity, NetworkEvidence, ProcessIdentity};
+
+    const RULES: &str = r#"
+title: Office Spawning Encoded PowerShell
+id: 5b2d7a6e-0001
+level: high
+logsource:
+  category: process_creation
+  product: windows
+detection:
+  selection_parent:
+    ParentImage|endswith:
+      - '\winword.exe'
+      - '\excel.exe'
+  selection_child:
+    Image|endswith: '\powershell.exe'
+    CommandLine|contains|all:
+      - ' -enc'
+      - 'hidden'
+  filter_signed_tool:
+    CommandLine|re: 'Invoke-Corp[A-Z]+'
+  condition: all of selection_* and not filter_signed_tool
+---
+title: Script Host Talking To Public Address
+level: medium
+logsource:
+  category: network_connection
+detection:
+  selection:
+    Image|endswith: ['\wscript.exe', '\cscript.exe']
+    DestinationPort: 443
+  filter_private:
+    DestinationIp|cidr:
+      - '10.0.0.0/8'
+      - '192.168.0.0/16'
+  condition: selection and not filter_private
+"#;
+
+    fn identity(path: &str, cmdline: &str) -> ProcessIdentity {
+        ProcessIdentity {
+            pid: 20,
+            ppid: Some(10),
+            image_name: path.rsplit('\\').next().unwrap_or(path).to_string(),
+            image_path: Some(path.to_string()),
+            cmdline: Some(cmdline.to_string()),
+            ..ProcessIdentity::default()
+        }
+    }
+
+    fn compile(source: &str) -> SigmaRuleSet {
+        let documents = yaml_documents(source).expect("yaml");
+        let (rules, errors) = compile_documents(documents, "test.yml");
+        assert!(errors.is_empty(), "{errors:?}");
+        let mut set = SigmaRuleSet::default();
+        for rule in rules {
+            set.push(rule);
+        }
+        set
+    }
+
+    #[test]
+    fn process_creation_rule_uses_parent_modifiers_and_condition() {
+        let rules = compile(RULES);
+        let parent = identity("C:\\Program Files\\Office\\WINWORD.EXE", "winword.exe /n");
+        let mut event = EventEnvelope {
+            event_type: "process_started".to_string(),
+            process: Some(identity(
+                "C:\\Windows\\System32\\WindowsPowerShell\\v1.0\\powershell.exe",
+                "powershell.exe -w Hidden -EncodedCommand SQBFAFgA",
+            )),
+            ..EventEnvelope::default()
+        };
+
+        let hits = rules.matches(&event, Some(&parent));
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].summary().id, "5b2d7a6e-0001");
+        assert!(matches!(hits[0].severity(), EventSeverity::Critical));
+
+        assert!(rules.matches(&event, None).is_empty());
+        event.process = Some(identity(
+            "C:\\Windows\\System32\\WindowsPowerShell\\v1.0\\powershell.exe",
+            "powershell.exe -w hidden -enc AAAA Invoke-CorpInventory",
+        ));
+        assert!(rules.matches(&event, Some(&parent)).is_empty());
+    }
+
+    #[test]
+    fn network_rule_matches_ports_and_cidr_filters() {
+        let rules = compile(RULES);
+        let mut event = EventEnvelope {
+            event_type: "connection_opened".to_string(),
+            process: Some(identity(
+                "C:\\Windows\\System32\\wscript.exe",
+                "wscript.exe a.js",
+            )),
+            network: Some(NetworkEvidence {
+                protocol: "tcp".to_string(),
+                local_address: "192.168.1.5:50000".to_string(),
+                remote_address: "203.0.113.7:443".to_string(),
+                state: Some("ESTABLISHED".to_string()),
+                pid: 20,
+            }),
+            ..EventEnvelope::default()
+        };
+        assert_eq!(rules.matches(&event, None).len(), 1);
+
+        if let Some(network) = event.network.as_mut() {
+            network.remote_address = "10.1.2.3:443".to_string();
+        }
+        assert!(rules.matches(&event, None).is_empty());
+    }
+
+    #[test]
+    fn rules_may_use_anchors_aliases_and_multi_line_flow_lists() {
+        let rules = compile(
+            r#"
+title: Shells From Temp
+logsource: {category: process_creation}
+detection:
+  selection_image:
+    Image|endswith: &shells ['\cmd.exe',
+                             '\powershell.exe']
+  selection_parent:
+    ParentImage|contains: '\Temp\'
+  filter_shell_parent:
+    ParentImage|endswith: *shells
+  condition: all of selection_* and not filter_shell_parent
+"#,
+        );
+        let event = EventEnvelope {
+            event_type: "process_started".to_string(),
+            process: Some(identity("C:\\Windows\\System32\\cmd.exe", "cmd.exe /c x")),
+            ..EventEnvelope::default()
+        };
+
+        let dropper = identity("C:\\Users\\a\\AppData\\Local\\Temp\\setup.exe", "setup.exe");
+        assert_eq!(rules.matches(&event, Some(&dropper)).len(), 1);
+        let shell = identity(
+            "C:\\Users\\a\\AppData\\Local\\Temp\\powershell.exe",
+            "powershell.exe",
+        );
+        assert!(rules.matches(&event, Some(&shell)).is_empty());
+    }
+
+    #[test]
+    fn unsupported_features_are_reported_per_rule() {
+        let source = r#"
+title: Uses base64 modifier
+logsource: {category: process_creation}
+detection:
+  selection:
+    CommandLine|base64: 'IEX'
+  condition: selection
+---
+title: Unknown category
+logsource: {category: file_event}
+detection:
+  selection: {Image: x}
+  condition: selection
+---
+title: Count aggregation
+logsource: {category: process_creation}
+detection:
+  selection: {Image: x}
+  condition: selection | count() > 5
+---
+title: Fine
+logsource: {category: process_creation}
+detection:
+  selection: {Image: '*\x.exe'}
+  condition: 1 of them
+"#;
+        let documents = yaml_documents(source).expect("yaml");
+        let (rules, errors) = compile_documents(documents, "mixed.yml");
+
+        assert_eq!(rules.len(), 1);
+        assert_eq!(errors.len(), 3);
+        assert!(
+            errors[0].contains("unsupported modifier `base64`"),
+            "{}",
+            errors[0]
+        );
+        assert!(errors[1].contains("unsupported logsource category `file_event`"));
+        assert!(errors[2].contains("aggregation"));
+    }
+}
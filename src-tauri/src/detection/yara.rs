@@ -0,0 +1,926 @@
+//! Compiles the subset of the YARA language Nyx can evaluate without a native
+//! engine: text strings (`nocase`, `wide`, `ascii`), hex strings with
+//! wildcards, jumps and alternatives, and conditions built from string
+//! references, `any/all/none/N of`, `filesize` comparisons and boolean
+//! operators. Rules that use anything else are rejected individually and the
+//! rest of the file still loads.
+
+use std::collections::HashSet;
+
+use regex::bytes::Regex;
+
+use crate::models::YaraMatch;
+
+const DEFAULT_WEIGHT: u8 = 50;
+const UNSUPPORTED_MODIFIERS: &[&str] = &["fullword", "xor", "base64", "base64wide", "private"];
+
+pub struct YaraRule {
+    name: String,
+    weight: u8,
+    reason: String,
+    source: String,
+    strings: Vec<(String, Regex)>,
+    condition: Condition,
+}
+
+enum Condition {
+    Bool(bool),
+    String(usize),
+    Not(Box<Condition>),
+    And(Vec<Condition>),
+    Or(Vec<Condition>),
+    Of(Quantifier, Vec<usize>),
+    FileSize(Comparison, u64),
+}
+
+#[derive(Clone, Copy)]
+enum Quantifier {
+    Any,
+    All,
+    None,
+    AtLeast(usize),
+}
+
+#[derive(Clone, Copy)]
+enum Comparison {
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+}
+
+#[derive(Default)]
+pub struct YaraRuleSet {
+    rules: Vec<YaraRule>,
+}
+
+impl YaraRuleSet {
+    pub fn push(&mut self, rule: YaraRule) {
+        self.rules.push(rule);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// `data` may be a prefix of the file; `filesize` is always the real size.
+    pub fn scan(&self, data: &[u8], filesize: u64) -> Vec<YaraMatch> {
+        self.rules
+            .iter()
+            .filter(|rule| rule.matches(data, filesize))
+            .map(|rule| YaraMatch {
+                rule: rule.name.clone(),
+                weight: rule.weight,
+                reason: rule.reason.clone(),
+                source: rule.source.clone(),
+            })
+            .collect()
+    }
+}
+
+impl YaraRule {
+    fn matches(&self, data: &[u8], filesize: u64) -> bool {
+        let hits: Vec<bool> = self
+            .strings
+            .iter()
+            .map(|(_, pattern)| pattern.is_match(data))
+            .collect();
+        self.condition.eval(&hits, filesize)
+    }
+}
+
+impl Condition {
+    fn eval(&self, hits: &[bool], filesize: u64) -> bool {
+        match self {
+            Self::Bool(value) => *value,
+            Self::String(index) => hits[*index],
+            Self::Not(inner) => !inner.eval(hits, filesize),
+            Self::And(items) => items.iter().all(|item| item.eval(hits, filesize)),
+            Self::Or(items) => items.iter().any(|item| item.eval(hits, filesize)),
+            Self::Of(quantifier, strings) => {
+                let count = strings.iter().filter(|index| hits[**index]).count();
+                match quantifier {
+                    Quantifier::Any => count > 0,
+                    Quantifier::All => count == strings.len(),
+                    Quantifier::None => count == 0,
+                    Quantifier::AtLeast(required) => count >= *required,
+                }
+            }
+            Self::FileSize(comparison, value) => match comparison {
+                Comparison::Lt => filesize < *value,
+                Comparison::Le => filesize <= *value,
+                Comparison::Gt => filesize > *value,
+                Comparison::Ge => filesize >= *value,
+                Comparison::Eq => filesize == *value,
+                Comparison::Ne => filesize != *value,
+            },
+        }
+    }
+}
+
+/// Compiles every rule in a `.yar` file. A broken rule is reported and
+/// skipped; parsing resumes at the next line that starts a rule.
+pub fn compile_source(source: &str, source_name: &str) -> (Vec<YaraRule>, Vec<String>) {
+    let mut parser = Parser {
+        src: source.as_bytes(),
+        pos: 0,
+    };
+    let mut rules = Vec::new();
+    let mut errors = Vec::new();
+    let mut names = HashSet::new();
+
+    loop {
+        parser.skip_trivia();
+        if parser.pos >= parser.src.len() {
+            break;
+        }
+        let start = parser.pos;
+        match parser.rule(source_name) {
+            Ok(rule) if !names.insert(rule.name.clone()) => {
+                errors.push(format!(
+                    "{source_name}: duplicate rule `{}` ignored",
+                    rule.name
+                ));
+            }
+            Ok(rule) => rules.push(rule),
+            Err(err) => {
+                errors.push(format!("{source_name}: {err}"));
+                if !parser.recover(start) {
+                    break;
+                }
+            }
+        }
+    }
+    (rules, errors)
+}
+
+struct Parser<'a> {
+    src: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn line(&self) -> usize {
+        self.src[..self.pos.min(self.src.len())]
+            .iter()
+            .filter(|byte| **byte == b'\n')
+            .count()
+            + 1
+    }
+
+    fn error(&self, message: impl std::fmt::Display) -> String {
+        format!("line {}: {message}", self.line())
+    }
+
+    fn skip_trivia(&mut self) {
+        loop {
+            while self.src.get(self.pos).is_some_and(u8::is_ascii_whitespace) {
+                self.pos += 1;
+            }
+            if self.src[self.pos.min(self.src.len())..].starts_with(b"//") {
+                while self.src.get(self.pos).is_some_and(|byte| *byte != b'\n') {
+                    self.pos += 1;
+                }
+            } else if self.src[self.pos.min(self.src.len())..].starts_with(b"/*") {
+                let rest = &self.src[self.pos + 2..];
+                self.pos = match rest.windows(2).position(|window| window == b"*/") {
+                    Some(end) => self.pos + 2 + end + 2,
+                    None => self.src.len(),
+                };
+            } else {
+                return;
+            }
+        }
+    }
+
+    fn peek(&mut self) -> Option<u8> {
+        self.skip_trivia();
+        self.src.get(self.pos).copied()
+    }
+
+    fn eat(&mut self, expected: u8) -> bool {
+        if self.peek() == Some(expected) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, expected: u8) -> Result<(), String> {
+        if self.eat(expected) {
+            Ok(())
+        } else {
+            Err(self.error(format!("expected `{}`", expected as char)))
+        }
+    }
+
+    fn ident(&mut self) -> Option<&'a str> {
+        self.skip_trivia();
+        let start = self.pos;
+        while self
+            .src
+            .get(self.pos)
+            .is_some_and(|byte| byte.is_ascii_alphanumeric() || *byte == b'_')
+        {
+            self.pos += 1;
+        }
+        if start == self.pos || self.src[start].is_ascii_digit() {
+            self.pos = start;
+            return None;
+        }
+        std::str::from_utf8(&self.src[start..self.pos]).ok()
+    }
+
+    fn expect_ident(&mut self) -> Result<&'a str, String> {
+        self.ident()
+            .ok_or_else(|| self.error("expected an identifier"))
+    }
+
+    /// Moves to the next line that begins a rule after `start`.
+    fn recover(&mut self, start: usize) -> bool {
+        let mut cursor = start;
+        while let Some(offset) = self.src[cursor..].iter().position(|byte| *byte == b'\n') {
+            cursor += offset + 1;
+            let line = &self.src[cursor..];
+            let trimmed = &line[line
+                .iter()
+                .take_while(|byte| **byte == b' ' || **byte == b'\t')
+                .count()..];
+            if ["rule ", "private ", "global ", "import ", "include "]
+                .iter()
+                .any(|keyword| trimmed.starts_with(keyword.as_bytes()))
+            {
+                self.pos = cursor;
+                return true;
+            }
+        }
+        false
+    }
+
+    fn rule(&mut self, source_name: &str) -> Result<YaraRule, String> {
+        let keyword = self.expect_ident()?;
+        match keyword {
+            "rule" => {}
+            "private" | "global" => {
+                return Err(self.error(format!("`{keyword}` rules are not supported")))
+            }
+            "import" | "include" => {
+                return Err(self.error(format!("`{keyword}` directives are not supported")))
+            }
+            other => return Err(self.error(format!("expected `rule`, found `{other}`"))),
+        }
+        let name = self.expect_ident()?.to_string();
+        self.rule_body(&name, source_name)
+            .map_err(|err| format!("rule `{name}`, {err}"))
+    }
+
+    fn rule_body(&mut self, name: &str, source_name: &str) -> Result<YaraRule, String> {
+        if self.eat(b':') {
+            while self.peek().is_some_and(|byte| byte != b'{') {
+                self.expect_ident()?;
+            }
+        }
+        self.expect(b'{')?;
+
+        let mut weight = DEFAULT_WEIGHT;
+        let mut description = None;
+        let mut strings = Vec::new();
+        loop {
+            let section = self.expect_ident()?;
+            self.expect(b':')?;
+            match section {
+                "meta" => self.meta(&mut weight, &mut description)?,
+                "strings" => self.strings(&mut strings)?,
+                "condition" => {
+                    let condition = self.condition(&strings)?;
+                    self.expect(b'}')?;
+                    let reason = match description {
+                        Some(text) => format!("YARA rule {name} matched: {text}"),
+                        None => format!("YARA rule {name} matched"),
+                    };
+                    return Ok(YaraRule {
+                        name: name.to_string(),
+                        weight,
+                        reason,
+                        source: source_name.to_string(),
+                        strings,
+                        condition,
+                    });
+                }
+                other => return Err(self.error(format!("unknown section `{other}`"))),
+            }
+        }
+    }
+
+    fn at_section(&mut self) -> bool {
+        let saved = self.pos;
+        let is_section = self.ident().is_some() && self.peek() == Some(b':');
+        self.pos = saved;
+        is_section
+    }
+
+    fn meta(&mut self, weight: &mut u8, description: &mut Option<String>) -> Result<(), String> {
+        while !self.at_section() {
+            let key = self.expect_ident()?;
+            self.expect(b'=')?;
+            let value = match self.peek() {
+                Some(b'"') => String::from_utf8_lossy(&self.quoted()?).to_string(),
+                _ => {
+                    let start = self.pos;
+                    while self
+                        .src
+                        .get(self.pos)
+                        .is_some_and(|byte| byte.is_ascii_alphanumeric() || *byte == b'-')
+                    {
+                        self.pos += 1;
+                    }
+                    if start == self.pos {
+                        return Err(self.error(format!("missing value for meta `{key}`")));
+                    }
+                    String::from_utf8_lossy(&self.src[start..self.pos]).to_string()
+                }
+            };
+            match key {
+                "weight" => {
+                    *weight = value
+                        .parse::<u8>()
+                        .ok()
+                        .filter(|value| *value <= 100)
+                        .ok_or_else(|| self.error("meta `weight` must be 0-100"))?;
+                }
+                "description" => *description = Some(value),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn strings(&mut self, strings: &mut Vec<(String, Regex)>) -> Result<(), String> {
+        while self.peek() == Some(b'$') {
+            self.pos += 1;
+            let label = self.ident().unwrap_or_default();
+            let name = if label.is_empty() {
+                format!("${}", strings.len())
+            } else {
+                format!("${label}")
+            };
+            if !label.is_empty() && strings.iter().any(|(existing, _)| *existing == name) {
+                return Err(self.error(format!("duplicate string `{name}`")));
+            }
+            self.expect(b'=')?;
+
+            let pattern = match self.peek() {
+                Some(b'"') => {
+                    let text = self.quoted()?;
+                    if text.is_empty() {
+                        return Err(self.error(format!("string `{name}` is empty")));
+                    }
+                    let (mut nocase, mut wide, mut ascii) = (false, false, false);
+                    loop {
+                        let saved = self.pos;
+                        match self.ident() {
+                            Some("nocase") => nocase = true,
+                            Some("wide") => wide = true,
+                            Some("ascii") => ascii = true,
+                            Some(word) if UNSUPPORTED_MODIFIERS.contains(&word) => {
+                                return Err(
+                                    self.error(format!("unsupported string modifier `{word}`"))
+                                )
+                            }
+                            _ => {
+                                self.pos = saved;
+                                break;
+                            }
+                        }
+                    }
+                    text_pattern(&text, nocase, wide, ascii)
+                }
+                Some(b'{') => {
+                    self.pos += 1;
+                    let start = self.pos;
+                    let end = self.src[start..]
+                        .iter()
+                        .position(|byte| *byte == b'}')
+                        .ok_or_else(|| self.error("unterminated hex string"))?;
+                    self.pos = start + end + 1;
+                    let body = String::from_utf8_lossy(&self.src[start..start + end]).to_string();
+                    hex_pattern(&body)
+                        .map_err(|err| self.error(format!("string `{name}`: {err}")))?
+                }
+                Some(b'/') => {
+                    return Err(self.error("regular expression strings are not supported"))
+                }
+                _ => return Err(self.error(format!("expected a value for `{name}`"))),
+            };
+            let regex = Regex::new(&format!("(?s-u){pattern}"))
+                .map_err(|err| self.error(format!("string `{name}`: {err}")))?;
+            strings.push((name, regex));
+        }
+        Ok(())
+    }
+
+    fn quoted(&mut self) -> Result<Vec<u8>, String> {
+        self.expect(b'"')?;
+        let mut value = Vec::new();
+        loop {
+            let Some(byte) = self.src.get(self.pos).copied() else {
+                return Err(self.error("unterminated string"));
+            };
+            self.pos += 1;
+            match byte {
+                b'"' => return Ok(value),
+                b'\n' => return Err(self.error("unterminated string")),
+                b'\\' => {
+                    let escaped = self.src.get(self.pos).copied().unwrap_or_default();
+                    self.pos += 1;
+                    value.push(match escaped {
+                        b'n' => b'\n',
+                        b't' => b'\t',
+                        b'r' => b'\r',
+                        b'"' | b'\\' => escaped,
+                        b'x' => {
+                            let digits = self.src.get(self.pos..self.pos + 2).unwrap_or_default();
+                            let parsed = std::str::from_utf8(digits)
+                                .ok()
+                                .and_then(|text| u8::from_str_radix(text, 16).ok())
+                                .ok_or_else(|| self.error("invalid `\\x` escape"))?;
+                            self.pos += 2;
+                            parsed
+                        }
+                        other => {
+                            return Err(self.error(format!("invalid escape `\\{}`", other as char)))
+                        }
+                    });
+                }
+                other => value.push(other),
+            }
+        }
+    }
+
+    fn condition(&mut self, strings: &[(String, Regex)]) -> Result<Condition, String> {
+        let line = self.line();
+        let mut tokens = Vec::new();
+        while let Some(byte) = self.peek() {
+            let start = self.pos;
+            match byte {
+                b'}' => break,
+                b'(' | b')' | b',' => self.pos += 1,
+                b'<' | b'>' | b'=' | b'!' => {
+                    self.pos += 1;
+                    if self.src.get(self.pos) == Some(&b'=') {
+                        self.pos += 1;
+                    }
+                }
+                b'$' | b'#' | b'@' => {
+                    self.pos += 1;
+                    while self.src.get(self.pos).is_some_and(|byte| {
+                        byte.is_ascii_alphanumeric() || matches!(byte, b'_' | b'*')
+                    }) {
+                        self.pos += 1;
+                    }
+                }
+                byte if byte.is_ascii_alphanumeric() || byte == b'_' => {
+                    while self.src.get(self.pos).is_some_and(|byte| {
+                        byte.is_ascii_alphanumeric() || matches!(byte, b'_' | b'.')
+                    }) {
+                        self.pos += 1;
+                    }
+                }
+                other => {
+                    return Err(self.error(format!(
+                        "unsupported condition syntax near `{}`",
+                        other as char
+                    )))
+                }
+            }
+            tokens.push(String::from_utf8_lossy(&self.src[start..self.pos]).to_string());
+        }
+
+        let mut parser = ConditionParser {
+            tokens,
+            pos: 0,
+            strings,
+        };
+        let condition = parser
+            .or_expr()
+            .map_err(|err| format!("line {line}: {err}"))?;
+        if let Some(token) = parser.tokens.get(parser.pos) {
+            return Err(format!(
+                "line {line}: unsupported condition syntax near `{token}`"
+            ));
+        }
+        Ok(condition)
+    }
+}
+
+struct ConditionParser<'a> {
+    tokens: Vec<String>,
+    pos: usize,
+    strings: &'a [(String, Regex)],
+}
+
+impl ConditionParser<'_> {
+    fn peek(&self) -> Option<&str> {
+        self.tokens.get(self.pos).map(String::as_str)
+    }
+
+    fn next(&mut self) -> Result<String, String> {
+        let token = self
+            .tokens
+            .get(self.pos)
+            .cloned()
+            .ok_or("condition ended unexpectedly")?;
+        self.pos += 1;
+        Ok(token)
+    }
+
+    fn or_expr(&mut self) -> Result<Condition, String> {
+        let mut items = vec![self.and_expr()?];
+        while self.peek() == Some("or") {
+            self.pos += 1;
+            items.push(self.and_expr()?);
+        }
+        Ok(if items.len() == 1 {
+            items.remove(0)
+        } else {
+            Condition::Or(items)
+        })
+    }
+
+    fn and_expr(&mut self) -> Result<Condition, String> {
+        let mut items = vec![self.not_expr()?];
+        while self.peek() == Some("and") {
+            self.pos += 1;
+            items.push(self.not_expr()?);
+        }
+        Ok(if items.len() == 1 {
+            items.remove(0)
+        } else {
+            Condition::And(items)
+        })
+    }
+
+    fn not_expr(&mut self) -> Result<Condition, String> {
+        if self.peek() == Some("not") {
+            self.pos += 1;
+            return Ok(Condition::Not(Box::new(self.not_expr()?)));
+        }
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<Condition, String> {
+        let token = self.next()?;
+        let quantifier = match token.as_str() {
+            "(" => {
+                let inner = self.or_expr()?;
+                if self.next()? != ")" {
+                    return Err("missing `)` in condition".to_string());
+                }
+                return Ok(inner);
+            }
+            "true" => return Ok(Condition::Bool(true)),
+            "false" => return Ok(Condition::Bool(false)),
+            "filesize" => {
+                let comparison = match self.next()?.as_str() {
+                    "<" => Comparison::Lt,
+                    "<=" => Comparison::Le,
+                    ">" => Comparison::Gt,
+                    ">=" => Comparison::Ge,
+                    "==" => Comparison::Eq,
+                    "!=" => Comparison::Ne,
+                    other => return Err(format!("unsupported filesize operator `{other}`")),
+                };
+                let size = parse_size(&self.next()?)?;
+                return Ok(Condition::FileSize(comparison, size));
+            }
+            "any" => Quantifier::Any,
+            "all" => Quantifier::All,
+            "none" => Quantifier::None,
+            number
+                if number.starts_with(|ch: char| ch.is_ascii_digit())
+                    && self.peek() == Some("of") =>
+            {
+                Quantifier::AtLeast(parse_size(number)? as usize)
+            }
+            reference if reference.starts_with('$') && !reference.contains('*') => {
+                if matches!(self.peek(), Some("at" | "in")) {
+                    return Err("string offsets (`at`/`in`) are not supported".to_string());
+                }
+                let index = self
+                    .strings
+                    .iter()
+                    .position(|(name, _)| *name == reference)
+                    .ok_or_else(|| format!("undefined string `{reference}`"))?;
+                return Ok(Condition::String(index));
+            }
+            other => return Err(format!("unsupported condition syntax near `{other}`")),
+        };
+
+        if self.next()? != "of" {
+            return Err(format!("expected `of` after `{token}`"));
+        }
+        let strings = self.string_set()?;
+        if let Quantifier::AtLeast(required) = quantifier {
+            if required > strings.len() {
+                return Err(format!(
+                    "`{required} of` exceeds the {} strings in the set",
+                    strings.len()
+                ));
+            }
+        }
+        Ok(Condition::Of(quantifier, strings))
+    }
+
+    fn string_set(&mut self) -> Result<Vec<usize>, String> {
+        let patterns = match self.next()?.as_str() {
+            "them" => vec!["$*".to_string()],
+            "(" => {
+                let mut patterns = vec![self.next()?];
+                loop {
+                    match self.next()?.as_str() {
+                        "," => patterns.push(self.next()?),
+                        ")" => break patterns,
+                        other => return Err(format!("unexpected `{other}` in string set")),
+                    }
+                }
+            }
+            other => return Err(format!("expected `them` or a string set, found `{other}`")),
+        };
+
+        let mut indices = Vec::new();
+        for pattern in patterns {
+            if !pattern.starts_with('$') {
+                return Err(format!("unsupported string set member `{pattern}`"));
+            }
+            let matched: Vec<usize> = self
+                .strings
+                .iter()
+                .enumerate()
+                .filter(|(_, (name, _))| match pattern.strip_suffix('*') {
+                    Some(prefix) => name.starts_with(prefix),
+                    None => *name == pattern,
+                })
+                .map(|(index, _)| index)
+                .collect();
+            if matched.is_empty() {
+                return Err(format!("`{pattern}` matches no string"));
+            }
+            for index in matched {
+                if !indices.contains(&index) {
+                    indices.push(index);
+                }
+            }
+        }
+        Ok(indices)
+    }
+}
+
+fn parse_size(token: &str) -> Result<u64, String> {
+    let (digits, multiplier) = if let Some(value) = token.strip_suffix("KB") {
+        (value, 1024)
+    } else if let Some(value) = token.strip_suffix("MB") {
+        (value, 1024 * 1024)
+    } else {
+        (token, 1)
+    };
+    let value = match digits.strip_prefix("0x") {
+        Some(hex) => u64::from_str_radix(hex, 16),
+        None => digits.parse::<u64>(),
+    };
+    value
+        .ok()
+        .and_then(|value| value.checked_mul(multiplier))
+        .ok_or_else(|| format!("invalid number `{token}`"))
+}
+
+fn text_pattern(text: &[u8], nocase: bool, wide: bool, ascii: bool) -> String {
+    let encode = |wide: bool| {
+        text.iter()
+            .map(|byte| {
+                let mut piece = if nocase && byte.is_ascii_alphabetic() {
+                    format!(
+                        "[\\x{:02x}\\x{:02x}]",
+                        byte.to_ascii_lowercase(),
+                        byte.to_ascii_uppercase()
+                    )
+                } else {
+                    format!("\\x{byte:02x}")
+                };
+                if wide {
+                    piece.push_str("\\x00");
+                }
+                piece
+            })
+            .collect::<String>()
+    };
+    match (wide, ascii) {
+        (true, true) => format!("(?:{}|{})", encode(false), encode(true)),
+        (true, false) => encode(true),
+        (false, _) => encode(false),
+    }
+}
+
+/// Translates a hex string body (`4D 5A ?? [2-4] ( 90 | CC )`) into a byte
+/// regex fragment.
+fn hex_pattern(body: &str) -> Result<String, String> {
+    let chars: Vec<char> = body.chars().filter(|ch| !ch.is_whitespace()).collect();
+    let mut pos = 0;
+    let pattern = hex_sequence(&chars, &mut pos, false)?;
+    if pos != chars.len() {
+        return Err(format!("unexpected `{}` in hex string", chars[pos]));
+    }
+    if pattern.is_empty() {
+        return Err("empty hex string".to_string());
+    }
+    Ok(pattern)
+}
+
+fn hex_sequence(chars: &[char], pos: &mut usize, in_group: bool) -> Result<String, String> {
+    let mut pattern = String::new();
+    while let Some(ch) = chars.get(*pos).copied() {
+        match ch {
+            '|' | ')' if in_group => return Ok(pattern),
+            '[' => {
+                let end = chars[*pos..]
+                    .iter()
+                    .position(|ch| *ch == ']')
+                    .ok_or("unterminated jump in hex string")?;
+                let jump: String = chars[*pos + 1..*pos + end].iter().collect();
+                *pos += end + 1;
+                let bound = |text: &str| {
+                    text.parse::<u32>()
+                        .map_err(|_| format!("invalid jump `[{jump}]`"))
+                };
+                pattern.push_str(&match jump.split_once('-') {
+                    None => format!(".{{{}}}", bound(&jump)?),
+                    Some(("", "")) => ".*".to_string(),
+                    Some((low, "")) => format!(".{{{},}}", bound(low)?),
+                    Some((low, high)) => format!(".{{{},{}}}", bound(low)?, bound(high)?),
+                });
+            }
+            '(' => {
+                *pos += 1;
+                let mut alternatives = vec![hex_sequence(chars, pos, true)?];
+                loop {
+                    match chars.get(*pos) {
+                        Some('|') => {
+                            *pos += 1;
+                            alternatives.push(hex_sequence(chars, pos, true)?);
+                        }
+                        Some(')') => {
+                            *pos += 1;
+                            break;
+                        }
+                        _ => return Err("unterminated alternative in hex string".to_string()),
+                    }
+                }
+                pattern.push_str(&format!("(?:{})", alternatives.join("|")));
+            }
+            '~' => return Err("negated hex bytes are not supported".to_string()),
+            _ => {
+                let high = ch;
+                let low = chars
+                    .get(*pos + 1)
+                    .copied()
+                    .ok_or("odd number of hex digits")?;
+                *pos += 2;
+                pattern.push_str(&hex_byte(high, low)?);
+            }
+        }
+    }
+    if in_group {
+        return Err("unterminated alternative in hex string".to_string());
+    }
+    Ok(pattern)
+}
+
+fn hex_byte(high: char, low: char) -> Result<String, String> {
+    let nibble = |ch: char| match ch {
+        '?' => Ok(None),
+        _ => ch
+            .to_digit(16)
+            .map(Some)
+            .ok_or_else(|| format!("invalid hex digit `{ch}`")),
+    };
+    Ok(match (nibble(high)?, nibble(low)?) {
+        (Some(high), Some(low)) => format!("\\x{:02x}", high * 16 + low),
+        (Some(high), None) => format!("[\\x{:02x}-\\x{:02x}]", high * 16, high * 16 + 15),
+        (None, Some(low)) => {
+            let members: String = (0..16)
+                .map(|high| format!("\\x{:02x}", high * 16 + low))
+                .collect();
+            format!("[{members}]")
+        }
+        (None, None) => ".".to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::compile_source;
+
+    const RULES: &str = r#"
+// Loader stub packed with a well-known marker.
+rule Packed_Loader : loader
+{
+    meta:
+        description = "packed loader stub"
+        weight = 70
+    strings:
+        $mz = { 4D 5A ?? 00 [2-4] ( 90 90 | CC ) }
+        $marker = "EvilLoader" nocase wide ascii
+        $cfg = "cfg\x3d"
+    condition:
+        $mz and ($marker or all of ($cfg*)) and filesize < 1MB
+}
+
+rule Two_Of_Three {
+    strings:
+        $a = "alpha"
+        $b = "bravo"
+        $c = "charlie"
+    condition:
+        2 of them and not $c
+}
+"#;
+
+    #[test]
+    fn text_and_hex_strings_evaluate_with_conditions() {
+        let (rules, errors) = compile_source(RULES, "test.yar");
+        assert!(errors.is_empty(), "{errors:?}");
+        let mut set = super::YaraRuleSet::default();
+        rules.into_iter().for_each(|rule| set.push(rule));
+
+        let mut sample = b"MZ\x90\x00\x01\x02\x03\xCC".to_vec();
+        sample.extend("evilloader".encode_utf16().flat_map(u16::to_le_bytes));
+        sample.extend_from_slice(b" alpha bravo");
+        let matches = set.scan(&sample, sample.len() as u64);
+        let names: Vec<&str> = matches.iter().map(|hit| hit.rule.as_str()).collect();
+        assert_eq!(names, vec!["Packed_Loader", "Two_Of_Three"]);
+        assert_eq!(matches[0].weight, 70);
+        assert_eq!(
+            matches[0].reason,
+            "YARA rule Packed_Loader matched: packed loader stub"
+        );
+        assert_eq!(matches[1].weight, 50);
+
+        assert!(set.scan(&sample, 2 * 1024 * 1024).len() == 1);
+        sample.extend_from_slice(b" charlie");
+        let matches = set.scan(&sample, sample.len() as u64);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].rule, "Packed_Loader");
+        assert!(set.scan(b"MZ\x90\x01\x01\x02\xCCcfg=", 16).is_empty());
+        assert_eq!(set.scan(b"MZ\x90\x00\x01\x02\xCCcfg=", 16).len(), 1);
+    }
+
+    #[test]
+    fn unsupported_rules_are_reported_and_skipped() {
+        let source = r#"
+import "pe"
+
+rule Uses_Module {
+    condition:
+        pe.is_dll()
+}
+
+rule Uses_Regex {
+    strings:
+        $re = /evil[0-9]+/
+    condition:
+        $re
+}
+
+rule Uses_Offset {
+    strings:
+        $mz = "MZ"
+    condition:
+        $mz at 0
+}
+
+rule Fine {
+    strings:
+        $a = "fine" fullword
+    condition:
+        $a
+}
+
+rule Still_Fine {
+    strings:
+        $a = "ok"
+    condition:
+        any of them
+}
+"#;
+        let (rules, errors) = compile_source(source, "mixed.yar");
+        assert_eq!(rules.len(), 1, "{errors:?}");
+        assert_eq!(errors.len(), 5, "{errors:?}");
+        assert!(errors[0].contains("`import` directives"));
+        assert!(errors[1].contains("rule `Uses_Module`") && errors[1].contains("pe.is_dll"));
+        assert!(errors[2].contains("regular expression strings"));
+        assert!(errors[3].contains("`at`/`in`"));
+        assert!(errors[4].contains("unsupported string modifier `fullword`"));
+    }
+}
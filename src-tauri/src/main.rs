@@ -6,16 +6,16 @@ mod models;
 mod monitoring;
 mod response_engine;
 mod storage;
+#[cfg(test)]
+mod test_support;
 
 use anyhow::Context;
-use app_state::RuntimeState;
+use app_state::{RuntimeState, StorePaths};
 use models::{
-    DetectionProfile, EventEnvelope, PerformanceStats, ResponseActionRecord, ResponseActionType,
-    ResponsePolicy, SensorHealth, TrustLevel,
+    Alert, AlertDisposition, AppBaseline, BaselineConfig, DetectionProfile, EventEnvelope,
+    Incident, PerformanceStats, ResponseActionRecord, ResponseActionType, ResponsePolicy,
+    SensorHealth, SuppressionRule, TrustLevel,
 };
-use sha2::{Digest, Sha256};
-use std::fs::File;
-use std::io::{BufReader, Read};
 use std::path::Path;
 use std::process::Command;
 use tauri::{Manager, State};
@@ -46,7 +46,36 @@ fn get_app_usage_history(state: State<'_, RuntimeState>) -> Vec<models::AppUsage
 }
 
 #[tauri::command]
-fn get_active_alerts(state: State<'_, RuntimeState>) -> Vec<models::Alert> {
+fn list_app_baselines(state: State<'_, RuntimeState>) -> Vec<AppBaseline> {
+    state.list_app_baselines()
+}
+
+#[tauri::command]
+fn get_app_baseline(app_key: String, state: State<'_, RuntimeState>) -> Option<AppBaseline> {
+    state.get_app_baseline(&app_key)
+}
+
+#[tauri::command]
+fn reset_app_baseline(app_key: String, state: State<'_, RuntimeState>) -> Result<bool, String> {
+    state
+        .reset_app_baseline(&app_key)
+        .map_err(|err| format!("failed resetting app baseline: {err}"))
+}
+
+#[tauri::command]
+fn get_baseline_config(state: State<'_, RuntimeState>) -> BaselineConfig {
+    state.baseline_config()
+}
+
+#[tauri::command]
+fn set_baseline_config(config: BaselineConfig, state: State<'_, RuntimeState>) -> Result<(), String> {
+    state
+        .set_baseline_config(config)
+        .map_err(|err| format!("failed saving baseline config: {err}"))
+}
+
+#[tauri::command]
+fn get_active_alerts(state: State<'_, RuntimeState>) -> Vec<Alert> {
     state.active_alerts()
 }
 
@@ -103,23 +132,169 @@ fn run_response_action(
         .map_err(|err| format!("failed running response action: {err}"))
 }
 
+#[tauri::command]
+fn get_alert_history(
+    include_archived: Option<bool>,
+    state: State<'_, RuntimeState>,
+) -> Vec<Alert> {
+    state.alert_history(include_archived.unwrap_or(false))
+}
+
+#[tauri::command]
+fn acknowledge_alert(alert_id: String, state: State<'_, RuntimeState>) -> Result<Alert, String> {
+    state
+        .acknowledge_alert(&alert_id)
+        .map_err(|err| format!("failed acknowledging alert: {err}"))
+}
+
+#[tauri::command]
+fn resolve_alert(
+    alert_id: String,
+    disposition: AlertDisposition,
+    note: Option<String>,
+    state: State<'_, RuntimeState>,
+) -> Result<Alert, String> {
+    state
+        .resolve_alert(&alert_id, disposition, note)
+        .map_err(|err| format!("failed resolving alert: {err}"))
+}
+
+#[tauri::command]
+fn reopen_alert(
+    alert_id: String,
+    note: Option<String>,
+    state: State<'_, RuntimeState>,
+) -> Result<Alert, String> {
+    state
+        .reopen_alert(&alert_id, note)
+        .map_err(|err| format!("failed reopening alert: {err}"))
+}
+
+#[tauri::command]
+fn add_alert_note(
+    alert_id: String,
+    note: String,
+    state: State<'_, RuntimeState>,
+) -> Result<Alert, String> {
+    state
+        .add_alert_note(&alert_id, &note)
+        .map_err(|err| format!("failed adding alert note: {err}"))
+}
+
+#[tauri::command]
+fn list_incidents(include_archived: Option<bool>, state: State<'_, RuntimeState>) -> Vec<Incident> {
+    state.list_incidents(include_archived.unwrap_or(false))
+}
+
+#[tauri::command]
+fn get_incident(incident_id: String, state: State<'_, RuntimeState>) -> Option<Incident> {
+    state.incident(&incident_id)
+}
+
+#[tauri::command]
+fn list_suppression_rules(state: State<'_, RuntimeState>) -> Vec<SuppressionRule> {
+    state.list_suppressions()
+}
+
+#[tauri::command]
+fn create_suppression_rule(
+    rule: SuppressionRule,
+    state: State<'_, RuntimeState>,
+) -> Result<SuppressionRule, String> {
+    state
+        .create_suppression(rule)
+        .map_err(|err| format!("failed creating suppression rule: {err}"))
+}
+
+#[tauri::command]
+fn delete_suppression_rule(
+    rule_id: String,
+    state: State<'_, RuntimeState>,
+) -> Result<bool, String> {
+    state
+        .delete_suppression(&rule_id)
+        .map_err(|err| format!("failed deleting suppression rule: {err}"))
+}
+
+/// Archives rather than removes, so the alert stays in history.
 #[tauri::command]
 fn delete_alert(alert_id: String, state: State<'_, RuntimeState>) -> Result<bool, String> {
     state
-        .delete_alert(&alert_id)
-        .map_err(|err| format!("failed deleting alert: {err}"))
+        .archive_alert(&alert_id)
+        .map_err(|err| format!("failed archiving alert: {err}"))
 }
 
 #[tauri::command]
 fn delete_all_alerts(state: State<'_, RuntimeState>) -> Result<usize, String> {
     state
-        .delete_all_active_alerts()
-        .map_err(|err| format!("failed deleting all alerts: {err}"))
+        .archive_open_alerts()
+        .map_err(|err| format!("failed archiving all alerts: {err}"))
+}
+
+#[tauri::command]
+fn list_detection_profiles(state: State<'_, RuntimeState>) -> Vec<DetectionProfile> {
+    state.list_profiles()
 }
 
 #[tauri::command]
-fn set_detection_profile(profile: DetectionProfile, state: State<'_, RuntimeState>) {
-    state.set_profile(profile);
+fn get_active_detection_profile(state: State<'_, RuntimeState>) -> DetectionProfile {
+    state.profile()
+}
+
+#[tauri::command]
+fn create_detection_profile(
+    profile: DetectionProfile,
+    state: State<'_, RuntimeState>,
+) -> Result<DetectionProfile, String> {
+    state
+        .create_profile(profile)
+        .map_err(|err| format!("failed creating detection profile: {err}"))
+}
+
+#[tauri::command]
+fn update_detection_profile(
+    profile: DetectionProfile,
+    state: State<'_, RuntimeState>,
+) -> Result<DetectionProfile, String> {
+    state
+        .update_profile(profile)
+        .map_err(|err| format!("failed updating detection profile: {err}"))
+}
+
+#[tauri::command]
+fn clone_detection_profile(
+    source_id: String,
+    name: String,
+    state: State<'_, RuntimeState>,
+) -> Result<DetectionProfile, String> {
+    state
+        .clone_profile(&source_id, &name)
+        .map_err(|err| format!("failed cloning detection profile: {err}"))
+}
+
+#[tauri::command]
+fn activate_detection_profile(
+    profile_id: String,
+    state: State<'_, RuntimeState>,
+) -> Result<DetectionProfile, String> {
+    state
+        .activate_profile(&profile_id)
+        .map_err(|err| format!("failed activating detection profile: {err}"))
+}
+
+#[tauri::command]
+fn list_sequence_rules(state: State<'_, RuntimeState>) -> Vec<models::SequenceRule> {
+    state.list_sequence_rules()
+}
+
+#[tauri::command]
+fn list_detection_rules(state: State<'_, RuntimeState>) -> Vec<models::DetectionRule> {
+    state.list_detection_rules()
+}
+
+#[tauri::command]
+fn list_sigma_rules(state: State<'_, RuntimeState>) -> Vec<models::SigmaRuleSummary> {
+    state.list_sigma_rules()
 }
 
 #[tauri::command]
@@ -220,40 +395,15 @@ fn open_url_in_browser(url: String) -> Result<bool, String> {
 }
 
 #[tauri::command]
-fn get_file_sha256(path: String) -> Result<Option<String>, String> {
+fn get_file_sha256(path: String, state: State<'_, RuntimeState>) -> Result<Option<String>, String> {
     let normalized = path.trim();
     if normalized.is_empty() {
         return Ok(None);
     }
 
-    let candidate = Path::new(normalized);
-    if !candidate.is_file() {
-        return Ok(None);
-    }
-
-    let hash = compute_sha256(candidate)?;
-    Ok(Some(hash))
-}
-
-fn compute_sha256(path: &Path) -> Result<String, String> {
-    let file = File::open(path)
-        .map_err(|err| format!("failed opening file for hashing {}: {err}", path.display()))?;
-    let mut reader = BufReader::new(file);
-    let mut hasher = Sha256::new();
-    let mut buffer = [0u8; 8192];
-
-    loop {
-        let read = reader
-            .read(&mut buffer)
-            .map_err(|err| format!("failed reading file for hashing {}: {err}", path.display()))?;
-        if read == 0 {
-            break;
-        }
-        hasher.update(&buffer[..read]);
-    }
-
-    let digest = hasher.finalize();
-    Ok(format!("{:x}", digest))
+    let hash = monitoring::hash_worker::hash_file_cached(&state, normalized)?;
+    state.complete_hash_job(normalized, hash.clone());
+    Ok(hash)
 }
 
 fn main() {
@@ -266,12 +416,7 @@ fn main() {
             std::fs::create_dir_all(&data_dir)
                 .with_context(|| format!("failed creating app data dir {}", data_dir.display()))?;
 
-            let state = RuntimeState::new(
-                data_dir.join("alerts.json"),
-                data_dir.join("known_entities.json"),
-                data_dir.join("events.db"),
-                data_dir.join("response_actions.json"),
-            )?;
+            let state = RuntimeState::new(StorePaths::in_dir(&data_dir))?;
             monitoring::start_background_tasks(app.handle().clone(), state.clone());
             app.manage(state);
             Ok(())
@@ -282,6 +427,11 @@ fn main() {
             get_installed_programs,
             get_startup_processes,
             get_app_usage_history,
+            list_app_baselines,
+            get_app_baseline,
+            reset_app_baseline,
+            get_baseline_config,
+            set_baseline_config,
             get_active_alerts,
             get_event_timeline,
             get_sensor_health,
@@ -290,9 +440,27 @@ fn main() {
             set_response_policy,
             get_response_actions,
             run_response_action,
+            get_alert_history,
+            acknowledge_alert,
+            resolve_alert,
+            reopen_alert,
+            add_alert_note,
+            list_incidents,
+            get_incident,
+            list_suppression_rules,
+            create_suppression_rule,
+            delete_suppression_rule,
             delete_alert,
             delete_all_alerts,
-            set_detection_profile,
+            list_detection_profiles,
+            get_active_detection_profile,
+            create_detection_profile,
+            update_detection_profile,
+            clone_detection_profile,
+            activate_detection_profile,
+            list_detection_rules,
+            list_sequence_rules,
+            list_sigma_rules,
             add_known_program,
             set_process_trust_override,
             open_path_in_explorer,
@@ -1,3 +1,5 @@
+use std::collections::{BTreeMap, BTreeSet};
+
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
@@ -46,6 +48,7 @@ impl Default for RiskLevel {
 #[serde(rename_all = "snake_case")]
 pub enum TrustLevel {
     WindowsNative,
+    SystemNative,
     Trusted,
     Unknown,
 }
@@ -62,6 +65,8 @@ pub struct SuspicionAssessment {
     pub score: u8,
     pub reasons: Vec<String>,
     pub confidence: f32,
+    #[serde(default)]
+    pub evidence: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -77,16 +82,83 @@ pub struct ProcessNode {
     pub children: Vec<ProcessNode>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SignatureStatus {
+    Valid,
+    Catalog,
+    DigestMismatch,
+    Malformed,
+    Unsigned,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct SignerDetails {
+    pub subject: String,
+    pub issuer: String,
+    pub thumbprint: String,
+    pub serial_number: String,
+    pub not_before: Option<String>,
+    pub not_after: Option<String>,
+    pub digest_algorithm: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BinarySignature {
+    pub status: SignatureStatus,
+    pub signer: Option<SignerDetails>,
+    /// Set when the embedded image digest matches and the signer chains to an
+    /// embedded issuer, or when Windows resolved a catalog signature.
+    #[serde(default)]
+    pub verified: bool,
+}
+
+impl BinarySignature {
+    /// Only verified signatures count: a self-signed certificate still
+    /// parses as `Valid`.
+    pub fn is_signed(&self) -> bool {
+        self.verified
+            && matches!(self.status, SignatureStatus::Valid | SignatureStatus::Catalog)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct BinaryPrevalence {
+    pub sha256: String,
+    pub path: String,
+    pub first_seen: String,
+    pub last_seen: String,
+    pub run_count: u64,
+    /// Distinct parent process names, lowercased.
+    pub parents: Vec<String>,
+    /// File modification time when the binary was first seen running.
+    pub file_modified: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct ProcessMetric {
     pub pid: u32,
     pub ppid: Option<u32>,
     pub name: String,
     pub exe_path: Option<String>,
+    pub sha256: Option<String>,
+    pub signature: Option<BinarySignature>,
+    #[serde(default)]
+    pub prevalence: Option<BinaryPrevalence>,
+    pub cmdline: Option<String>,
     pub user: Option<String>,
+    pub real_uid: Option<u32>,
+    pub effective_uid: Option<u32>,
+    pub real_gid: Option<u32>,
+    pub effective_gid: Option<u32>,
     pub cpu_pct: f32,
     pub gpu_pct: f32,
+    pub gpu_memory_mb: f32,
     pub memory_mb: f32,
+    pub disk_read_bytes_per_sec: f32,
+    pub disk_write_bytes_per_sec: f32,
+    pub net_sent_bytes_per_sec: f32,
+    pub net_received_bytes_per_sec: f32,
     pub status: String,
     pub started_at: Option<String>,
     pub trust_level: TrustLevel,
@@ -97,17 +169,85 @@ pub struct ProcessMetric {
     pub verdict: ThreatVerdict,
 }
 
+/// Scoring thresholds for `assess_process`, `compute_risk_score` and
+/// `classify_threat_verdict`. The built-in presets are read-only; custom
+/// profiles are stored on disk.
 #[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "snake_case")]
-pub enum DetectionProfile {
-    Conservative,
-    Balanced,
-    Aggressive,
+pub struct DetectionProfile {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub builtin: bool,
+    pub suspicious_threshold: u8,
+    pub unknown_threshold: u8,
+    /// Weight overrides keyed by declarative rule id, or `source:id` for the
+    /// built-in checks (`cmdline:download-cradle`, `yara:<rule>`, `cpu-spike`).
+    /// A weight of zero disables the check.
+    #[serde(default)]
+    pub rule_weights: BTreeMap<String, u8>,
+    pub correlation_cap: u8,
+    #[serde(default)]
+    pub verdict: VerdictThresholds,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerdictThresholds {
+    /// Score at which a process assessed as legitimate is still low risk.
+    pub legitimate_low_risk: u8,
+    pub confirmed_malicious: u8,
+    pub confirmed_min_correlations: usize,
+    pub likely_malicious: u8,
+    pub likely_min_correlations: usize,
+    pub suspicious: u8,
+    pub low_risk: u8,
+}
+
+impl Default for VerdictThresholds {
+    fn default() -> Self {
+        Self {
+            legitimate_low_risk: 55,
+            confirmed_malicious: 95,
+            confirmed_min_correlations: 2,
+            likely_malicious: 86,
+            likely_min_correlations: 1,
+            suspicious: 70,
+            low_risk: 35,
+        }
+    }
+}
+
+impl DetectionProfile {
+    pub const DEFAULT_ID: &'static str = "conservative";
+
+    pub fn builtins() -> Vec<Self> {
+        vec![
+            Self::preset(Self::DEFAULT_ID, "Conservative", 85, 45),
+            Self::preset("balanced", "Balanced", 70, 35),
+            Self::preset("aggressive", "Aggressive", 55, 25),
+        ]
+    }
+
+    fn preset(id: &str, name: &str, suspicious_threshold: u8, unknown_threshold: u8) -> Self {
+        Self {
+            id: id.to_string(),
+            name: name.to_string(),
+            builtin: true,
+            suspicious_threshold,
+            unknown_threshold,
+            rule_weights: BTreeMap::new(),
+            correlation_cap: 22,
+            verdict: VerdictThresholds::default(),
+        }
+    }
+
+    pub fn weight(&self, key: &str, default: u8) -> u8 {
+        self.rule_weights.get(key).copied().unwrap_or(default)
+    }
 }
 
 impl Default for DetectionProfile {
     fn default() -> Self {
-        Self::Conservative
+        Self::builtins().remove(0)
     }
 }
 
@@ -128,6 +268,178 @@ impl Default for CpuSpikeConfig {
     }
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IoSpikeConfig {
+    pub threshold_bytes_per_sec: f32,
+    pub min_consecutive_samples: usize,
+    pub deviation_ratio: f32,
+}
+
+impl Default for IoSpikeConfig {
+    fn default() -> Self {
+        Self {
+            threshold_bytes_per_sec: 50.0 * 1024.0 * 1024.0,
+            min_consecutive_samples: 5,
+            deviation_ratio: 3.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryGrowthConfig {
+    pub window_samples: usize,
+    pub min_slope_mb_per_min: f32,
+    pub min_growth_mb: f32,
+    pub min_increasing_ratio: f32,
+}
+
+impl Default for MemoryGrowthConfig {
+    fn default() -> Self {
+        Self {
+            window_samples: 90,
+            min_slope_mb_per_min: 10.0,
+            min_growth_mb: 64.0,
+            min_increasing_ratio: 0.8,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct MemoryGrowthObservation {
+    pub slope_mb_per_min: f32,
+    pub window_secs: f32,
+    pub series_mb: Vec<f32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct FieldMatcher {
+    #[serde(default)]
+    pub equals: Vec<String>,
+    #[serde(default)]
+    pub contains: Vec<String>,
+    #[serde(default)]
+    pub starts_with: Vec<String>,
+    #[serde(default)]
+    pub ends_with: Vec<String>,
+    #[serde(default)]
+    pub regex: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct RuleConditions {
+    pub name: Option<FieldMatcher>,
+    pub path: Option<FieldMatcher>,
+    pub parent: Option<FieldMatcher>,
+    pub cmdline: Option<FieldMatcher>,
+    pub user: Option<FieldMatcher>,
+    pub signed: Option<bool>,
+    /// Ancestor patterns, oldest first. Each step must match some ancestor,
+    /// in order, but generations may be skipped between steps.
+    #[serde(default)]
+    pub lineage: Vec<LineageStep>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct LineageStep {
+    pub name: Option<FieldMatcher>,
+    pub path: Option<FieldMatcher>,
+    pub cmdline: Option<FieldMatcher>,
+    pub user: Option<FieldMatcher>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DetectionRule {
+    pub id: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default = "default_rule_enabled")]
+    pub enabled: bool,
+    pub weight: u8,
+    pub reason: String,
+    #[serde(rename = "match")]
+    pub conditions: RuleConditions,
+    #[serde(default, skip_deserializing)]
+    pub source: String,
+}
+
+fn default_rule_enabled() -> bool {
+    true
+}
+
+/// Which events may continue a partially matched sequence.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SequenceScope {
+    /// Every step comes from the process that matched the first step.
+    Process,
+    /// Steps come from a process already in the sequence or one of its descendants.
+    #[default]
+    Lineage,
+    /// Any event on the host, whether or not it is attributed to a process.
+    Host,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SequenceStep {
+    /// Event types that satisfy the step; a trailing `*` matches by prefix.
+    pub event_types: Vec<String>,
+    #[serde(default)]
+    pub image: Option<String>,
+    #[serde(default)]
+    pub rule_hit: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SequenceRule {
+    pub id: String,
+    pub title: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default = "default_rule_enabled")]
+    pub enabled: bool,
+    #[serde(default = "default_sequence_window")]
+    pub window_secs: u64,
+    #[serde(default)]
+    pub scope: SequenceScope,
+    /// Risk bonus for the processes in a completed sequence while it is
+    /// inside its window.
+    #[serde(default)]
+    pub bonus: u8,
+    /// Raise a correlated alert with this severity when the sequence completes.
+    #[serde(default)]
+    pub alert: Option<AlertSeverity>,
+    pub steps: Vec<SequenceStep>,
+    #[serde(default, skip_deserializing)]
+    pub source: String,
+}
+
+fn default_sequence_window() -> u64 {
+    300
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct YaraMatch {
+    pub rule: String,
+    pub weight: u8,
+    pub reason: String,
+    pub source: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SigmaRuleSummary {
+    pub id: String,
+    pub title: String,
+    pub level: String,
+    pub category: String,
+    pub source: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InstalledProgram {
     pub name: String,
@@ -148,6 +460,10 @@ pub struct StartupProcess {
     pub location: String,
     pub source: String,
     pub trust_level: TrustLevel,
+    #[serde(default)]
+    pub executable: Option<String>,
+    #[serde(default)]
+    pub yara_matches: Vec<YaraMatch>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -162,6 +478,45 @@ pub struct AppUsageEntry {
     pub last_seen: String,
 }
 
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct ValueRange {
+    pub min: f32,
+    pub max: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct AppBaseline {
+    pub app_key: String,
+    pub name: String,
+    pub first_seen: String,
+    pub last_seen: String,
+    /// Set once the learning period is over; deviations are only scored after that.
+    pub learned_at: Option<String>,
+    pub samples: u64,
+    pub parents: BTreeSet<String>,
+    pub children: BTreeSet<String>,
+    pub remote_hosts: BTreeSet<String>,
+    pub remote_ports: BTreeSet<u16>,
+    pub users: BTreeSet<String>,
+    pub cpu_pct: Option<ValueRange>,
+    pub memory_mb: Option<ValueRange>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BaselineConfig {
+    pub learning_hours: u32,
+    pub min_samples: u64,
+}
+
+impl Default for BaselineConfig {
+    fn default() -> Self {
+        Self {
+            learning_hours: 72,
+            min_samples: 150,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum AlertSeverity {
@@ -175,7 +530,48 @@ pub enum AlertSeverity {
 pub enum AlertStatus {
     Active,
     Acknowledged,
-    Deleted,
+    Resolved,
+    /// Soft-deleted: hidden from the alert list but kept in history.
+    #[serde(alias = "deleted")]
+    Archived,
+}
+
+impl AlertStatus {
+    /// Still waiting for a verdict from the analyst.
+    pub fn is_open(&self) -> bool {
+        matches!(self, Self::Active | Self::Acknowledged)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertDisposition {
+    TruePositive,
+    FalsePositive,
+    Benign,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertAction {
+    Raised,
+    Acknowledged,
+    Resolved,
+    Reopened,
+    NoteAdded,
+    Archived,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertAuditEntry {
+    pub timestamp: String,
+    pub action: AlertAction,
+    pub from_status: Option<AlertStatus>,
+    pub to_status: AlertStatus,
+    #[serde(default)]
+    pub disposition: Option<AlertDisposition>,
+    #[serde(default)]
+    pub note: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -189,6 +585,80 @@ pub struct Alert {
     pub evidence: Vec<String>,
     pub timestamp: String,
     pub status: AlertStatus,
+    /// Ids of the timeline events the alert was derived from.
+    #[serde(default)]
+    pub evidence_refs: Vec<String>,
+    #[serde(default)]
+    pub disposition: Option<AlertDisposition>,
+    /// Every lifecycle transition and note, oldest first.
+    #[serde(default)]
+    pub audit: Vec<AlertAuditEntry>,
+    /// The alerted process as it was when the alert was raised.
+    #[serde(default)]
+    pub process: Option<ProcessIdentity>,
+}
+
+/// Silences matching alerts before they are stored. Every criterion that is
+/// set must match; text comparisons ignore case.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SuppressionRule {
+    #[serde(default)]
+    pub id: String,
+    #[serde(default)]
+    pub alert_type: Option<String>,
+    #[serde(default)]
+    pub process_path: Option<String>,
+    #[serde(default)]
+    pub sha256: Option<String>,
+    /// Image name or full path of the alerted process's parent.
+    #[serde(default)]
+    pub parent: Option<String>,
+    /// Substring of the alert's evidence or description.
+    #[serde(default)]
+    pub evidence_contains: Option<String>,
+    /// RFC 3339; the rule stops matching after this instant.
+    #[serde(default)]
+    pub expires_at: Option<String>,
+    pub reason: String,
+    /// Defaults to the OS account running Nyx when left empty.
+    #[serde(default)]
+    pub author: String,
+    #[serde(default)]
+    pub created_at: String,
+    #[serde(default)]
+    pub suppressed_count: u64,
+    #[serde(default)]
+    pub last_suppressed_at: Option<String>,
+}
+
+/// A process in an incident's affected-process tree. Processes without
+/// alerts of their own only appear when they link two alerted processes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IncidentProcess {
+    pub pid: u32,
+    pub ppid: Option<u32>,
+    pub name: String,
+    pub exe_path: Option<String>,
+    pub sha256: Option<String>,
+    pub alert_ids: Vec<String>,
+    pub children: Vec<IncidentProcess>,
+}
+
+/// Alerts clustered by process lineage, shared binary and time proximity.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Incident {
+    pub id: String,
+    pub title: String,
+    pub severity: AlertSeverity,
+    /// True while any member alert is still active or acknowledged.
+    pub open: bool,
+    pub first_seen: String,
+    pub last_seen: String,
+    /// Oldest first.
+    pub alerts: Vec<Alert>,
+    pub processes: Vec<IncidentProcess>,
+    /// Merged event timeline, oldest first. Only filled by `get_incident`.
+    pub timeline: Vec<EventEnvelope>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -227,8 +697,13 @@ pub struct ProcessIdentity {
     pub ppid: Option<u32>,
     pub image_name: String,
     pub image_path: Option<String>,
+    pub sha256: Option<String>,
     pub cmdline: Option<String>,
     pub user: Option<String>,
+    pub real_uid: Option<u32>,
+    pub effective_uid: Option<u32>,
+    pub real_gid: Option<u32>,
+    pub effective_gid: Option<u32>,
 }
 
 impl ProcessMetric {
@@ -238,8 +713,13 @@ impl ProcessMetric {
             ppid: self.ppid,
             image_name: self.name.clone(),
             image_path: self.exe_path.clone(),
-            cmdline: None,
+            sha256: self.sha256.clone(),
+            cmdline: self.cmdline.clone(),
             user: self.user.clone(),
+            real_uid: self.real_uid,
+            effective_uid: self.effective_uid,
+            real_gid: self.real_gid,
+            effective_gid: self.effective_gid,
         }
     }
 }
@@ -298,6 +778,8 @@ pub struct PerformanceStats {
     pub total_events: u64,
     pub event_store_size: u64,
     pub tracked_processes: usize,
+    pub hash_backlog: usize,
+    pub hash_cache_entries: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -390,7 +872,10 @@ mod tests {
             ppid: Some(101),
             name: "powershell.exe".to_string(),
             exe_path: Some("C:\\Windows\\System32\\WindowsPowerShell\\v1.0\\powershell.exe".to_string()),
+            cmdline: Some("powershell.exe -NoProfile -File run.ps1".to_string()),
             user: Some("ASIER\\user".to_string()),
+            real_uid: Some(1000),
+            effective_uid: Some(0),
             ..ProcessMetric::default()
         };
 
@@ -404,6 +889,11 @@ mod tests {
             Some("C:\\Windows\\System32\\WindowsPowerShell\\v1.0\\powershell.exe")
         );
         assert_eq!(identity.user.as_deref(), Some("ASIER\\user"));
-        assert_eq!(identity.cmdline, None);
+        assert_eq!(
+            identity.cmdline.as_deref(),
+            Some("powershell.exe -NoProfile -File run.ps1")
+        );
+        assert_eq!(identity.real_uid, Some(1000));
+        assert_eq!(identity.effective_uid, Some(0));
     }
 }
@@ -0,0 +1,236 @@
+//! Attributes persistence changes (autorun registry values, Linux persistence
+//! files) to the process most likely responsible. The sensors only see the
+//! change itself, so the writer is inferred: first from the executable the
+//! new value points at, then from an untrusted process that started just
+//! before the change was noticed. Anything less certain stays unattributed.
+
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+use crate::models::{ProcessMetric, TrustLevel};
+
+use super::trust;
+
+/// Long enough to cover a registry poll interval plus a process that wrote
+/// its persistence entry and exited straight away.
+const RECENT_START_WINDOW: Duration = Duration::from_secs(120);
+/// How close before the change a process must have started to count as the writer.
+const WRITER_WINDOW: Duration = Duration::from_secs(30);
+
+/// Processes started in the last couple of minutes, including ones that have
+/// already exited.
+#[derive(Default)]
+pub struct RecentStarts {
+    entries: VecDeque<(Instant, ProcessMetric)>,
+}
+
+impl RecentStarts {
+    pub fn record(&mut self, metric: &ProcessMetric, at: Instant) {
+        self.entries.push_back((at, metric.clone()));
+    }
+
+    pub fn prune(&mut self, now: Instant) {
+        while self
+            .entries
+            .front()
+            .is_some_and(|(at, _)| now.duration_since(*at) > RECENT_START_WINDOW)
+        {
+            self.entries.pop_front();
+        }
+    }
+
+    /// Newest first.
+    fn newest(&self) -> impl Iterator<Item = &(Instant, ProcessMetric)> {
+        self.entries.iter().rev()
+    }
+}
+
+pub struct Attribution {
+    pub process: ProcessMetric,
+    pub method: &'static str,
+    pub detail: String,
+}
+
+pub struct PersistenceAttributor<'a> {
+    pub running: &'a HashMap<u32, &'a ProcessMetric>,
+    pub recent: &'a RecentStarts,
+    pub now: Instant,
+    /// Whether watched values are command lines (registry autoruns) rather
+    /// than file contents.
+    pub values_are_commands: bool,
+}
+
+impl PersistenceAttributor<'_> {
+    pub fn attribute(&self, new_value: Option<&str>) -> Option<Attribution> {
+        new_value
+            .filter(|_| self.values_are_commands)
+            .and_then(|value| self.by_referenced_executable(value))
+            .or_else(|| self.by_writer_timing())
+    }
+
+    /// The new value launches a binary that is running or just ran: the
+    /// classic dropper installing itself.
+    fn by_referenced_executable(&self, value: &str) -> Option<Attribution> {
+        let executable = trust::extract_executable_from_command(&expand_env_vars(value))?;
+        let key = trust::normalize_key(&executable)?;
+        let bare_name = !key.contains('\\');
+        let matches = |metric: &ProcessMetric| {
+            if bare_name {
+                return metric.name.eq_ignore_ascii_case(&key);
+            }
+            metric
+                .exe_path
+                .as_deref()
+                .and_then(trust::normalize_key)
+                .is_some_and(|path| path == key)
+        };
+
+        let recent = self
+            .recent
+            .newest()
+            .map(|(_, metric)| metric)
+            .find(|metric| matches(metric));
+        let process = recent
+            .or_else(|| {
+                self.running
+                    .values()
+                    .copied()
+                    .filter(|metric| matches(metric))
+                    .max_by_key(|metric| metric.pid)
+            })?
+            .clone();
+        Some(Attribution {
+            detail: format!("new value launches {executable}, run by this process"),
+            process,
+            method: "referenced_executable",
+        })
+    }
+
+    /// Falls back to the untrusted process that started shortly before the
+    /// change, but only when it is the sole candidate in that window.
+    fn by_writer_timing(&self) -> Option<Attribution> {
+        let mut started = self
+            .recent
+            .newest()
+            .map(|(at, metric)| (self.now.duration_since(*at), metric))
+            .filter(|(age, metric)| *age <= WRITER_WINDOW && is_candidate_writer(metric));
+        let (age, metric) = started.next()?;
+        if started.next().is_some() {
+            return None;
+        }
+        Some(Attribution {
+            process: metric.clone(),
+            method: "writer_timing",
+            detail: format!("untrusted process started {}s before the change", age.as_secs()),
+        })
+    }
+}
+
+fn is_candidate_writer(metric: &ProcessMetric) -> bool {
+    metric.trust_level == TrustLevel::Unknown
+}
+
+/// Expands `%NAME%` references so `%APPDATA%\x.exe` compares against real paths.
+fn expand_env_vars(value: &str) -> String {
+    let mut expanded = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(start) = rest.find('%') {
+        let Some(len) = rest[start + 1..].find('%') else {
+            break;
+        };
+        let name = &rest[start + 1..start + 1 + len];
+        expanded.push_str(&rest[..start]);
+        match std::env::var(name) {
+            Ok(resolved) if !name.is_empty() => expanded.push_str(&resolved),
+            _ => expanded.push_str(&rest[start..start + len + 2]),
+        }
+        rest = &rest[start + len + 2..];
+    }
+    expanded.push_str(rest);
+    expanded
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::time::{Duration, Instant};
+
+    use super::{PersistenceAttributor, RecentStarts};
+    use crate::models::{ProcessMetric, TrustLevel};
+
+    fn metric(pid: u32, name: &str, path: &str, trust_level: TrustLevel) -> ProcessMetric {
+        ProcessMetric {
+            pid,
+            name: name.to_string(),
+            exe_path: Some(path.to_string()),
+            trust_level,
+            ..ProcessMetric::default()
+        }
+    }
+
+    #[test]
+    fn prefers_the_referenced_executable_then_recent_untrusted_starts() {
+        let now = Instant::now();
+        let dropper = metric(
+            40,
+            "upd.exe",
+            "C:\\Users\\a\\AppData\\Roaming\\upd.exe",
+            TrustLevel::Unknown,
+        );
+        let editor = metric(
+            41,
+            "notepad.exe",
+            "C:\\Windows\\notepad.exe",
+            TrustLevel::WindowsNative,
+        );
+        let mut recent = RecentStarts::default();
+        recent.record(&dropper, now - Duration::from_secs(50));
+        recent.record(&editor, now - Duration::from_secs(5));
+        let running: HashMap<u32, &ProcessMetric> = HashMap::from([(41, &editor)]);
+        let attributor = PersistenceAttributor {
+            running: &running,
+            recent: &recent,
+            now,
+            values_are_commands: true,
+        };
+
+        let found = attributor
+            .attribute(Some("\"C:\\Users\\A\\AppData\\Roaming\\upd.exe\" --silent"))
+            .expect("attributed by executable");
+        assert_eq!(
+            (found.process.pid, found.method),
+            (40, "referenced_executable")
+        );
+
+        assert!(attributor.attribute(Some("C:\\Tools\\other.exe")).is_none());
+
+        let mut late = dropper.clone();
+        late.pid = 42;
+        recent.record(&late, now - Duration::from_secs(10));
+        let attributor = PersistenceAttributor {
+            running: &running,
+            recent: &recent,
+            now,
+            values_are_commands: true,
+        };
+        let found = attributor
+            .attribute(Some("C:\\Tools\\other.exe"))
+            .expect("attributed by timing");
+        assert_eq!((found.process.pid, found.method), (42, "writer_timing"));
+
+        // Two untrusted starts in the window are ambiguous.
+        let mut busy = running.clone();
+        let mut writer = dropper.clone();
+        writer.pid = 43;
+        writer.disk_write_bytes_per_sec = 4096.0;
+        busy.insert(43, &writer);
+        recent.record(&writer, now - Duration::from_secs(3));
+        let attributor = PersistenceAttributor {
+            running: &busy,
+            recent: &recent,
+            now,
+            values_are_commands: true,
+        };
+        assert!(attributor.attribute(Some("C:\\Tools\\other.exe")).is_none());
+    }
+}
@@ -0,0 +1,542 @@
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+use chrono::NaiveDateTime;
+use sha1::Sha1;
+use sha2::digest::DynDigest;
+use sha2::{Digest, Sha256, Sha384, Sha512};
+
+use crate::models::{BinarySignature, SignatureStatus, SignerDetails};
+
+const HEADER_PROBE_BYTES: u64 = 64 * 1024;
+const MAX_CERTIFICATE_TABLE_BYTES: u32 = 16 * 1024 * 1024;
+const SECURITY_DIRECTORY_INDEX: usize = 4;
+const WIN_CERT_TYPE_PKCS_SIGNED_DATA: u16 = 0x0002;
+
+const OID_SIGNED_DATA: &[u8] = &[0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x07, 0x02];
+const OID_SPC_INDIRECT_DATA: &[u8] = &[0x2B, 0x06, 0x01, 0x04, 0x01, 0x82, 0x37, 0x02, 0x01, 0x04];
+const OID_SHA1: &[u8] = &[0x2B, 0x0E, 0x03, 0x02, 0x1A];
+const OID_SHA256: &[u8] = &[0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x01];
+const OID_SHA384: &[u8] = &[0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x02];
+const OID_SHA512: &[u8] = &[0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x03];
+
+const NAME_ATTRIBUTES: &[(&[u8], &str)] = &[
+    (&[0x55, 0x04, 0x03], "CN"),
+    (&[0x55, 0x04, 0x06], "C"),
+    (&[0x55, 0x04, 0x07], "L"),
+    (&[0x55, 0x04, 0x08], "S"),
+    (&[0x55, 0x04, 0x0A], "O"),
+    (&[0x55, 0x04, 0x0B], "OU"),
+    (&[0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x09, 0x01], "E"),
+];
+
+/// Reads the embedded Authenticode signature of a PE file without leaving the
+/// process. Returns `Ok(None)` for files that are not PE images.
+///
+/// The image digest is recomputed and compared against the one stored in the
+/// signature, and the signer certificate is extracted for display. An intact
+/// image whose signer is issued by another certificate embedded in the same
+/// signature is marked `verified`; a lone self-signed signer is not. The
+/// RSA/ECDSA signature over the signed attributes is not checked.
+pub fn inspect_file(path: &Path) -> Result<Option<BinarySignature>, String> {
+    let mut file = File::open(path)
+        .map_err(|err| format!("failed opening {} for signature check: {err}", path.display()))?;
+    inspect(&mut file).map_err(|err| format!("failed reading {}: {err}", path.display()))
+}
+
+fn inspect<R: Read + Seek>(reader: &mut R) -> std::io::Result<Option<BinarySignature>> {
+    let file_len = reader.seek(SeekFrom::End(0))?;
+    reader.seek(SeekFrom::Start(0))?;
+    let mut header = Vec::new();
+    reader
+        .by_ref()
+        .take(HEADER_PROBE_BYTES.min(file_len))
+        .read_to_end(&mut header)?;
+
+    let Some(layout) = parse_pe_layout(&header) else {
+        return Ok(None);
+    };
+    let Some((cert_offset, cert_size)) = layout.certificate_table else {
+        return Ok(Some(status_only(SignatureStatus::Unsigned)));
+    };
+
+    let cert_end = cert_offset as u64 + cert_size as u64;
+    if !(8..=MAX_CERTIFICATE_TABLE_BYTES).contains(&cert_size)
+        || (cert_offset as usize) < layout.certificate_entry_offset + 8
+        || cert_end > file_len
+    {
+        return Ok(Some(status_only(SignatureStatus::Malformed)));
+    }
+
+    let mut table = vec![0u8; cert_size as usize];
+    reader.seek(SeekFrom::Start(cert_offset as u64))?;
+    reader.read_exact(&mut table)?;
+    let Some(pkcs7) = first_pkcs7_certificate(&table) else {
+        return Ok(Some(status_only(SignatureStatus::Malformed)));
+    };
+    let Some(parsed) = parse_signed_data(pkcs7) else {
+        return Ok(Some(status_only(SignatureStatus::Malformed)));
+    };
+    let Some(mut hasher) = digest_for_oid(parsed.digest_oid) else {
+        return Ok(Some(status_only(SignatureStatus::Malformed)));
+    };
+
+    let skipped = [
+        (layout.checksum_offset as u64, 4u64),
+        (layout.certificate_entry_offset as u64, 8u64),
+        (cert_offset as u64, cert_size as u64),
+    ];
+    let mut position = 0u64;
+    for (start, len) in skipped {
+        hash_range(reader, hasher.as_mut(), position, start)?;
+        position = start + len;
+    }
+    hash_range(reader, hasher.as_mut(), position, file_len)?;
+
+    let computed = hasher.finalize();
+    let status = if computed.as_ref() == parsed.expected_digest {
+        SignatureStatus::Valid
+    } else {
+        SignatureStatus::DigestMismatch
+    };
+    Ok(Some(BinarySignature {
+        verified: status == SignatureStatus::Valid && parsed.signer_chain,
+        status,
+        signer: parsed.signer,
+    }))
+}
+
+fn status_only(status: SignatureStatus) -> BinarySignature {
+    BinarySignature {
+        status,
+        signer: None,
+        verified: false,
+    }
+}
+
+struct PeLayout {
+    checksum_offset: usize,
+    certificate_entry_offset: usize,
+    certificate_table: Option<(u32, u32)>,
+}
+
+fn parse_pe_layout(header: &[u8]) -> Option<PeLayout> {
+    if header.get(0..2)? != b"MZ" {
+        return None;
+    }
+    let pe_offset = read_u32(header, 0x3C)? as usize;
+    if header.get(pe_offset..pe_offset + 4)? != b"PE\0\0" {
+        return None;
+    }
+    let optional_offset = pe_offset + 4 + 20;
+    let (directories_offset, count_offset) = match read_u16(header, optional_offset)? {
+        0x10B => (optional_offset + 96, optional_offset + 92),
+        0x20B => (optional_offset + 112, optional_offset + 108),
+        _ => return None,
+    };
+
+    let checksum_offset = optional_offset + 64;
+    let certificate_entry_offset = directories_offset + SECURITY_DIRECTORY_INDEX * 8;
+    let directory_count = read_u32(header, count_offset)? as usize;
+    let certificate_table = if directory_count > SECURITY_DIRECTORY_INDEX {
+        let offset = read_u32(header, certificate_entry_offset)?;
+        let size = read_u32(header, certificate_entry_offset + 4)?;
+        (offset != 0 && size != 0).then_some((offset, size))
+    } else {
+        None
+    };
+
+    Some(PeLayout {
+        checksum_offset,
+        certificate_entry_offset,
+        certificate_table,
+    })
+}
+
+fn first_pkcs7_certificate(table: &[u8]) -> Option<&[u8]> {
+    let length = read_u32(table, 0)? as usize;
+    let certificate_type = read_u16(table, 6)?;
+    if certificate_type != WIN_CERT_TYPE_PKCS_SIGNED_DATA || length < 8 {
+        return None;
+    }
+    table.get(8..length)
+}
+
+fn hash_range<R: Read + Seek>(
+    reader: &mut R,
+    hasher: &mut dyn DynDigest,
+    start: u64,
+    end: u64,
+) -> std::io::Result<()> {
+    if end <= start {
+        return Ok(());
+    }
+    reader.seek(SeekFrom::Start(start))?;
+    let mut remaining = end - start;
+    let mut buffer = [0u8; 64 * 1024];
+    while remaining > 0 {
+        let chunk = remaining.min(buffer.len() as u64) as usize;
+        reader.read_exact(&mut buffer[..chunk])?;
+        hasher.update(&buffer[..chunk]);
+        remaining -= chunk as u64;
+    }
+    Ok(())
+}
+
+fn digest_for_oid(oid: &[u8]) -> Option<Box<dyn DynDigest>> {
+    match oid {
+        OID_SHA1 => Some(Box::new(Sha1::new())),
+        OID_SHA256 => Some(Box::new(Sha256::new())),
+        OID_SHA384 => Some(Box::new(Sha384::new())),
+        OID_SHA512 => Some(Box::new(Sha512::new())),
+        _ => None,
+    }
+}
+
+fn digest_name(oid: &[u8]) -> &'static str {
+    match oid {
+        OID_SHA1 => "sha1",
+        OID_SHA256 => "sha256",
+        OID_SHA384 => "sha384",
+        OID_SHA512 => "sha512",
+        _ => "unknown",
+    }
+}
+
+struct ParsedSignedData<'a> {
+    digest_oid: &'a [u8],
+    expected_digest: &'a [u8],
+    signer: Option<SignerDetails>,
+    signer_chain: bool,
+}
+
+fn parse_signed_data(pkcs7: &[u8]) -> Option<ParsedSignedData<'_>> {
+    let (content_info, _) = Der::read(pkcs7)?;
+    let content_info = content_info.children()?;
+    if content_info.first()?.as_oid()? != OID_SIGNED_DATA {
+        return None;
+    }
+    let signed_data = Der::read(content_info.get(1)?.expect_tag(0xA0)?.body)?.0;
+    let signed_data = signed_data.children()?;
+
+    let encapsulated = signed_data.get(2)?.children()?;
+    if encapsulated.first()?.as_oid()? != OID_SPC_INDIRECT_DATA {
+        return None;
+    }
+    let mut indirect = Der::read(encapsulated.get(1)?.expect_tag(0xA0)?.body)?.0;
+    if indirect.tag == 0x04 {
+        indirect = Der::read(indirect.body)?.0;
+    }
+    let digest_info = indirect.children()?.get(1)?.children()?;
+    let digest_oid = digest_info.first()?.children()?.first()?.as_oid()?;
+    let expected_digest = digest_info.get(1)?.expect_tag(0x04)?.body;
+
+    let certificates = signed_data
+        .iter()
+        .find(|element| element.tag == 0xA0)
+        .and_then(|element| Der::sequence(element.body))
+        .unwrap_or_default();
+    let signer_identity = signed_data
+        .iter()
+        .rev()
+        .find(|element| element.tag == 0x31)
+        .and_then(|set| Der::read(set.body))
+        .and_then(|(signer_info, _)| signer_info.children())
+        .and_then(|fields| fields.get(1)?.children());
+    let identified_signer = signer_identity.as_ref().and_then(|identity| {
+        let issuer = identity.first()?.raw;
+        let serial = identity.get(1)?.body;
+        certificates.iter().find(|certificate| {
+            certificate_issuer_and_serial(certificate)
+                .map(|(cert_issuer, cert_serial)| cert_issuer == issuer && cert_serial == serial)
+                .unwrap_or(false)
+        })
+    });
+    let signer_chain = identified_signer
+        .is_some_and(|certificate| embeds_issuer(certificate, &certificates));
+    let signer_certificate = identified_signer.or_else(|| certificates.first());
+
+    Some(ParsedSignedData {
+        digest_oid,
+        expected_digest,
+        signer: signer_certificate
+            .and_then(|certificate| describe_certificate(certificate, digest_name(digest_oid))),
+        signer_chain,
+    })
+}
+
+/// Roots are normally left out of a signature, so a chain is present once
+/// the signer names a different issuer and that issuer's certificate is
+/// embedded next to it.
+fn embeds_issuer(signer: &Der<'_>, certificates: &[Der<'_>]) -> bool {
+    let Some((issuer, subject)) = certificate_names(signer) else {
+        return false;
+    };
+    issuer != subject
+        && certificates.iter().any(|candidate| {
+            certificate_names(candidate).is_some_and(|(_, subject)| subject == issuer)
+        })
+}
+
+fn tbs_fields<'a>(certificate: &Der<'a>) -> Option<Vec<Der<'a>>> {
+    let tbs = certificate.children()?.into_iter().next()?;
+    let mut fields = tbs.children()?;
+    if fields.first()?.tag == 0xA0 {
+        fields.remove(0);
+    }
+    Some(fields)
+}
+
+fn certificate_issuer_and_serial<'a>(certificate: &Der<'a>) -> Option<(&'a [u8], &'a [u8])> {
+    let fields = tbs_fields(certificate)?;
+    Some((fields.get(2)?.raw, fields.first()?.body))
+}
+
+fn certificate_names<'a>(certificate: &Der<'a>) -> Option<(&'a [u8], &'a [u8])> {
+    let fields = tbs_fields(certificate)?;
+    Some((fields.get(2)?.raw, fields.get(4)?.raw))
+}
+
+fn describe_certificate(certificate: &Der<'_>, digest_algorithm: &str) -> Option<SignerDetails> {
+    let fields = tbs_fields(certificate)?;
+    let serial = fields.first()?.body;
+    let validity = fields.get(3)?.children()?;
+
+    Some(SignerDetails {
+        subject: format_name(fields.get(4)?)?,
+        issuer: format_name(fields.get(2)?)?,
+        thumbprint: to_hex(&Sha1::digest(certificate.raw)),
+        serial_number: to_hex(strip_leading_zeros(serial)),
+        not_before: validity.first().and_then(parse_time),
+        not_after: validity.get(1).and_then(parse_time),
+        digest_algorithm: digest_algorithm.to_string(),
+    })
+}
+
+fn format_name(name: &Der<'_>) -> Option<String> {
+    let mut parts = Vec::new();
+    for relative in name.children()? {
+        for attribute in relative.children()? {
+            let fields = attribute.children()?;
+            let oid = fields.first()?.as_oid()?;
+            let label = NAME_ATTRIBUTES
+                .iter()
+                .find(|(known, _)| *known == oid)
+                .map(|(_, label)| label.to_string())
+                .unwrap_or_else(|| format!("OID.{}", format_oid(oid)));
+            parts.push(format!("{label}={}", decode_string(fields.get(1)?)));
+        }
+    }
+    parts.reverse();
+    Some(parts.join(", "))
+}
+
+fn decode_string(value: &Der<'_>) -> String {
+    match value.tag {
+        0x1E => {
+            let units: Vec<u16> = value
+                .body
+                .chunks_exact(2)
+                .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+                .collect();
+            String::from_utf16_lossy(&units)
+        }
+        _ => String::from_utf8_lossy(value.body).to_string(),
+    }
+}
+
+fn parse_time(value: &Der<'_>) -> Option<String> {
+    let text = std::str::from_utf8(value.body).ok()?;
+    let full = match value.tag {
+        0x17 => {
+            let year: u32 = text.get(0..2)?.parse().ok()?;
+            let century = if year >= 50 { "19" } else { "20" };
+            format!("{century}{text}")
+        }
+        0x18 => text.to_string(),
+        _ => return None,
+    };
+    NaiveDateTime::parse_from_str(&full, "%Y%m%d%H%M%SZ")
+        .ok()
+        .map(|time| time.and_utc().to_rfc3339())
+}
+
+fn format_oid(oid: &[u8]) -> String {
+    let mut arcs = Vec::new();
+    let mut value = 0u64;
+    for byte in oid {
+        value = (value << 7) | u64::from(byte & 0x7F);
+        if byte & 0x80 != 0 {
+            continue;
+        }
+        if arcs.is_empty() {
+            let first = (value / 40).min(2);
+            arcs.push(first);
+            arcs.push(value - first * 40);
+        } else {
+            arcs.push(value);
+        }
+        value = 0;
+    }
+    arcs.iter().map(u64::to_string).collect::<Vec<_>>().join(".")
+}
+
+fn strip_leading_zeros(bytes: &[u8]) -> &[u8] {
+    let start = bytes
+        .iter()
+        .position(|byte| *byte != 0)
+        .unwrap_or(bytes.len().saturating_sub(1));
+    &bytes[start..]
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02X}")).collect()
+}
+
+fn read_u16(bytes: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(bytes.get(offset..offset + 2)?.try_into().ok()?))
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(bytes.get(offset..offset + 4)?.try_into().ok()?))
+}
+
+/// One DER element: `raw` spans header and body, `body` only the contents.
+#[derive(Clone, Copy)]
+struct Der<'a> {
+    tag: u8,
+    body: &'a [u8],
+    raw: &'a [u8],
+}
+
+impl<'a> Der<'a> {
+    fn read(input: &'a [u8]) -> Option<(Der<'a>, &'a [u8])> {
+        let tag = *input.first()?;
+        let first_length = *input.get(1)?;
+        let (length, header_len) = if first_length < 0x80 {
+            (first_length as usize, 2)
+        } else {
+            let count = (first_length & 0x7F) as usize;
+            if count == 0 || count > 4 {
+                return None;
+            }
+            let length = input
+                .get(2..2 + count)?
+                .iter()
+                .fold(0usize, |acc, byte| (acc << 8) | *byte as usize);
+            (length, 2 + count)
+        };
+        let end = header_len.checked_add(length)?;
+        let raw = input.get(..end)?;
+        Some((
+            Der {
+                tag,
+                body: &raw[header_len..],
+                raw,
+            },
+            &input[end..],
+        ))
+    }
+
+    fn sequence(mut input: &'a [u8]) -> Option<Vec<Der<'a>>> {
+        let mut elements = Vec::new();
+        while !input.is_empty() {
+            let (element, rest) = Der::read(input)?;
+            elements.push(element);
+            input = rest;
+        }
+        Some(elements)
+    }
+
+    fn children(&self) -> Option<Vec<Der<'a>>> {
+        if self.tag & 0x20 == 0 {
+            return None;
+        }
+        Der::sequence(self.body)
+    }
+
+    fn expect_tag(&self, tag: u8) -> Option<&Der<'a>> {
+        (self.tag == tag).then_some(self)
+    }
+
+    fn as_oid(&self) -> Option<&'a [u8]> {
+        (self.tag == 0x06).then_some(self.body)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::inspect_file;
+    use crate::models::SignatureStatus;
+
+    fn fixture(name: &str) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/pe")
+            .join(name)
+    }
+
+    #[test]
+    fn signed_fixture_is_valid_and_exposes_signer() {
+        let signature = inspect_file(&fixture("signed.exe"))
+            .expect("inspect")
+            .expect("pe image");
+        let expected_thumbprint =
+            std::fs::read_to_string(fixture("signed.thumbprint")).expect("thumbprint fixture");
+
+        assert_eq!(signature.status, SignatureStatus::Valid);
+        // A self-signed signer carries no chain to vouch for it.
+        assert!(!signature.is_signed());
+        let signer = signature.signer.expect("signer details");
+        assert_eq!(
+            signer.subject,
+            "CN=Nyx Fixture Publisher, O=Nyx Test Signing, C=ES"
+        );
+        assert_eq!(signer.issuer, signer.subject);
+        assert_eq!(signer.thumbprint, expected_thumbprint.trim());
+        assert_eq!(signer.serial_number, "4E595801");
+        assert_eq!(signer.not_before.as_deref(), Some("2024-01-01T00:00:00+00:00"));
+        assert_eq!(signer.not_after.as_deref(), Some("2034-01-01T00:00:00+00:00"));
+        assert_eq!(signer.digest_algorithm, "sha256");
+    }
+
+    #[test]
+    fn signer_issued_by_an_embedded_ca_is_verified() {
+        let signature = inspect_file(&fixture("chained.exe"))
+            .expect("inspect")
+            .expect("pe image");
+
+        assert_eq!(signature.status, SignatureStatus::Valid);
+        assert!(signature.is_signed());
+        let signer = signature.signer.expect("signer details");
+        assert_eq!(
+            signer.subject,
+            "CN=Nyx Chained Publisher, O=Nyx Test Signing, C=ES"
+        );
+        assert_eq!(signer.issuer, "CN=Nyx Fixture CA, O=Nyx Test Signing, C=ES");
+    }
+
+    #[test]
+    fn tampered_image_reports_digest_mismatch() {
+        let signature = inspect_file(&fixture("tampered.exe"))
+            .expect("inspect")
+            .expect("pe image");
+
+        assert_eq!(signature.status, SignatureStatus::DigestMismatch);
+        assert!(!signature.verified);
+        assert!(signature.signer.is_some());
+    }
+
+    #[test]
+    fn unsigned_and_non_pe_files_are_distinguished() {
+        let unsigned = inspect_file(&fixture("unsigned.exe"))
+            .expect("inspect")
+            .expect("pe image");
+        assert_eq!(unsigned.status, SignatureStatus::Unsigned);
+        assert!(unsigned.signer.is_none());
+
+        assert!(inspect_file(&fixture("generate.py")).expect("inspect").is_none());
+    }
+}
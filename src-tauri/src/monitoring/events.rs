@@ -1,14 +1,17 @@
 use std::collections::{HashMap, HashSet};
+use std::net::SocketAddr;
 use std::sync::atomic::{AtomicU64, Ordering};
 
 use chrono::Utc;
 
 use crate::app_state::RuntimeState;
+use crate::detection::rules::RuleSet;
 use crate::models::{
     Alert, AlertSeverity, EventEnvelope, EventSeverity, NetworkEvidence, ProcessMetric,
-    RegistryEvidence, ThreatVerdict,
+    RegistryEvidence, ThreatVerdict, YaraMatch,
 };
 
+use super::attribution::{Attribution, PersistenceAttributor};
 use super::network_collector::NetworkConnection;
 
 pub(super) fn emit_process_lifecycle_events(
@@ -20,12 +23,13 @@ pub(super) fn emit_process_lifecycle_events(
         .iter()
         .map(|metric| (metric.pid, metric))
         .collect();
+    let rules = state.detection_rules();
 
     for metric in current_metrics {
         if previous_metrics.contains_key(&metric.pid) {
             continue;
         }
-        let event = EventEnvelope {
+        let mut event = EventEnvelope {
             event_id: next_event_id("process", "process_started"),
             host_id: state.host_id(),
             timestamp_utc: Utc::now().to_rfc3339(),
@@ -41,6 +45,13 @@ pub(super) fn emit_process_lifecycle_events(
             verdict: Some(metric.verdict.as_str().to_string()),
             evidence_refs: Vec::new(),
         };
+        let parent = metric.ppid.and_then(|ppid| {
+            current_by_pid
+                .get(&ppid)
+                .copied()
+                .or_else(|| previous_metrics.get(&ppid))
+        });
+        apply_sigma_rules(&rules, &mut event, parent);
         let _ = state.push_event(event);
     }
 
@@ -73,9 +84,10 @@ pub(super) fn emit_network_events(
     metrics_by_pid: &HashMap<u32, &ProcessMetric>,
     previous_connections: &mut HashSet<String>,
     connections: Vec<NetworkConnection>,
-) -> HashSet<u32> {
+) -> Vec<(u32, SocketAddr)> {
     let mut current_keys = HashSet::new();
-    let mut emitted_pids = HashSet::new();
+    let mut opened = Vec::new();
+    let rules = state.detection_rules();
 
     for connection in connections {
         let key = connection.key();
@@ -86,11 +98,14 @@ pub(super) fn emit_network_events(
         if !should_emit_network_connection(&connection) {
             continue;
         }
+        if let Some(remote) = connection.remote_address {
+            opened.push((connection.pid, remote));
+        }
 
         let process = metrics_by_pid
             .get(&connection.pid)
             .map(|metric| metric.identity());
-        let event = EventEnvelope {
+        let mut event = EventEnvelope {
             event_id: next_event_id("network", "connection_opened"),
             host_id: state.host_id(),
             timestamp_utc: Utc::now().to_rfc3339(),
@@ -101,14 +116,14 @@ pub(super) fn emit_network_events(
                 "{} {} -> {} (PID {})",
                 connection.protocol.to_uppercase(),
                 connection.local_address,
-                connection.remote_address,
+                connection.remote_display(),
                 connection.pid
             ),
             process,
             network: Some(NetworkEvidence {
+                local_address: connection.local_address.to_string(),
+                remote_address: connection.remote_display(),
                 protocol: connection.protocol,
-                local_address: connection.local_address,
-                remote_address: connection.remote_address,
                 state: connection.state,
                 pid: connection.pid,
             }),
@@ -118,115 +133,134 @@ pub(super) fn emit_network_events(
             verdict: None,
             evidence_refs: Vec::new(),
         };
+        let parent = metrics_by_pid
+            .get(&connection.pid)
+            .and_then(|metric| metric.ppid)
+            .and_then(|ppid| metrics_by_pid.get(&ppid).copied());
+        apply_sigma_rules(&rules, &mut event, parent);
         let _ = state.push_event(event);
-        emitted_pids.insert(connection.pid);
     }
 
     *previous_connections = current_keys;
-    emitted_pids
+    opened
 }
 
-pub(super) fn emit_registry_change_events(
+pub(super) struct SnapshotWatch {
+    pub sensor: &'static str,
+    pub event_prefix: &'static str,
+    pub subject: &'static str,
+    pub rule_hit: &'static str,
+    /// Registry autoruns hold command lines that name the binary they launch;
+    /// persistence files hold arbitrary content.
+    pub values_are_commands: bool,
+    /// Whether entries already present at the first poll are reported as
+    /// added. Persistence files exist on every install, so that watch only
+    /// seeds its baseline.
+    pub report_initial_snapshot: bool,
+}
+
+#[cfg(not(target_os = "linux"))]
+pub(super) const REGISTRY_WATCH: SnapshotWatch = SnapshotWatch {
+    sensor: "registry",
+    event_prefix: "registry_value",
+    subject: "Registry value",
+    rule_hit: "registry_persistence_watch",
+    values_are_commands: true,
+    report_initial_snapshot: true,
+};
+
+#[cfg(target_os = "linux")]
+pub(super) const PERSISTENCE_FILE_WATCH: SnapshotWatch = SnapshotWatch {
+    sensor: "persistence",
+    event_prefix: "persistence_file",
+    subject: "Persistence file",
+    rule_hit: "persistence_file_watch",
+    values_are_commands: false,
+    report_initial_snapshot: false,
+};
+
+pub(super) fn emit_snapshot_change_events(
     state: &RuntimeState,
+    watch: &SnapshotWatch,
+    attributor: &PersistenceAttributor<'_>,
     previous_snapshot: &HashMap<String, String>,
     current_snapshot: &HashMap<String, String>,
-) -> bool {
-    let mut changed = false;
+) {
+    let mut changes = Vec::new();
     for (key, new_value) in current_snapshot {
         match previous_snapshot.get(key) {
-            None => {
-                let (key_path, value_name) = split_registry_composite_key(key);
-                let event = EventEnvelope {
-                    event_id: next_event_id("registry", "registry_value_added"),
-                    host_id: state.host_id(),
-                    timestamp_utc: Utc::now().to_rfc3339(),
-                    event_type: "registry_value_added".to_string(),
-                    sensor: "registry".to_string(),
-                    severity: EventSeverity::Warn,
-                    message: format!("Registry value added: {}", key),
-                    process: None,
-                    network: None,
-                    registry: Some(RegistryEvidence {
-                        key_path,
-                        value_name,
-                        old_value: None,
-                        new_value: Some(new_value.clone()),
-                        operation: "add".to_string(),
-                    }),
-                    rule_hits: vec!["registry_persistence_watch".to_string()],
-                    risk_score: Some(35),
-                    verdict: Some(ThreatVerdict::LowRisk.as_str().to_string()),
-                    evidence_refs: Vec::new(),
-                };
-                let _ = state.push_event(event);
-                changed = true;
-            }
+            None => changes.push((key, None, Some(new_value))),
             Some(old_value) if old_value != new_value => {
-                let (key_path, value_name) = split_registry_composite_key(key);
-                let event = EventEnvelope {
-                    event_id: next_event_id("registry", "registry_value_changed"),
-                    host_id: state.host_id(),
-                    timestamp_utc: Utc::now().to_rfc3339(),
-                    event_type: "registry_value_changed".to_string(),
-                    sensor: "registry".to_string(),
-                    severity: EventSeverity::Warn,
-                    message: format!("Registry value changed: {}", key),
-                    process: None,
-                    network: None,
-                    registry: Some(RegistryEvidence {
-                        key_path,
-                        value_name,
-                        old_value: Some(old_value.clone()),
-                        new_value: Some(new_value.clone()),
-                        operation: "update".to_string(),
-                    }),
-                    rule_hits: vec!["registry_persistence_watch".to_string()],
-                    risk_score: Some(45),
-                    verdict: Some(ThreatVerdict::Suspicious.as_str().to_string()),
-                    evidence_refs: Vec::new(),
-                };
-                let _ = state.push_event(event);
-                changed = true;
+                changes.push((key, Some(old_value), Some(new_value)));
             }
             _ => {}
         }
     }
-
     for (key, old_value) in previous_snapshot {
-        if current_snapshot.contains_key(key) {
-            continue;
+        if !current_snapshot.contains_key(key) {
+            changes.push((key, Some(old_value), None));
         }
-        let (key_path, value_name) = split_registry_composite_key(key);
-        let event = EventEnvelope {
-            event_id: next_event_id("registry", "registry_value_removed"),
-            host_id: state.host_id(),
-            timestamp_utc: Utc::now().to_rfc3339(),
-            event_type: "registry_value_removed".to_string(),
-            sensor: "registry".to_string(),
-            severity: EventSeverity::Warn,
-            message: format!("Registry value removed: {}", key),
-            process: None,
-            network: None,
-            registry: Some(RegistryEvidence {
-                key_path,
-                value_name,
-                old_value: Some(old_value.clone()),
-                new_value: None,
-                operation: "remove".to_string(),
-            }),
-            rule_hits: vec!["registry_persistence_watch".to_string()],
-            risk_score: Some(40),
-            verdict: Some(ThreatVerdict::LowRisk.as_str().to_string()),
-            evidence_refs: Vec::new(),
-        };
-        let _ = state.push_event(event);
-        changed = true;
     }
 
-    changed
+    for (key, old_value, new_value) in changes {
+        let attribution = attributor.attribute(new_value.map(String::as_str));
+        push_snapshot_change_event(state, watch, key, (old_value, new_value), attribution);
+    }
 }
 
-pub(super) fn emit_alert_event(state: &RuntimeState, metric: &ProcessMetric, alert: &Alert) {
+fn push_snapshot_change_event(
+    state: &RuntimeState,
+    watch: &SnapshotWatch,
+    key: &str,
+    (old_value, new_value): (Option<&String>, Option<&String>),
+    attribution: Option<Attribution>,
+) {
+    let (suffix, verb, operation, risk_score, verdict) = match (old_value, new_value) {
+        (None, _) => ("added", "added", "add", 35, ThreatVerdict::LowRisk),
+        (Some(_), Some(_)) => ("changed", "changed", "update", 45, ThreatVerdict::Suspicious),
+        (Some(_), None) => ("removed", "removed", "remove", 40, ThreatVerdict::LowRisk),
+    };
+    let event_type = format!("{}_{}", watch.event_prefix, suffix);
+    let (key_path, value_name) = split_registry_composite_key(key);
+    let mut message = format!("{} {}: {}", watch.subject, verb, key);
+    let mut rule_hits = vec![watch.rule_hit.to_string()];
+    if let Some(attribution) = &attribution {
+        message.push_str(&format!(
+            " (attributed to {} PID {}: {})",
+            attribution.process.name, attribution.process.pid, attribution.detail
+        ));
+        rule_hits.push(format!("attribution_{}", attribution.method));
+    }
+    let event = EventEnvelope {
+        event_id: next_event_id(watch.sensor, &event_type),
+        host_id: state.host_id(),
+        timestamp_utc: Utc::now().to_rfc3339(),
+        event_type,
+        sensor: watch.sensor.to_string(),
+        severity: EventSeverity::Warn,
+        message,
+        process: attribution.map(|attribution| attribution.process.identity()),
+        network: None,
+        registry: Some(RegistryEvidence {
+            key_path,
+            value_name,
+            old_value: old_value.cloned(),
+            new_value: new_value.cloned(),
+            operation: operation.to_string(),
+        }),
+        rule_hits,
+        risk_score: Some(risk_score),
+        verdict: Some(verdict.as_str().to_string()),
+        evidence_refs: Vec::new(),
+    };
+    let _ = state.push_event(event);
+}
+
+pub(super) fn emit_alert_event(
+    state: &RuntimeState,
+    metric: Option<&ProcessMetric>,
+    alert: &Alert,
+) {
     let event = EventEnvelope {
         event_id: next_event_id("detection", "alert_generated"),
         host_id: state.host_id(),
@@ -235,19 +269,71 @@ pub(super) fn emit_alert_event(state: &RuntimeState, metric: &ProcessMetric, ale
         sensor: "detection".to_string(),
         severity: event_severity_from_alert(&alert.severity),
         message: format!("Alert generated: {}", alert.title),
-        process: Some(metric.identity()),
+        process: metric.map(ProcessMetric::identity),
         network: None,
         registry: None,
         rule_hits: alert.evidence.clone(),
-        risk_score: Some(metric.risk_score),
-        verdict: Some(metric.verdict.as_str().to_string()),
-        evidence_refs: Vec::new(),
+        risk_score: metric.map(|metric| metric.risk_score),
+        verdict: metric.map(|metric| metric.verdict.as_str().to_string()),
+        evidence_refs: alert.evidence_refs.clone(),
     };
     let _ = state.push_event(event);
 }
 
+pub(super) fn emit_yara_match_event(
+    state: &RuntimeState,
+    path: &str,
+    sha256: &str,
+    matches: &[YaraMatch],
+) {
+    let names: Vec<&str> = matches.iter().map(|hit| hit.rule.as_str()).collect();
+    let event = EventEnvelope {
+        event_id: next_event_id("yara", "file_yara_match"),
+        host_id: state.host_id(),
+        timestamp_utc: Utc::now().to_rfc3339(),
+        event_type: "file_yara_match".to_string(),
+        sensor: "yara".to_string(),
+        severity: EventSeverity::Critical,
+        message: format!("YARA match in {}: {}", path, names.join(", ")),
+        process: None,
+        network: None,
+        registry: None,
+        rule_hits: names.iter().map(|name| format!("yara:{name}")).collect(),
+        risk_score: Some(
+            matches
+                .iter()
+                .fold(0u8, |total, hit| total.saturating_add(hit.weight)),
+        ),
+        verdict: None,
+        evidence_refs: vec![format!("sha256:{sha256}")],
+    };
+    let _ = state.push_event(event);
+}
+
+/// Records matching Sigma rules as `sigma:<id>` hits and raises the event
+/// severity to the highest matching rule level.
+fn apply_sigma_rules(rules: &RuleSet, event: &mut EventEnvelope, parent: Option<&ProcessMetric>) {
+    let parent = parent.map(ProcessMetric::identity);
+    let hits = rules.sigma().matches(event, parent.as_ref());
+    for rule in hits {
+        event.rule_hits.push(format!("sigma:{}", rule.summary().id));
+        if severity_rank(rule.severity()) > severity_rank(&event.severity) {
+            event.severity = rule.severity().clone();
+        }
+    }
+}
+
+fn severity_rank(severity: &EventSeverity) -> u8 {
+    match severity {
+        EventSeverity::Info => 0,
+        EventSeverity::Warn => 1,
+        EventSeverity::Critical => 2,
+    }
+}
+
 fn split_registry_composite_key(key: &str) -> (String, String) {
-    if let Some((path, value_name)) = key.rsplit_once('\\') {
+    let separator = if key.contains('\\') { '\\' } else { '/' };
+    if let Some((path, value_name)) = key.rsplit_once(separator) {
         return (path.to_string(), value_name.to_string());
     }
     (key.to_string(), String::new())
@@ -261,7 +347,7 @@ fn event_severity_from_alert(severity: &AlertSeverity) -> EventSeverity {
     }
 }
 
-fn should_emit_network_connection(connection: &NetworkConnection) -> bool {
+pub(super) fn should_emit_network_connection(connection: &NetworkConnection) -> bool {
     if connection.protocol.eq_ignore_ascii_case("tcp")
         && connection
             .state
@@ -272,12 +358,10 @@ fn should_emit_network_connection(connection: &NetworkConnection) -> bool {
         return false;
     }
 
-    let remote = connection.remote_address.trim();
-    !(remote.is_empty()
-        || remote == "*:*"
-        || remote.ends_with(":0")
-        || remote.starts_with("0.0.0.0")
-        || remote.starts_with("[::]:"))
+    connection
+        .remote_address
+        .map(|remote| !remote.ip().is_unspecified() && remote.port() != 0)
+        .unwrap_or(false)
 }
 
 fn next_event_id(sensor: &str, event_type: &str) -> String {
@@ -313,12 +397,20 @@ mod tests {
         assert_eq!(value_name, "");
     }
 
+    #[test]
+    fn split_registry_composite_key_handles_file_paths() {
+        let (key_path, value_name) = split_registry_composite_key("/etc/cron.d/backdoor");
+
+        assert_eq!(key_path, "/etc/cron.d");
+        assert_eq!(value_name, "backdoor");
+    }
+
     #[test]
     fn should_emit_network_connection_skips_listening_tcp_rows() {
         let connection = NetworkConnection {
             protocol: "tcp".to_string(),
-            local_address: "127.0.0.1:8080".to_string(),
-            remote_address: "0.0.0.0:0".to_string(),
+            local_address: "127.0.0.1:8080".parse().expect("valid address"),
+            remote_address: Some("0.0.0.0:0".parse().expect("valid address")),
             state: Some("LISTENING".to_string()),
             pid: 1234,
         };
@@ -330,8 +422,8 @@ mod tests {
     fn should_emit_network_connection_keeps_real_outbound_rows() {
         let connection = NetworkConnection {
             protocol: "tcp".to_string(),
-            local_address: "192.168.1.10:51515".to_string(),
-            remote_address: "8.8.8.8:443".to_string(),
+            local_address: "192.168.1.10:51515".parse().expect("valid address"),
+            remote_address: Some("8.8.8.8:443".parse().expect("valid address")),
             state: Some("ESTABLISHED".to_string()),
             pid: 1234,
         };
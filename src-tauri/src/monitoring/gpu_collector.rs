@@ -1,63 +1,374 @@
 use std::collections::HashMap;
+#[cfg(target_os = "linux")]
+use std::path::{Path, PathBuf};
+#[cfg(not(target_os = "linux"))]
 use std::process::Command;
+#[cfg(any(target_os = "linux", test))]
+use std::time::Duration;
+#[cfg(target_os = "linux")]
+use std::time::Instant;
 
+#[cfg(any(not(target_os = "linux"), test))]
 use regex::Regex;
 
-pub fn collect_gpu_usage_by_pid() -> HashMap<u32, f32> {
-    let mut usage_by_pid: HashMap<u32, f32> = HashMap::new();
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct GpuProcessUsage {
+    pub utilization_pct: f32,
+    pub memory_bytes: u64,
+}
+
+pub trait GpuBackend: Send {
+    fn sensor(&self) -> &'static str;
+    fn collect_usage_by_pid(&mut self) -> Result<HashMap<u32, GpuProcessUsage>, String>;
+}
 
-    let script = "$ErrorActionPreference='SilentlyContinue'; Get-Counter '\\GPU Engine(*)\\Utilization Percentage' | Select-Object -ExpandProperty CounterSamples | ForEach-Object { \"{0}|{1}\" -f $_.InstanceName, $_.CookedValue }";
-    let mut command = Command::new("powershell.exe");
-    command.args(["-NoProfile", "-Command", script]);
-    #[cfg(target_os = "windows")]
-    {
-        use std::os::windows::process::CommandExt;
-        command.creation_flags(0x08000000);
+#[cfg(target_os = "linux")]
+pub fn default_backend() -> Box<dyn GpuBackend> {
+    Box::new(DrmFdinfoBackend::new(Path::new(
+        super::procfs::DEFAULT_PROC_ROOT,
+    )))
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn default_backend() -> Box<dyn GpuBackend> {
+    Box::new(WindowsCounterBackend)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub struct WindowsCounterBackend;
+
+#[cfg(not(target_os = "linux"))]
+impl GpuBackend for WindowsCounterBackend {
+    fn sensor(&self) -> &'static str {
+        "gpu_counters"
     }
-    let output = command.output();
 
-    let Ok(output) = output else {
-        return usage_by_pid;
-    };
-    if !output.status.success() {
-        return usage_by_pid;
+    fn collect_usage_by_pid(&mut self) -> Result<HashMap<u32, GpuProcessUsage>, String> {
+        let script = "$ErrorActionPreference='SilentlyContinue'; Get-Counter '\\GPU Engine(*)\\Utilization Percentage','\\GPU Process Memory(*)\\Dedicated Usage' | Select-Object -ExpandProperty CounterSamples | ForEach-Object { \"{0}|{1}\" -f $_.Path, $_.CookedValue }";
+        let mut command = Command::new("powershell.exe");
+        command.args(["-NoProfile", "-Command", script]);
+        #[cfg(target_os = "windows")]
+        {
+            use std::os::windows::process::CommandExt;
+            command.creation_flags(0x08000000);
+        }
+        let output = command
+            .output()
+            .map_err(|err| format!("failed to run Get-Counter: {}", err))?;
+        if !output.status.success() {
+            return Err(format!("Get-Counter exited with {}", output.status));
+        }
+
+        Ok(parse_gpu_counter_output(&String::from_utf8_lossy(
+            &output.stdout,
+        )))
     }
+}
+
+/// Parses `<counter path>|<value>` lines for the `GPU Engine` utilisation and
+/// `GPU Process Memory` dedicated usage counters. Engine instances of the same
+/// process are summed, as the counters report each engine separately.
+#[cfg(any(not(target_os = "linux"), test))]
+pub fn parse_gpu_counter_output(raw: &str) -> HashMap<u32, GpuProcessUsage> {
+    let mut usage_by_pid: HashMap<u32, GpuProcessUsage> = HashMap::new();
 
     let Ok(pid_regex) = Regex::new(r"pid_(\d+)") else {
         return usage_by_pid;
     };
 
-    let raw = String::from_utf8_lossy(&output.stdout);
     for line in raw.lines() {
-        let mut parts = line.split('|');
-        let instance = parts.next().unwrap_or_default();
+        let mut parts = line.rsplitn(2, '|');
         let value = parts
             .next()
             .unwrap_or_default()
             .trim()
-            .parse::<f32>()
+            .parse::<f64>()
             .unwrap_or(0.0);
+        let counter = parts.next().unwrap_or_default().to_lowercase();
 
         if value <= 0.0 {
             continue;
         }
 
         let pid = pid_regex
-            .captures(instance)
+            .captures(&counter)
             .and_then(|caps| caps.get(1))
             .and_then(|m| m.as_str().parse::<u32>().ok());
+        let Some(pid) = pid else {
+            continue;
+        };
+
+        let entry = usage_by_pid.entry(pid).or_default();
+        if counter.contains("gpu process memory") {
+            entry.memory_bytes = entry.memory_bytes.saturating_add(value as u64);
+        } else if counter.contains("gpu engine") {
+            entry.utilization_pct += value as f32;
+        }
+    }
+
+    for usage in usage_by_pid.values_mut() {
+        usage.utilization_pct = usage.utilization_pct.clamp(0.0, 100.0);
+    }
+
+    usage_by_pid
+}
+
+#[cfg(target_os = "linux")]
+pub struct DrmFdinfoBackend {
+    proc_root: PathBuf,
+    previous: Option<(Instant, DrmSnapshot)>,
+}
+
+#[cfg(target_os = "linux")]
+impl DrmFdinfoBackend {
+    pub fn new(proc_root: &Path) -> Self {
+        Self {
+            proc_root: proc_root.to_path_buf(),
+            previous: None,
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl GpuBackend for DrmFdinfoBackend {
+    fn sensor(&self) -> &'static str {
+        "gpu_drm"
+    }
+
+    fn collect_usage_by_pid(&mut self) -> Result<HashMap<u32, GpuProcessUsage>, String> {
+        if !self.proc_root.is_dir() {
+            return Err(format!("{} is not available", self.proc_root.display()));
+        }
+
+        let sampled_at = Instant::now();
+        let snapshot = collect_drm_snapshot(&self.proc_root);
+        let usage = match self.previous.as_ref() {
+            Some((previous_at, previous)) => compute_drm_usage(
+                previous,
+                &snapshot,
+                sampled_at.duration_since(*previous_at),
+            ),
+            None => compute_drm_usage(&snapshot, &snapshot, Duration::ZERO),
+        };
+        self.previous = Some((sampled_at, snapshot));
+        Ok(usage)
+    }
+}
+
+/// Per-client counters read from one `fdinfo` entry. A DRM client can be
+/// reachable through several fds (dup, fork), so samples are keyed by the
+/// owning pid together with `drm-pdev` and `drm-client-id`.
+#[cfg(any(target_os = "linux", test))]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DrmClientSample {
+    pub device: String,
+    pub client_id: u64,
+    pub engine_busy_ns: HashMap<String, u64>,
+    pub engine_capacity: HashMap<String, u64>,
+    pub memory_bytes: u64,
+}
+
+#[cfg(any(target_os = "linux", test))]
+pub type DrmSnapshot = HashMap<(u32, String, u64), DrmClientSample>;
+
+#[cfg(any(target_os = "linux", test))]
+pub fn parse_drm_fdinfo(raw: &str) -> Option<DrmClientSample> {
+    let mut sample = DrmClientSample::default();
+    let mut client_id = None;
+    let mut total_memory = 0u64;
+    let mut legacy_memory = 0u64;
 
-        if let Some(pid) = pid {
-            usage_by_pid
-                .entry(pid)
-                .and_modify(|current| *current += value)
-                .or_insert(value);
+    for line in raw.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let key = key.trim();
+        let value = value.trim();
+
+        if key == "drm-client-id" {
+            client_id = value.parse::<u64>().ok();
+        } else if key == "drm-pdev" {
+            sample.device = value.to_string();
+        } else if let Some(engine) = key.strip_prefix("drm-engine-capacity-") {
+            if let Ok(capacity) = value.parse::<u64>() {
+                sample.engine_capacity.insert(engine.to_string(), capacity);
+            }
+        } else if let Some(engine) = key.strip_prefix("drm-engine-") {
+            if let Some(busy) = value
+                .strip_suffix("ns")
+                .and_then(|number| number.trim().parse::<u64>().ok())
+            {
+                sample.engine_busy_ns.insert(engine.to_string(), busy);
+            }
+        } else if key.starts_with("drm-total-") {
+            total_memory = total_memory.saturating_add(parse_drm_memory(value));
+        } else if key.starts_with("drm-memory-") {
+            legacy_memory = legacy_memory.saturating_add(parse_drm_memory(value));
+        }
+    }
+
+    // `drm-memory-<region>` is the older spelling of `drm-total-<region>`;
+    // drivers that print both would otherwise be counted twice.
+    sample.memory_bytes = if total_memory > 0 {
+        total_memory
+    } else {
+        legacy_memory
+    };
+    sample.client_id = client_id?;
+    Some(sample)
+}
+
+#[cfg(any(target_os = "linux", test))]
+fn parse_drm_memory(value: &str) -> u64 {
+    let mut parts = value.split_whitespace();
+    let amount = parts
+        .next()
+        .and_then(|number| number.parse::<u64>().ok())
+        .unwrap_or(0);
+    let multiplier = match parts.next() {
+        Some("KiB") => 1024,
+        Some("MiB") => 1024 * 1024,
+        Some("GiB") => 1024 * 1024 * 1024,
+        _ => 1,
+    };
+    amount.saturating_mul(multiplier)
+}
+
+#[cfg(target_os = "linux")]
+fn collect_drm_snapshot(proc_root: &Path) -> DrmSnapshot {
+    let mut snapshot = DrmSnapshot::new();
+    for pid in super::procfs::list_pids(proc_root) {
+        let pid_dir = proc_root.join(pid.to_string());
+        let Ok(entries) = std::fs::read_dir(pid_dir.join("fd")) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            // Only render nodes carry DRM counters; skip every other fd
+            // without opening its fdinfo.
+            let is_drm = std::fs::read_link(entry.path())
+                .is_ok_and(|target| target.starts_with("/dev/dri"));
+            if !is_drm {
+                continue;
+            }
+            let Ok(raw) = std::fs::read_to_string(pid_dir.join("fdinfo").join(entry.file_name()))
+            else {
+                continue;
+            };
+            if let Some(sample) = parse_drm_fdinfo(&raw) {
+                snapshot
+                    .entry((pid, sample.device.clone(), sample.client_id))
+                    .or_insert(sample);
+            }
+        }
+    }
+    snapshot
+}
+
+/// Utilisation is the busiest engine's share of the elapsed wall time, scaled
+/// by the engine capacity, so a process saturating one engine reads as 100%.
+/// Memory is reported from the current sample alone.
+#[cfg(any(target_os = "linux", test))]
+pub fn compute_drm_usage(
+    previous: &DrmSnapshot,
+    current: &DrmSnapshot,
+    elapsed: Duration,
+) -> HashMap<u32, GpuProcessUsage> {
+    let elapsed_ns = elapsed.as_nanos() as f64;
+    let mut engine_pct_by_pid: HashMap<(u32, String), f64> = HashMap::new();
+    let mut usage_by_pid: HashMap<u32, GpuProcessUsage> = HashMap::new();
+
+    for (key, sample) in current {
+        let pid = key.0;
+        let usage = usage_by_pid.entry(pid).or_default();
+        usage.memory_bytes = usage.memory_bytes.saturating_add(sample.memory_bytes);
+
+        let Some(before) = previous.get(key) else {
+            continue;
+        };
+        if elapsed_ns <= 0.0 {
+            continue;
+        }
+        for (engine, busy_ns) in &sample.engine_busy_ns {
+            let previous_ns = before.engine_busy_ns.get(engine).copied().unwrap_or(*busy_ns);
+            let capacity = sample.engine_capacity.get(engine).copied().unwrap_or(1).max(1);
+            let pct = busy_ns.saturating_sub(previous_ns) as f64 / elapsed_ns / capacity as f64
+                * 100.0;
+            *engine_pct_by_pid.entry((pid, engine.clone())).or_insert(0.0) += pct;
         }
     }
 
-    for value in usage_by_pid.values_mut() {
-        *value = value.clamp(0.0, 100.0);
+    for ((pid, _), pct) in engine_pct_by_pid {
+        if let Some(usage) = usage_by_pid.get_mut(&pid) {
+            usage.utilization_pct = usage.utilization_pct.max(pct.clamp(0.0, 100.0) as f32);
+        }
     }
 
     usage_by_pid
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{compute_drm_usage, parse_drm_fdinfo, parse_gpu_counter_output, DrmSnapshot};
+
+    const WINDOWS_COUNTERS: &str =
+        include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/gpu/get_counter.txt"));
+    const FDINFO_AMDGPU_T0: &str =
+        include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/gpu/fdinfo_amdgpu_t0.txt"));
+    const FDINFO_AMDGPU_T1: &str =
+        include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/gpu/fdinfo_amdgpu_t1.txt"));
+    const FDINFO_I915: &str =
+        include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/gpu/fdinfo_i915.txt"));
+
+    fn snapshot(pid: u32, raw: &str) -> DrmSnapshot {
+        let sample = parse_drm_fdinfo(raw).expect("drm fdinfo sample");
+        let mut snapshot = DrmSnapshot::new();
+        snapshot.insert((pid, sample.device.clone(), sample.client_id), sample);
+        snapshot
+    }
+
+    #[test]
+    fn parse_gpu_counter_output_sums_engines_and_reads_memory() {
+        let usage = parse_gpu_counter_output(WINDOWS_COUNTERS);
+
+        let game = usage.get(&4312).expect("pid 4312 usage");
+        assert!((game.utilization_pct - 62.5).abs() < 0.01);
+        assert_eq!(game.memory_bytes, 734_003_200);
+
+        let browser = usage.get(&9020).expect("pid 9020 usage");
+        assert!((browser.utilization_pct - 3.25).abs() < 0.01);
+        assert_eq!(browser.memory_bytes, 0);
+
+        assert!(!usage.contains_key(&1180));
+    }
+
+    #[test]
+    fn parse_drm_fdinfo_reads_engines_and_memory() {
+        let amdgpu = parse_drm_fdinfo(FDINFO_AMDGPU_T0).expect("amdgpu sample");
+        assert_eq!(amdgpu.client_id, 42);
+        assert_eq!(amdgpu.device, "0000:03:00.0");
+        assert_eq!(amdgpu.engine_busy_ns.get("gfx"), Some(&1_000_000_000));
+        assert_eq!(amdgpu.memory_bytes, (204_800 + 2_048) * 1024);
+
+        let i915 = parse_drm_fdinfo(FDINFO_I915).expect("i915 sample");
+        assert_eq!(i915.engine_capacity.get("video"), Some(&2));
+        assert_eq!(i915.memory_bytes, 64 * 1024 * 1024);
+
+        assert!(parse_drm_fdinfo("pos:\t0\nflags:\t02100002\n").is_none());
+    }
+
+    #[test]
+    fn compute_drm_usage_uses_deltas_between_ticks() {
+        let first = snapshot(2201, FDINFO_AMDGPU_T0);
+        let second = snapshot(2201, FDINFO_AMDGPU_T1);
+
+        let primed = compute_drm_usage(&first, &first, Duration::ZERO);
+        assert_eq!(primed.get(&2201).map(|usage| usage.utilization_pct), Some(0.0));
+
+        let usage = compute_drm_usage(&first, &second, Duration::from_secs(2));
+        let process = usage.get(&2201).expect("pid 2201 usage");
+        assert!((process.utilization_pct - 75.0).abs() < 0.01);
+        assert_eq!(process.memory_bytes, (307_200 + 2_048) * 1024);
+    }
+}
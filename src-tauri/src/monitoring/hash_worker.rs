@@ -0,0 +1,151 @@
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+use std::time::{Duration, Instant, UNIX_EPOCH};
+
+use sha2::{Digest, Sha256};
+
+use crate::app_state::RuntimeState;
+
+const IDLE_POLL: Duration = Duration::from_millis(500);
+
+/// Hashes queued executables on a dedicated thread so large binaries never
+/// stall the 2-second monitoring loop.
+pub fn spawn(state: RuntimeState) {
+    let health = state.clone();
+    let spawned = std::thread::Builder::new()
+        .name("nyx-hash-worker".to_string())
+        .spawn(move || loop {
+            match state.next_hash_job() {
+                Some(path) => {
+                    let started = Instant::now();
+                    match hash_file_cached(&state, &path) {
+                        Ok(hash) => {
+                            state.complete_hash_job(&path, hash);
+                            state.record_sensor_success(
+                                "hash_worker",
+                                Some(started.elapsed().as_secs_f32() * 1000.0),
+                            );
+                        }
+                        Err(err) => {
+                            state.complete_hash_job(&path, None);
+                            state.record_sensor_error("hash_worker", &err);
+                        }
+                    }
+                }
+                None => std::thread::sleep(IDLE_POLL),
+            }
+        });
+    if let Err(err) = spawned {
+        health.record_sensor_error("hash_worker", &format!("failed to start hash worker: {err}"));
+    }
+}
+
+/// Resolves the SHA-256 of `path`, reusing the persistent cache while the
+/// file size and modification time are unchanged. Returns `None` when the
+/// path is not a regular file (deleted binaries, pseudo paths).
+pub fn hash_file_cached(state: &RuntimeState, path: &str) -> Result<Option<String>, String> {
+    let Some((size, modified_ns)) = file_fingerprint(path) else {
+        return Ok(None);
+    };
+
+    if let Some(hash) = state.cached_file_hash(path, size, modified_ns) {
+        return Ok(Some(hash));
+    }
+
+    let hash = compute_sha256(Path::new(path))?;
+    state
+        .store_file_hash(path, size, modified_ns, &hash)
+        .map_err(|err| format!("failed caching hash for {}: {err}", path))?;
+    Ok(Some(hash))
+}
+
+/// Size and modification time of a regular file; `None` when the path is
+/// missing or not a regular file.
+pub fn file_fingerprint(path: &str) -> Option<(u64, i64)> {
+    let metadata = std::fs::metadata(path).ok()?;
+    if !metadata.is_file() {
+        return None;
+    }
+    let modified_ns = metadata
+        .modified()
+        .ok()
+        .and_then(|value| value.duration_since(UNIX_EPOCH).ok())
+        .map(|value| value.as_nanos() as i64)
+        .unwrap_or(0);
+    Some((metadata.len(), modified_ns))
+}
+
+pub fn compute_sha256(path: &Path) -> Result<String, String> {
+    let file = File::open(path)
+        .map_err(|err| format!("failed opening file for hashing {}: {err}", path.display()))?;
+    let mut reader = BufReader::new(file);
+    let mut hasher = Sha256::new();
+    let mut buffer = [0u8; 8192];
+
+    loop {
+        let read = reader
+            .read(&mut buffer)
+            .map_err(|err| format!("failed reading file for hashing {}: {err}", path.display()))?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+
+    let digest = hasher.finalize();
+    Ok(format!("{:x}", digest))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::hash_file_cached;
+    use crate::app_state::{RuntimeState, StorePaths};
+    use crate::test_support::TempDir;
+
+    #[test]
+    fn hash_file_cached_reuses_cache_until_file_changes() {
+        let dir = TempDir::new("hash-worker");
+        let state = RuntimeState::new(StorePaths::in_dir(&dir)).expect("runtime state");
+
+        let binary = dir.join("tool.bin");
+        fs::write(&binary, b"abc").expect("write binary");
+        let path = binary.to_string_lossy().to_string();
+
+        let first = hash_file_cached(&state, &path).expect("hash");
+        assert_eq!(
+            first.as_deref(),
+            Some("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")
+        );
+        assert_eq!(state.get_performance_stats().hash_cache_entries, 1);
+
+        fs::write(&binary, b"abcd").expect("rewrite binary");
+        let second = hash_file_cached(&state, &path).expect("hash");
+        assert_ne!(first, second);
+        assert_eq!(state.get_performance_stats().hash_cache_entries, 1);
+
+        assert_eq!(
+            hash_file_cached(&state, &dir.join("missing").to_string_lossy()).expect("hash"),
+            None
+        );
+    }
+
+    #[test]
+    fn failed_paths_are_queued_again_only_after_they_change() {
+        let dir = TempDir::new("hash-worker-failed");
+        let state = RuntimeState::new(StorePaths::in_dir(&dir)).expect("runtime state");
+        let path = dir.join("tool.bin").to_string_lossy().to_string();
+
+        assert_eq!(state.file_hash(&path, false), None);
+        assert_eq!(state.next_hash_job().as_deref(), Some(path.as_str()));
+        state.complete_hash_job(&path, None);
+        assert_eq!(state.file_hash(&path, true), None);
+        assert_eq!(state.next_hash_job(), None);
+
+        fs::write(&path, b"abc").expect("write binary");
+        assert_eq!(state.file_hash(&path, false), None);
+        assert_eq!(state.next_hash_job().as_deref(), Some(path.as_str()));
+    }
+}
@@ -1,115 +1,93 @@
+mod attribution;
 mod events;
 
+pub mod authenticode;
 pub mod gpu_collector;
+pub mod hash_worker;
 pub mod network_collector;
+#[cfg(target_os = "linux")]
+pub mod persistence_collector;
 pub mod process_collector;
+#[cfg(target_os = "linux")]
+pub mod procfs;
 pub mod programs;
+#[cfg(not(target_os = "linux"))]
 pub mod registry_collector;
+#[cfg(target_os = "linux")]
+pub mod sock_diag;
 pub mod startup;
 pub mod trust;
+pub mod yara_worker;
 
 use std::collections::{HashMap, HashSet};
+use std::net::SocketAddr;
 use std::time::{Duration, Instant};
 
 use chrono::Utc;
 use tauri::{AppHandle, Emitter};
 
-use crate::app_state::RuntimeState;
-use crate::detection;
+use crate::app_state::{self, RuntimeState};
+use crate::detection::baseline::BaselineObservation;
+use crate::detection::beacon::BeaconTracker;
+use crate::detection::lineage::{self, Ancestor};
+use crate::detection::prevalence;
+use crate::detection::{self, ProcessSignals};
 use crate::models::{
-    Alert, AlertSeverity, AlertStatus, ProcessMetric, ResponseActionRecord,
-    SuspicionAssessment, TrustLevel,
+    Alert, AlertSeverity, AlertStatus, BinarySignature, DetectionProfile, ProcessMetric,
+    ResponseActionRecord, SequenceScope, SuspicionAssessment, TrustLevel,
 };
 
+use self::attribution::{PersistenceAttributor, RecentStarts};
 use self::events::{
     emit_alert_event, emit_network_events, emit_process_lifecycle_events,
-    emit_registry_change_events,
+    emit_snapshot_change_events, should_emit_network_connection, SnapshotWatch,
 };
+use self::gpu_collector::{GpuBackend, GpuProcessUsage};
 
 const GPU_REFRESH_TICKS: u64 = 3;
 const NETWORK_REFRESH_TICKS: u64 = 3;
 const REGISTRY_REFRESH_TICKS: u64 = 5;
+const RULES_REFRESH_TICKS: u64 = 5;
+const BASELINE_FLUSH_TICKS: u64 = 30;
 const INVENTORY_REFRESH_TICKS: u64 = 300;
-const SIGNATURE_PROBE_BUDGET: usize = 16;
-const CORRELATION_WINDOW_SECS: u64 = 300;
-
-#[derive(Default)]
-struct CorrelationState {
-    recent_process_start: HashMap<u32, Instant>,
-    recent_network_activity: HashMap<u32, Instant>,
-    last_registry_change: Option<Instant>,
-}
+/// Catalog lookups spawn PowerShell, so only a few run per tick; embedded
+/// signatures are checked in-process without a budget.
+const CATALOG_LOOKUP_BUDGET: usize = 4;
+/// Host-wide sequences are not tied to a process, so they only raise the
+/// score of untrusted processes that already look suspicious on their own.
+const HOST_SEQUENCE_MIN_SCORE: u8 = 45;
 
 struct CorrelationOutcome {
     bonuses: Vec<u8>,
     reasons: Vec<String>,
-}
-
-impl CorrelationState {
-    fn mark_process_start(&mut self, pid: u32, now: Instant) {
-        self.recent_process_start.insert(pid, now);
-    }
-
-    fn mark_network_activity(&mut self, pid: u32, now: Instant) {
-        self.recent_network_activity.insert(pid, now);
-    }
-
-    fn mark_registry_change(&mut self, now: Instant) {
-        self.last_registry_change = Some(now);
-    }
-
-    fn has_recent_process_start(&self, pid: u32, now: Instant) -> bool {
-        self.recent_process_start
-            .get(&pid)
-            .map(|instant| now.duration_since(*instant).as_secs() <= CORRELATION_WINDOW_SECS)
-            .unwrap_or(false)
-    }
-
-    fn has_recent_network_activity(&self, pid: u32, now: Instant) -> bool {
-        self.recent_network_activity
-            .get(&pid)
-            .map(|instant| now.duration_since(*instant).as_secs() <= CORRELATION_WINDOW_SECS)
-            .unwrap_or(false)
-    }
-
-    fn has_recent_registry_change(&self, now: Instant) -> bool {
-        self.last_registry_change
-            .map(|instant| now.duration_since(instant).as_secs() <= CORRELATION_WINDOW_SECS)
-            .unwrap_or(false)
-    }
-
-    fn prune(&mut self, now: Instant) {
-        self.recent_process_start.retain(|_, instant| {
-            now.duration_since(*instant).as_secs() <= CORRELATION_WINDOW_SECS
-        });
-        self.recent_network_activity.retain(|_, instant| {
-            now.duration_since(*instant).as_secs() <= CORRELATION_WINDOW_SECS
-        });
-        if let Some(last_change) = self.last_registry_change {
-            if now.duration_since(last_change).as_secs() > CORRELATION_WINDOW_SECS {
-                self.last_registry_change = None;
-            }
-        }
-    }
+    evidence_refs: Vec<String>,
 }
 
 pub fn start_background_tasks(app: AppHandle, state: RuntimeState) {
+    state.reload_detection_rules_if_changed();
     refresh_installed_programs(&state);
     refresh_startup_processes(&state);
+    hash_worker::spawn(state.clone());
+    yara_worker::spawn(state.clone());
 
     tauri::async_runtime::spawn(async move {
-        let mut gpu_cache: HashMap<u32, f32> = HashMap::new();
+        let mut gpu_backend = gpu_collector::default_backend();
+        let mut gpu_cache: HashMap<u32, GpuProcessUsage> = HashMap::new();
         let mut tick: u64 = 0;
         let mut previous_metrics: HashMap<u32, ProcessMetric> = HashMap::new();
         let mut previous_connections: HashSet<String> = HashSet::new();
-        let mut previous_registry_values: HashMap<String, String> = HashMap::new();
-        let mut correlation = CorrelationState::default();
+        let mut remotes_by_pid: HashMap<u32, Vec<SocketAddr>> = HashMap::new();
+        let mut previous_registry_values: Option<HashMap<String, String>> = None;
+        let mut beacons = BeaconTracker::default();
+        let mut recent_starts = RecentStarts::default();
 
         loop {
             let loop_started = Instant::now();
-            correlation.prune(loop_started);
+            if tick % RULES_REFRESH_TICKS == 0 {
+                state.reload_detection_rules_if_changed();
+            }
             if tick % GPU_REFRESH_TICKS == 0 {
-                gpu_cache = gpu_collector::collect_gpu_usage_by_pid();
+                refresh_gpu_usage(&state, gpu_backend.as_mut(), &mut gpu_cache);
             }
 
             let mut metrics = collect_process_metrics(&state, &gpu_cache);
@@ -118,32 +96,48 @@ pub fn start_background_tasks(app: AppHandle, state: RuntimeState) {
                 &state,
                 &previous_metrics,
                 &mut metrics,
-                &mut correlation,
+                &remotes_by_pid,
                 loop_started,
             );
 
             if tick > 0 {
                 emit_process_lifecycle_events(&state, &previous_metrics, &metrics);
+                for metric in &metrics {
+                    if !previous_metrics.contains_key(&metric.pid) {
+                        recent_starts.record(metric, loop_started);
+                    }
+                }
             }
+            recent_starts.prune(loop_started);
 
             let metrics_by_pid: HashMap<u32, &ProcessMetric> =
                 metrics.iter().map(|metric| (metric.pid, metric)).collect();
 
-            refresh_network_activity(
+            let opened_connections = refresh_network_activity(
                 &state,
                 &metrics_by_pid,
                 &mut previous_connections,
-                &mut correlation,
+                &mut remotes_by_pid,
                 tick,
             );
+            track_beacons(
+                &app,
+                &state,
+                &metrics_by_pid,
+                &mut beacons,
+                &opened_connections,
+                loop_started,
+            );
             refresh_registry_activity(
                 &state,
+                &metrics_by_pid,
+                &recent_starts,
                 &mut previous_registry_values,
-                &mut correlation,
                 tick,
             );
+            emit_sequence_alerts(&app, &state, &metrics_by_pid);
 
-            state.prune_cpu_history(&live_pids);
+            state.prune_metric_history(&live_pids);
             let tree = process_collector::build_process_tree(&metrics);
             state.update_snapshot(tree, metrics.clone());
             let _ = app.emit("process_snapshot_updated", &metrics);
@@ -153,6 +147,18 @@ pub fn start_background_tasks(app: AppHandle, state: RuntimeState) {
                 .map(|metric| (metric.pid, metric))
                 .collect();
 
+            if tick % BASELINE_FLUSH_TICKS == 0 {
+                if let Err(err) = state.flush_app_baselines() {
+                    state.record_sensor_error("baseline", &err.to_string());
+                }
+                if let Err(err) = state.flush_sequence_state() {
+                    state.record_sensor_error("sequence", &err.to_string());
+                }
+                if let Err(err) = state.flush_suppression_hits() {
+                    state.record_sensor_error("suppression", &err.to_string());
+                }
+            }
+
             if tick % INVENTORY_REFRESH_TICKS == 0 {
                 refresh_installed_programs(&state);
                 refresh_startup_processes(&state);
@@ -165,13 +171,36 @@ pub fn start_background_tasks(app: AppHandle, state: RuntimeState) {
     });
 }
 
-fn collect_process_metrics(state: &RuntimeState, gpu_cache: &HashMap<u32, f32>) -> Vec<ProcessMetric> {
+fn refresh_gpu_usage(
+    state: &RuntimeState,
+    backend: &mut dyn GpuBackend,
+    gpu_cache: &mut HashMap<u32, GpuProcessUsage>,
+) {
+    let started = Instant::now();
+    match backend.collect_usage_by_pid() {
+        Ok(usage) => {
+            state.record_sensor_success(backend.sensor(), Some(elapsed_ms(started.elapsed())));
+            *gpu_cache = usage;
+        }
+        Err(err) => {
+            state.record_sensor_error(backend.sensor(), &err);
+            gpu_cache.clear();
+        }
+    }
+}
+
+fn collect_process_metrics(
+    state: &RuntimeState,
+    gpu_cache: &HashMap<u32, GpuProcessUsage>,
+) -> Vec<ProcessMetric> {
     let process_collect_started = Instant::now();
     let mut metrics = process_collector::collect_process_metrics();
     state.record_sensor_success("process", Some(elapsed_ms(process_collect_started.elapsed())));
 
     for metric in &mut metrics {
-        metric.gpu_pct = *gpu_cache.get(&metric.pid).unwrap_or(&0.0);
+        let usage = gpu_cache.get(&metric.pid).copied().unwrap_or_default();
+        metric.gpu_pct = usage.utilization_pct;
+        metric.gpu_memory_mb = usage.memory_bytes as f32 / 1024.0 / 1024.0;
     }
 
     metrics
@@ -182,34 +211,87 @@ fn enrich_metrics_and_emit_alerts(
     state: &RuntimeState,
     previous_metrics: &HashMap<u32, ProcessMetric>,
     metrics: &mut [ProcessMetric],
-    correlation: &mut CorrelationState,
+    remotes_by_pid: &HashMap<u32, Vec<SocketAddr>>,
     loop_started: Instant,
 ) -> Vec<u32> {
     let profile = state.profile();
-    let parent_names: HashMap<u32, String> =
-        metrics.iter().map(|metric| (metric.pid, metric.name.to_lowercase())).collect();
+    let rules = state.detection_rules();
+    let processes: HashMap<u32, Ancestor> = metrics
+        .iter()
+        .map(|metric| (metric.pid, Ancestor::from(metric)))
+        .collect();
+    let mut children: HashMap<u32, Vec<String>> = HashMap::new();
+    for metric in metrics.iter() {
+        if let Some(ppid) = metric.ppid.filter(|ppid| *ppid != metric.pid) {
+            let names = children.entry(ppid).or_default();
+            if !names.contains(&metric.name) {
+                names.push(metric.name.clone());
+            }
+        }
+    }
     let mut live_pids = Vec::with_capacity(metrics.len());
-    let mut signature_probes = 0usize;
+    let mut catalog_lookups = 0usize;
 
     for metric in metrics {
-        if !previous_metrics.contains_key(&metric.pid) {
-            correlation.mark_process_start(metric.pid, loop_started);
-        }
+        let new_process = !previous_metrics.contains_key(&metric.pid);
         live_pids.push(metric.pid);
-
-        let signed =
-            resolve_binary_signature(state, metric.exe_path.as_deref(), &mut signature_probes);
+        metric.sha256 = metric
+            .exe_path
+            .as_deref()
+            .and_then(|path| state.file_hash(path, new_process));
+
+        metric.signature =
+            resolve_binary_signature(state, metric.exe_path.as_deref(), &mut catalog_lookups);
+        let signed = metric.signature.as_ref().map(BinarySignature::is_signed);
         let cpu_spike = state.update_cpu_and_check_spike(metric.pid, metric.cpu_pct);
-        let parent_name = metric
-            .ppid
-            .and_then(|ppid| parent_names.get(&ppid))
-            .map(String::as_str);
-        let assessment = detection::assess_process(metric, parent_name, signed, cpu_spike, &profile);
+        let io_spike = state.update_io_and_check_spike(
+            metric.pid,
+            metric.disk_read_bytes_per_sec + metric.disk_write_bytes_per_sec,
+        );
+        let memory_growth =
+            state.update_memory_and_check_growth(metric.pid, metric.memory_mb, loop_started);
+        let yara_matches = metric
+            .exe_path
+            .as_deref()
+            .map(|path| state.yara_matches(path, metric.sha256.as_deref()))
+            .unwrap_or_default();
+        let ancestors = lineage::ancestor_chain(metric.ppid, &processes);
+        let observation = BaselineObservation {
+            parent: ancestors.first().map(|parent| parent.name.as_str()),
+            children: children.get(&metric.pid).map(Vec::as_slice).unwrap_or_default(),
+            remotes: remotes_by_pid.get(&metric.pid).map(Vec::as_slice).unwrap_or_default(),
+            user: metric.user.as_deref(),
+            cpu_pct: metric.cpu_pct,
+            memory_mb: metric.memory_mb,
+        };
+        let baseline_deviations = state.observe_app_baseline(metric, &observation);
+        metric.prevalence = state.observe_prevalence(metric, observation.parent);
+        let prevalence_signals = metric
+            .prevalence
+            .as_ref()
+            .map(|entry| prevalence::assess(entry, state.prevalence_since(), Utc::now()))
+            .unwrap_or_default();
+        let signals = ProcessSignals {
+            ancestors: &ancestors,
+            is_signed: signed,
+            cpu_spike,
+            io_spike,
+            yara_matches: &yara_matches,
+            baseline_deviations: &baseline_deviations,
+            prevalence: &prevalence_signals,
+        };
+        let assessment = detection::assess_process(metric, &signals, &profile, &rules);
         let internal_process = apply_metric_trust(state, metric, signed);
         let correlation_outcome =
-            build_correlation_outcome(correlation, metric, &assessment, loop_started);
+            build_correlation_outcome(state, metric, &ancestors, &assessment);
 
-        update_metric_assessment(metric, &assessment, &correlation_outcome, internal_process);
+        update_metric_assessment(
+            metric,
+            &assessment,
+            &correlation_outcome,
+            internal_process,
+            &profile,
+        );
 
         if !internal_process {
             emit_metric_alerts(
@@ -218,8 +300,13 @@ fn enrich_metrics_and_emit_alerts(
                 metric,
                 &assessment,
                 cpu_spike,
-                &correlation_outcome.reasons,
+                io_spike,
+                &correlation_outcome,
             );
+            if let Some(observation) = &memory_growth {
+                let alert = detection::build_memory_growth_alert(metric, observation);
+                emit_new_alert(app, state, metric, alert);
+            }
         }
     }
 
@@ -229,21 +316,34 @@ fn enrich_metrics_and_emit_alerts(
 fn resolve_binary_signature(
     state: &RuntimeState,
     exe_path: Option<&str>,
-    signature_probes: &mut usize,
-) -> Option<bool> {
+    catalog_lookups: &mut usize,
+) -> Option<BinarySignature> {
     let path = exe_path?;
 
     if let Some(cached) = state.get_cached_signature(path) {
-        return Some(cached);
-    }
-    if *signature_probes >= SIGNATURE_PROBE_BUDGET {
-        return None;
+        return cached;
     }
 
-    *signature_probes = signature_probes.saturating_add(1);
-    let discovered = process_collector::is_binary_signed(path);
-    state.put_cached_signature(path.to_string(), discovered);
-    Some(discovered)
+    let mut discovered = match process_collector::probe_binary_signature(path) {
+        Ok(signature) => signature,
+        Err(err) => {
+            state.record_sensor_error("signature", &err);
+            None
+        }
+    };
+    if let Some(found) = discovered
+        .as_mut()
+        .filter(|found| process_collector::needs_catalog_lookup(found))
+    {
+        // Left uncached so the lookup runs on a later tick.
+        if *catalog_lookups >= CATALOG_LOOKUP_BUDGET {
+            return None;
+        }
+        *catalog_lookups = catalog_lookups.saturating_add(1);
+        process_collector::apply_catalog_signature(path, found);
+    }
+    state.put_cached_signature(path.to_string(), discovered.clone());
+    discovered
 }
 
 fn apply_metric_trust(state: &RuntimeState, metric: &mut ProcessMetric, signed: Option<bool>) -> bool {
@@ -267,31 +367,36 @@ fn apply_metric_trust(state: &RuntimeState, metric: &mut ProcessMetric, signed:
 }
 
 fn build_correlation_outcome(
-    correlation: &CorrelationState,
+    state: &RuntimeState,
     metric: &ProcessMetric,
+    ancestors: &[Ancestor],
     assessment: &SuspicionAssessment,
-    loop_started: Instant,
 ) -> CorrelationOutcome {
-    let mut bonuses = Vec::new();
-    let mut reasons = Vec::new();
-
-    if correlation.has_recent_process_start(metric.pid, loop_started) {
-        bonuses.push(4);
-        reasons.push("Process creation observed recently in correlation window".to_string());
-    }
-    if correlation.has_recent_network_activity(metric.pid, loop_started) {
-        bonuses.push(8);
-        reasons.push("New outbound network activity correlated to this process".to_string());
-    }
-    if correlation.has_recent_registry_change(loop_started)
-        && assessment.score >= 45
-        && metric.trust_level == TrustLevel::Unknown
-    {
-        bonuses.push(6);
-        reasons.push("Critical registry persistence change observed recently".to_string());
+    let mut outcome = CorrelationOutcome {
+        bonuses: Vec::new(),
+        reasons: Vec::new(),
+        evidence_refs: Vec::new(),
+    };
+    let mut applied = HashSet::new();
+
+    let ancestry: Vec<u32> = ancestors.iter().map(|ancestor| ancestor.pid).collect();
+    for done in state.active_sequences(metric.pid, &ancestry) {
+        if done.bonus == 0 || applied.contains(&done.rule_id) {
+            continue;
+        }
+        if done.scope == SequenceScope::Host
+            && (assessment.score < HOST_SEQUENCE_MIN_SCORE
+                || metric.trust_level != TrustLevel::Unknown)
+        {
+            continue;
+        }
+        outcome.bonuses.push(done.bonus);
+        outcome.reasons.push(done.title);
+        outcome.evidence_refs.extend(done.event_ids);
+        applied.insert(done.rule_id);
     }
 
-    CorrelationOutcome { bonuses, reasons }
+    outcome
 }
 
 fn update_metric_assessment(
@@ -299,16 +404,21 @@ fn update_metric_assessment(
     assessment: &SuspicionAssessment,
     correlation_outcome: &CorrelationOutcome,
     internal_process: bool,
+    profile: &DetectionProfile,
 ) {
     metric.suspicion = assessment.clone();
-    metric.risk_score =
-        detection::compute_risk_score(metric.suspicion.score, &correlation_outcome.bonuses);
+    metric.risk_score = detection::compute_risk_score(
+        metric.suspicion.score,
+        &correlation_outcome.bonuses,
+        profile.correlation_cap,
+    );
     metric.verdict = detection::classify_threat_verdict(
         metric.risk_score,
         &metric.suspicion.level,
         &metric.trust_level,
         correlation_outcome.reasons.len(),
         internal_process,
+        &profile.verdict,
     );
 
     let mut risk_factors = metric.suspicion.reasons.clone();
@@ -333,9 +443,10 @@ fn emit_metric_alerts(
     metric: &ProcessMetric,
     assessment: &SuspicionAssessment,
     cpu_spike: bool,
-    correlation_reasons: &[String],
+    io_spike: bool,
+    correlation: &CorrelationOutcome,
 ) {
-    if let Some(alert) = detection::build_alert(metric, assessment, cpu_spike) {
+    if let Some(alert) = detection::build_alert(metric, assessment, cpu_spike, io_spike) {
         emit_new_alert(app, state, metric, alert);
     }
 
@@ -343,7 +454,8 @@ fn emit_metric_alerts(
         metric,
         metric.risk_score,
         &metric.verdict,
-        correlation_reasons,
+        &correlation.reasons,
+        &correlation.evidence_refs,
     ) {
         emit_new_alert(app, state, metric, correlated_alert);
     }
@@ -354,9 +466,40 @@ fn emit_metric_alerts(
 }
 
 fn emit_new_alert(app: &AppHandle, state: &RuntimeState, metric: &ProcessMetric, alert: Alert) {
+    let alert = Alert {
+        process: Some(metric.identity()),
+        ..alert
+    };
     if state.add_alert_if_new(alert.clone()).unwrap_or(false) {
         let _ = app.emit("alert_created", &alert);
-        emit_alert_event(state, metric, &alert);
+        emit_alert_event(state, Some(metric), &alert);
+    }
+}
+
+/// Raises the alerts of event sequences completed since the last tick. The
+/// first process in a sequence may already have exited.
+fn emit_sequence_alerts(
+    app: &AppHandle,
+    state: &RuntimeState,
+    metrics_by_pid: &HashMap<u32, &ProcessMetric>,
+) {
+    for done in state.take_sequence_alerts() {
+        let Some(severity) = done.alert.clone() else {
+            continue;
+        };
+        let metric = done
+            .pids
+            .first()
+            .and_then(|pid| metrics_by_pid.get(pid).copied());
+        let name = metric.map(|metric| metric.name.as_str());
+        let alert = Alert {
+            process: metric.map(ProcessMetric::identity),
+            ..detection::build_sequence_alert(&done, severity, name)
+        };
+        if state.add_alert_if_new(alert.clone()).unwrap_or(false) {
+            let _ = app.emit("alert_created", &alert);
+            emit_alert_event(state, metric, &alert);
+        }
     }
 }
 
@@ -386,6 +529,10 @@ fn build_response_action_alert(response_record: &ResponseActionRecord) -> Alert
         ],
         timestamp: Utc::now().to_rfc3339(),
         status: AlertStatus::Active,
+        disposition: None,
+        audit: Vec::new(),
+        process: None,
+        evidence_refs: Vec::new(),
     }
 }
 
@@ -393,31 +540,70 @@ fn refresh_network_activity(
     state: &RuntimeState,
     metrics_by_pid: &HashMap<u32, &ProcessMetric>,
     previous_connections: &mut HashSet<String>,
-    correlation: &mut CorrelationState,
+    remotes_by_pid: &mut HashMap<u32, Vec<SocketAddr>>,
     tick: u64,
-) {
+) -> Vec<(u32, SocketAddr)> {
     if tick % NETWORK_REFRESH_TICKS != 0 {
-        return;
+        return Vec::new();
     }
 
     let started = Instant::now();
     match network_collector::collect_connections() {
         Ok(connections) => {
             state.record_sensor_success("network", Some(elapsed_ms(started.elapsed())));
-            let network_pids =
-                emit_network_events(state, metrics_by_pid, previous_connections, connections);
-            for pid in network_pids {
-                correlation.mark_network_activity(pid, Instant::now());
+            remotes_by_pid.clear();
+            let outbound = connections
+                .iter()
+                .filter(|connection| should_emit_network_connection(connection));
+            for connection in outbound {
+                let remote = connection.remote_address.filter(|addr| !addr.ip().is_loopback());
+                if let Some(remote) = remote {
+                    remotes_by_pid.entry(connection.pid).or_default().push(remote);
+                }
             }
+            emit_network_events(state, metrics_by_pid, previous_connections, connections)
+        }
+        Err(err) => {
+            state.record_sensor_error("network", &err);
+            Vec::new()
+        }
+    }
+}
+
+/// Feeds newly opened connections of untrusted processes to the beacon
+/// tracker and raises a `beaconing` alert when one settles into a schedule.
+fn track_beacons(
+    app: &AppHandle,
+    state: &RuntimeState,
+    metrics_by_pid: &HashMap<u32, &ProcessMetric>,
+    beacons: &mut BeaconTracker,
+    opened: &[(u32, SocketAddr)],
+    now: Instant,
+) {
+    if opened.is_empty() {
+        return;
+    }
+    beacons.prune(now);
+    for (pid, remote) in opened {
+        let Some(metric) = metrics_by_pid.get(pid).copied() else {
+            continue;
+        };
+        if metric.trust_level != TrustLevel::Unknown || remote.ip().is_loopback() {
+            continue;
+        }
+        let app_key = app_state::app_key(metric);
+        if let Some(stats) = beacons.record(&app_key, &remote.to_string(), now) {
+            let alert = detection::build_beaconing_alert(metric, &stats);
+            emit_new_alert(app, state, metric, alert);
         }
-        Err(err) => state.record_sensor_error("network", &err),
     }
 }
 
 fn refresh_registry_activity(
     state: &RuntimeState,
-    previous_registry_values: &mut HashMap<String, String>,
-    correlation: &mut CorrelationState,
+    metrics_by_pid: &HashMap<u32, &ProcessMetric>,
+    recent_starts: &RecentStarts,
+    previous_registry_values: &mut Option<HashMap<String, String>>,
     tick: u64,
 ) {
     if tick % REGISTRY_REFRESH_TICKS != 0 {
@@ -425,18 +611,47 @@ fn refresh_registry_activity(
     }
 
     let started = Instant::now();
-    match registry_collector::snapshot_critical_values() {
+    let (watch, result) = collect_persistence_snapshot();
+    match result {
         Ok(snapshot) => {
-            state.record_sensor_success("registry", Some(elapsed_ms(started.elapsed())));
-            if emit_registry_change_events(state, previous_registry_values, &snapshot) {
-                correlation.mark_registry_change(Instant::now());
+            state.record_sensor_success(watch.sensor, Some(elapsed_ms(started.elapsed())));
+            let empty = HashMap::new();
+            let previous = match previous_registry_values.as_ref() {
+                Some(previous) => Some(previous),
+                None if watch.report_initial_snapshot => Some(&empty),
+                None => None,
+            };
+            if let Some(previous) = previous {
+                let attributor = PersistenceAttributor {
+                    running: metrics_by_pid,
+                    recent: recent_starts,
+                    now: Instant::now(),
+                    values_are_commands: watch.values_are_commands,
+                };
+                emit_snapshot_change_events(state, watch, &attributor, previous, &snapshot);
             }
-            *previous_registry_values = snapshot;
+            *previous_registry_values = Some(snapshot);
         }
-        Err(err) => state.record_sensor_error("registry", &err),
+        Err(err) => state.record_sensor_error(watch.sensor, &err),
     }
 }
 
+#[cfg(target_os = "linux")]
+fn collect_persistence_snapshot() -> (&'static SnapshotWatch, Result<HashMap<String, String>, String>) {
+    (
+        &events::PERSISTENCE_FILE_WATCH,
+        persistence_collector::snapshot_persistence_files(std::path::Path::new("/")),
+    )
+}
+
+#[cfg(not(target_os = "linux"))]
+fn collect_persistence_snapshot() -> (&'static SnapshotWatch, Result<HashMap<String, String>, String>) {
+    (
+        &events::REGISTRY_WATCH,
+        registry_collector::snapshot_critical_values(),
+    )
+}
+
 fn refresh_installed_programs(state: &RuntimeState) {
     let mut programs = programs::get_installed_programs();
     for program in &mut programs {
@@ -452,6 +667,9 @@ fn refresh_installed_programs(state: &RuntimeState) {
 
 fn refresh_startup_processes(state: &RuntimeState) {
     let startup_items = startup::get_startup_processes();
+    for path in startup_items.iter().filter_map(|item| item.executable.as_deref()) {
+        state.queue_yara_scan(path);
+    }
     state.update_startup_processes(startup_items);
 }
 
@@ -1,11 +1,12 @@
 use std::collections::HashSet;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
 use std::process::Command;
 
 #[derive(Debug, Clone)]
 pub struct NetworkConnection {
     pub protocol: String,
-    pub local_address: String,
-    pub remote_address: String,
+    pub local_address: SocketAddr,
+    pub remote_address: Option<SocketAddr>,
     pub state: Option<String>,
     pub pid: u32,
 }
@@ -15,17 +16,166 @@ impl NetworkConnection {
         format!(
             "{}|{}|{}|{}|{}",
             self.protocol.to_lowercase(),
-            self.local_address.to_lowercase(),
-            self.remote_address.to_lowercase(),
+            self.local_address,
+            self.remote_display(),
             self.state.as_deref().unwrap_or_default().to_lowercase(),
             self.pid
         )
     }
+
+    pub fn remote_display(&self) -> String {
+        self.remote_address
+            .map(|address| address.to_string())
+            .unwrap_or_else(|| "*:*".to_string())
+    }
 }
 
+#[cfg(target_os = "linux")]
 pub fn collect_connections() -> Result<Vec<NetworkConnection>, String> {
+    match collect_proc_net_connections(std::path::Path::new(super::procfs::DEFAULT_PROC_ROOT)) {
+        Ok(rows) => Ok(rows),
+        Err(proc_err) => collect_netstat_connections()
+            .map_err(|netstat_err| format!("{proc_err}; fallback failed: {netstat_err}")),
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn collect_connections() -> Result<Vec<NetworkConnection>, String> {
+    collect_netstat_connections()
+}
+
+#[cfg(target_os = "linux")]
+fn collect_proc_net_connections(
+    proc_root: &std::path::Path,
+) -> Result<Vec<NetworkConnection>, String> {
+    use std::fs;
+
+    let pids_by_inode = super::procfs::shared_socket_inode_owners(proc_root);
+    let mut rows = Vec::new();
+    let mut seen = HashSet::new();
+    let mut readable_tables = 0usize;
+
+    for (table, protocol) in [("tcp", "tcp"), ("tcp6", "tcp"), ("udp", "udp"), ("udp6", "udp")] {
+        let Ok(raw) = fs::read_to_string(proc_root.join("net").join(table)) else {
+            continue;
+        };
+        readable_tables += 1;
+        for row in parse_proc_net_table(&raw, protocol) {
+            let connection = NetworkConnection {
+                protocol: row.protocol,
+                local_address: row.local_address,
+                remote_address: row.remote_address,
+                state: row.state,
+                pid: pids_by_inode.get(&row.inode).copied().unwrap_or(0),
+            };
+            if seen.insert(connection.key()) {
+                rows.push(connection);
+            }
+        }
+    }
+
+    if readable_tables == 0 {
+        return Err(format!(
+            "no socket tables readable under {}",
+            proc_root.join("net").display()
+        ));
+    }
+    Ok(rows)
+}
+
+#[cfg(target_os = "linux")]
+#[derive(Debug, Clone)]
+struct ProcNetRow {
+    protocol: String,
+    local_address: SocketAddr,
+    remote_address: Option<SocketAddr>,
+    state: Option<String>,
+    inode: u64,
+}
+
+#[cfg(target_os = "linux")]
+fn parse_proc_net_table(raw: &str, protocol: &str) -> Vec<ProcNetRow> {
+    let mut rows = Vec::new();
+    for line in raw.lines().skip(1) {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.len() < 10 {
+            continue;
+        }
+        let Some(local_address) = parse_proc_net_address(fields[1]) else {
+            continue;
+        };
+        let remote_address =
+            parse_proc_net_address(fields[2]).filter(|address| !is_unspecified_endpoint(address));
+        let state = if protocol == "tcp" {
+            u8::from_str_radix(fields[3], 16)
+                .ok()
+                .and_then(tcp_state_name)
+                .map(ToString::to_string)
+        } else {
+            None
+        };
+        let inode = fields[9].parse::<u64>().unwrap_or(0);
+
+        rows.push(ProcNetRow {
+            protocol: protocol.to_string(),
+            local_address,
+            remote_address,
+            state,
+            inode,
+        });
+    }
+    rows
+}
+
+/// Addresses are printed as the in-memory words of the kernel, so each 32-bit
+/// group is decoded with native endianness while the port stays big-endian hex.
+#[cfg(target_os = "linux")]
+fn parse_proc_net_address(raw: &str) -> Option<SocketAddr> {
+    let (host, port) = raw.split_once(':')?;
+    let port = u16::from_str_radix(port, 16).ok()?;
+    let ip = match host.len() {
+        8 => IpAddr::V4(Ipv4Addr::from(
+            u32::from_str_radix(host, 16).ok()?.to_ne_bytes(),
+        )),
+        32 => {
+            let mut octets = [0u8; 16];
+            for (index, chunk) in octets.chunks_mut(4).enumerate() {
+                let word = u32::from_str_radix(&host[index * 8..index * 8 + 8], 16).ok()?;
+                chunk.copy_from_slice(&word.to_ne_bytes());
+            }
+            let ip = Ipv6Addr::from(octets);
+            ip.to_ipv4_mapped().map(IpAddr::V4).unwrap_or(IpAddr::V6(ip))
+        }
+        _ => return None,
+    };
+    Some(SocketAddr::new(ip, port))
+}
+
+#[cfg(target_os = "linux")]
+fn tcp_state_name(code: u8) -> Option<&'static str> {
+    let name = match code {
+        0x01 => "ESTABLISHED",
+        0x02 => "SYN_SENT",
+        0x03 => "SYN_RECEIVED",
+        0x04 => "FIN_WAIT_1",
+        0x05 => "FIN_WAIT_2",
+        0x06 => "TIME_WAIT",
+        0x07 => "CLOSED",
+        0x08 => "CLOSE_WAIT",
+        0x09 => "LAST_ACK",
+        0x0A => "LISTENING",
+        0x0B => "CLOSING",
+        _ => return None,
+    };
+    Some(name)
+}
+
+fn collect_netstat_connections() -> Result<Vec<NetworkConnection>, String> {
     let mut command = Command::new("netstat");
+    #[cfg(target_os = "windows")]
     command.args(["-ano"]);
+    #[cfg(not(target_os = "windows"))]
+    command.args(["-tunapW"]);
 
     #[cfg(target_os = "windows")]
     {
@@ -40,61 +190,230 @@ pub fn collect_connections() -> Result<Vec<NetworkConnection>, String> {
         return Err("netstat command failed".to_string());
     }
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(parse_netstat_output(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Accepts both the Windows `netstat -ano` layout (`TCP local remote state pid`)
+/// and the Linux `netstat -tunap` layout (`tcp recvq sendq local remote state pid/name`).
+fn parse_netstat_output(stdout: &str) -> Vec<NetworkConnection> {
     let mut rows = Vec::new();
     let mut seen = HashSet::new();
 
     for line in stdout.lines() {
         let trimmed = line.trim();
-        if trimmed.is_empty() {
-            continue;
-        }
-        if !(trimmed.starts_with("TCP") || trimmed.starts_with("UDP")) {
-            continue;
-        }
-
         let parts: Vec<&str> = trimmed.split_whitespace().collect();
-        if parts.len() < 4 {
-            continue;
-        }
-
-        if parts[0].eq_ignore_ascii_case("TCP") {
-            if parts.len() < 5 {
-                continue;
-            }
-            let pid = match parts[4].parse::<u32>() {
-                Ok(value) => value,
-                Err(_) => continue,
-            };
-            let connection = NetworkConnection {
-                protocol: "tcp".to_string(),
-                local_address: parts[1].to_string(),
-                remote_address: parts[2].to_string(),
-                state: Some(parts[3].to_string()),
-                pid,
-            };
-            if seen.insert(connection.key()) {
-                rows.push(connection);
-            }
+        let Some(proto) = parts.first().map(|value| value.to_lowercase()) else {
             continue;
-        }
-
-        let pid = match parts.last().and_then(|value| value.parse::<u32>().ok()) {
-            Some(value) => value,
-            None => continue,
         };
-        let remote = if parts.len() > 3 { parts[2] } else { "*:*" };
-        let connection = NetworkConnection {
-            protocol: "udp".to_string(),
-            local_address: parts[1].to_string(),
-            remote_address: remote.to_string(),
-            state: None,
-            pid,
+        let connection = match proto.as_str() {
+            "tcp" | "udp" => parse_windows_netstat_row(&proto, &parts),
+            "tcp6" | "udp6" => parse_linux_netstat_row(&proto[..3], &parts),
+            _ => None,
+        };
+        let Some(connection) = connection else {
+            continue;
         };
         if seen.insert(connection.key()) {
             rows.push(connection);
         }
     }
 
-    Ok(rows)
+    rows
+}
+
+fn parse_windows_netstat_row(protocol: &str, parts: &[&str]) -> Option<NetworkConnection> {
+    if parts.len() >= 6 && parts[1].parse::<u64>().is_ok() && parts[2].parse::<u64>().is_ok() {
+        return parse_linux_netstat_row(protocol, parts);
+    }
+    if parts.len() < 4 {
+        return None;
+    }
+
+    if protocol == "tcp" {
+        if parts.len() < 5 {
+            return None;
+        }
+        return Some(NetworkConnection {
+            protocol: "tcp".to_string(),
+            local_address: parse_netstat_address(parts[1])?,
+            remote_address: parse_netstat_address(parts[2])
+                .filter(|address| !is_unspecified_endpoint(address)),
+            state: Some(parts[3].to_string()),
+            pid: parts[4].parse::<u32>().ok()?,
+        });
+    }
+
+    let pid = parts.last().and_then(|value| value.parse::<u32>().ok())?;
+    let remote = if parts.len() > 3 { parts[2] } else { "*:*" };
+    Some(NetworkConnection {
+        protocol: "udp".to_string(),
+        local_address: parse_netstat_address(parts[1])?,
+        remote_address: parse_netstat_address(remote)
+            .filter(|address| !is_unspecified_endpoint(address)),
+        state: None,
+        pid,
+    })
+}
+
+fn parse_linux_netstat_row(protocol: &str, parts: &[&str]) -> Option<NetworkConnection> {
+    if parts.len() < 6 {
+        return None;
+    }
+    let local_address = parse_netstat_address(parts[3])?;
+    let remote_address =
+        parse_netstat_address(parts[4]).filter(|address| !is_unspecified_endpoint(address));
+    let (state, pid_column) = if protocol == "tcp" {
+        (parts.get(5).map(|value| normalize_linux_state(value)), parts.get(6))
+    } else {
+        (None, parts.get(5))
+    };
+    let pid = pid_column
+        .and_then(|value| value.split('/').next())
+        .and_then(|value| value.parse::<u32>().ok())
+        .unwrap_or(0);
+
+    Some(NetworkConnection {
+        protocol: protocol.to_string(),
+        local_address,
+        remote_address,
+        state,
+        pid,
+    })
+}
+
+fn normalize_linux_state(state: &str) -> String {
+    match state {
+        "LISTEN" => "LISTENING",
+        "SYN_RECV" => "SYN_RECEIVED",
+        "FIN_WAIT1" => "FIN_WAIT_1",
+        "FIN_WAIT2" => "FIN_WAIT_2",
+        "CLOSE" => "CLOSED",
+        other => other,
+    }
+    .to_string()
+}
+
+fn parse_netstat_address(raw: &str) -> Option<SocketAddr> {
+    if raw == "*:*" {
+        return None;
+    }
+    let (host, port) = raw.rsplit_once(':')?;
+    let port = if port == "*" { 0 } else { port.parse::<u16>().ok()? };
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    let host = host.split_once('%').map(|(address, _)| address).unwrap_or(host);
+    let ip = if host == "*" {
+        IpAddr::V4(Ipv4Addr::UNSPECIFIED)
+    } else {
+        host.parse::<IpAddr>().ok()?
+    };
+    Some(SocketAddr::new(ip, port))
+}
+
+fn is_unspecified_endpoint(address: &SocketAddr) -> bool {
+    address.ip().is_unspecified() && address.port() == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::SocketAddr;
+
+    use super::parse_netstat_output;
+
+    fn addr(value: &str) -> SocketAddr {
+        value.parse().expect("valid socket address")
+    }
+
+    #[test]
+    fn parse_netstat_output_reads_windows_fixture() {
+        let rows = parse_netstat_output(include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/network/netstat_windows.txt"
+        )));
+
+        assert_eq!(rows.len(), 6);
+        assert_eq!(rows[0].local_address, addr("0.0.0.0:135"));
+        assert_eq!(rows[0].remote_address, None);
+        assert_eq!(rows[0].state.as_deref(), Some("LISTENING"));
+        assert_eq!(rows[1].remote_address, Some(addr("140.82.112.3:443")));
+        assert_eq!(rows[1].pid, 7312);
+        assert_eq!(rows[3].local_address, addr("[fe80::1c2b:3a4d:5e6f:7081]:52000"));
+        assert_eq!(rows[4].protocol, "udp");
+        assert_eq!(rows[4].remote_address, None);
+        assert_eq!(rows[5].local_address, addr("[::1]:1900"));
+        assert_eq!(rows[5].pid, 5120);
+    }
+
+    #[test]
+    fn parse_netstat_output_reads_linux_fixture() {
+        let rows = parse_netstat_output(include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/network/netstat_linux.txt"
+        )));
+
+        assert_eq!(rows.len(), 5);
+        assert_eq!(rows[0].state.as_deref(), Some("LISTENING"));
+        assert_eq!(rows[0].pid, 0);
+        assert_eq!(rows[1].remote_address, Some(addr("140.82.112.3:443")));
+        assert_eq!(rows[1].pid, 2345);
+        assert_eq!(rows[2].local_address, addr("[::]:22"));
+        assert_eq!(rows[3].remote_address, Some(addr("[2001:db8::1]:443")));
+        assert_eq!(rows[4].protocol, "udp");
+        assert_eq!(rows[4].state, None);
+        assert_eq!(rows[4].pid, 1234);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn parse_proc_net_table_decodes_ipv4_and_states() {
+        let rows = super::parse_proc_net_table(
+            include_str!(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/tests/fixtures/network/proc_net_tcp.txt"
+            )),
+            "tcp",
+        );
+
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0].local_address, addr("127.0.0.53:53"));
+        assert_eq!(rows[0].remote_address, None);
+        assert_eq!(rows[0].state.as_deref(), Some("LISTENING"));
+        assert_eq!(rows[1].local_address, addr("192.168.1.5:44312"));
+        assert_eq!(rows[1].remote_address, Some(addr("200.82.112.3:443")));
+        assert_eq!(rows[1].inode, 88412);
+        assert_eq!(rows[2].state.as_deref(), Some("TIME_WAIT"));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn parse_proc_net_table_decodes_ipv6_and_mapped_ipv4() {
+        let rows = super::parse_proc_net_table(
+            include_str!(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/tests/fixtures/network/proc_net_tcp6.txt"
+            )),
+            "tcp",
+        );
+
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].local_address, addr("[::]:22"));
+        assert_eq!(rows[1].local_address, addr("192.168.1.5:50082"));
+        assert_eq!(rows[1].remote_address, Some(addr("200.82.112.3:443")));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn parse_proc_net_table_leaves_udp_stateless() {
+        let rows = super::parse_proc_net_table(
+            include_str!(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/tests/fixtures/network/proc_net_udp.txt"
+            )),
+            "udp",
+        );
+
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].local_address, addr("0.0.0.0:5353"));
+        assert_eq!(rows[0].state, None);
+        assert_eq!(rows[1].remote_address, Some(addr("8.8.8.8:53")));
+    }
 }
@@ -0,0 +1,198 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use sha2::{Digest, Sha256};
+
+const INLINE_CONTENT_LIMIT: usize = 4_096;
+
+const WATCHED_FILES: &[&str] = &[
+    "etc/ld.so.preload",
+    "etc/rc.local",
+    "etc/crontab",
+    "etc/anacrontab",
+    "etc/profile",
+    "etc/bash.bashrc",
+    "etc/bashrc",
+    "etc/environment",
+    "etc/zsh/zshrc",
+    "etc/zsh/zprofile",
+];
+
+const WATCHED_DIRS: &[(&str, usize)] = &[
+    ("etc/systemd/system", 2),
+    ("etc/systemd/user", 2),
+    ("usr/local/lib/systemd/system", 1),
+    ("etc/cron.d", 1),
+    ("etc/cron.hourly", 1),
+    ("etc/cron.daily", 1),
+    ("etc/cron.weekly", 1),
+    ("etc/cron.monthly", 1),
+    ("var/spool/cron", 2),
+    ("etc/profile.d", 1),
+    ("etc/xdg/autostart", 1),
+];
+
+const USER_FILES: &[&str] = &[
+    ".bashrc",
+    ".bash_profile",
+    ".bash_login",
+    ".bash_logout",
+    ".profile",
+    ".zshrc",
+    ".zprofile",
+    ".zlogin",
+];
+
+const USER_DIRS: &[(&str, usize)] = &[(".config/autostart", 1), (".config/systemd/user", 2)];
+
+/// Snapshots Linux persistence locations below `root` as `absolute path -> value`.
+/// Small text files keep their full content so diffs are readable in the
+/// timeline; larger or binary files are reduced to a digest.
+pub fn snapshot_persistence_files(root: &Path) -> Result<HashMap<String, String>, String> {
+    if !root.is_dir() {
+        return Err(format!("persistence root {} is not a directory", root.display()));
+    }
+
+    let mut snapshot = HashMap::new();
+    for file in WATCHED_FILES {
+        collect_entry(root, &root.join(file), &mut snapshot);
+    }
+    for (dir, depth) in WATCHED_DIRS {
+        collect_dir(root, &root.join(dir), *depth, &mut snapshot);
+    }
+    for home in user_homes(root) {
+        for file in USER_FILES {
+            collect_entry(root, &home.join(file), &mut snapshot);
+        }
+        for (dir, depth) in USER_DIRS {
+            collect_dir(root, &home.join(dir), *depth, &mut snapshot);
+        }
+    }
+
+    Ok(snapshot)
+}
+
+pub fn user_homes(root: &Path) -> Vec<PathBuf> {
+    let mut homes = vec![root.join("root")];
+    if let Ok(entries) = fs::read_dir(root.join("home")) {
+        homes.extend(
+            entries
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|path| path.is_dir()),
+        );
+    }
+    homes
+}
+
+fn collect_dir(root: &Path, dir: &Path, depth: usize, snapshot: &mut HashMap<String, String>) {
+    if depth == 0 {
+        return;
+    }
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if file_type.is_dir() {
+            collect_dir(root, &path, depth - 1, snapshot);
+        } else {
+            collect_entry(root, &path, snapshot);
+        }
+    }
+}
+
+fn collect_entry(root: &Path, path: &Path, snapshot: &mut HashMap<String, String>) {
+    let Ok(metadata) = fs::symlink_metadata(path) else {
+        return;
+    };
+    let value = if metadata.file_type().is_symlink() {
+        match fs::read_link(path) {
+            Ok(target) => format!("symlink -> {}", target.display()),
+            Err(_) => return,
+        }
+    } else if metadata.is_file() {
+        match fs::read(path) {
+            Ok(content) => describe_content(&content),
+            Err(_) => return,
+        }
+    } else {
+        return;
+    };
+    snapshot.insert(display_path(root, path), value);
+}
+
+fn describe_content(content: &[u8]) -> String {
+    if content.len() <= INLINE_CONTENT_LIMIT {
+        if let Ok(text) = std::str::from_utf8(content) {
+            return text.to_string();
+        }
+    }
+    let digest = Sha256::digest(content);
+    format!("sha256:{:x} ({} bytes)", digest, content.len())
+}
+
+fn display_path(root: &Path, path: &Path) -> String {
+    let relative = path.strip_prefix(root).unwrap_or(path);
+    format!("/{}", relative.to_string_lossy().trim_start_matches('/'))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::snapshot_persistence_files;
+    use crate::test_support::{write, TempDir};
+
+    #[test]
+    fn snapshot_covers_system_and_user_locations() {
+        let root = TempDir::new("persistence-locations");
+        write(&root, "etc/ld.so.preload", "/usr/lib/libevil.so\n");
+        write(&root, "etc/cron.d/backdoor", "* * * * * root /tmp/.x\n");
+        write(&root, "etc/systemd/system/updater.service", "[Service]\nExecStart=/opt/u\n");
+        write(&root, "home/alice/.bashrc", "alias ls='ls --color'\n");
+        write(&root, "home/alice/.config/autostart/sync.desktop", "[Desktop Entry]\n");
+        write(&root, "etc/unrelated.conf", "ignored\n");
+        fs::create_dir_all(root.join("etc/systemd/system/multi-user.target.wants"))
+            .expect("create wants dir");
+        std::os::unix::fs::symlink(
+            "/etc/systemd/system/updater.service",
+            root.join("etc/systemd/system/multi-user.target.wants/updater.service"),
+        )
+        .expect("create unit symlink");
+
+        let snapshot = snapshot_persistence_files(&root).expect("snapshot");
+
+        assert_eq!(
+            snapshot.get("/etc/ld.so.preload").map(String::as_str),
+            Some("/usr/lib/libevil.so\n")
+        );
+        assert!(snapshot.contains_key("/etc/cron.d/backdoor"));
+        assert!(snapshot.contains_key("/etc/systemd/system/updater.service"));
+        assert!(snapshot.contains_key("/home/alice/.bashrc"));
+        assert!(snapshot.contains_key("/home/alice/.config/autostart/sync.desktop"));
+        assert!(!snapshot.contains_key("/etc/unrelated.conf"));
+        assert_eq!(
+            snapshot
+                .get("/etc/systemd/system/multi-user.target.wants/updater.service")
+                .map(String::as_str),
+            Some("symlink -> /etc/systemd/system/updater.service")
+        );
+    }
+
+    #[test]
+    fn snapshot_digests_large_files() {
+        let root = TempDir::new("persistence-digest");
+        write(&root, "etc/rc.local", &"x".repeat(5_000));
+
+        let snapshot = snapshot_persistence_files(&root).expect("snapshot");
+        let value = snapshot.get("/etc/rc.local").expect("rc.local tracked");
+
+        assert!(value.starts_with("sha256:"));
+        assert!(value.ends_with("(5000 bytes)"));
+    }
+}
@@ -1,11 +1,22 @@
 use std::collections::HashMap;
-use std::process::Command;
+use std::path::Path;
 use std::sync::{Mutex, OnceLock};
+use std::time::Instant;
 
 use chrono::{TimeZone, Utc};
 use sysinfo::System;
+#[cfg(not(target_os = "linux"))]
+use sysinfo::{Pid, Users};
 
-use crate::models::{ProcessMetric, ProcessNode, RiskLevel, ThreatVerdict, TrustLevel};
+use crate::models::{
+    BinarySignature, ProcessMetric, ProcessNode, RiskLevel, SignatureStatus, ThreatVerdict,
+    TrustLevel,
+};
+use crate::monitoring::authenticode;
+#[cfg(target_os = "linux")]
+use crate::monitoring::procfs;
+#[cfg(target_os = "linux")]
+use crate::monitoring::sock_diag::{self, TcpByteCounters};
 
 pub fn collect_process_metrics() -> Vec<ProcessMetric> {
     static COLLECTOR: OnceLock<Mutex<ProcessCollector>> = OnceLock::new();
@@ -14,19 +25,44 @@ pub fn collect_process_metrics() -> Vec<ProcessMetric> {
     guard.collect()
 }
 
+const USER_DIRECTORY_RELOAD_SECS: u64 = 60;
+
 struct ProcessCollector {
     system: System,
+    #[cfg(target_os = "linux")]
+    users: procfs::UserDirectory,
+    #[cfg(not(target_os = "linux"))]
+    users: Users,
+    users_loaded_at: Instant,
+    refreshed_at: Instant,
+    /// TCP byte counters from the previous poll; `None` until the first one.
+    #[cfg(target_os = "linux")]
+    tcp_counters: Option<HashMap<u64, TcpByteCounters>>,
 }
 
 impl ProcessCollector {
     fn new() -> Self {
         let mut system = System::new_all();
         system.refresh_all();
-        Self { system }
+        Self {
+            system,
+            users: load_users(),
+            users_loaded_at: Instant::now(),
+            refreshed_at: Instant::now(),
+            #[cfg(target_os = "linux")]
+            tcp_counters: None,
+        }
     }
 
     fn collect(&mut self) -> Vec<ProcessMetric> {
         self.system.refresh_all();
+        let refreshed_at = Instant::now();
+        let elapsed_secs = refreshed_at
+            .duration_since(self.refreshed_at)
+            .as_secs_f32()
+            .max(0.001);
+        self.refreshed_at = refreshed_at;
+        let network_bytes = self.network_bytes_since_last_poll();
         let mut metrics = Vec::with_capacity(self.system.processes().len());
 
         for (pid, process) in self.system.processes() {
@@ -34,16 +70,36 @@ impl ProcessCollector {
                 .timestamp_opt(process.start_time() as i64, 0)
                 .single()
                 .map(|value| value.to_rfc3339());
+            let args: Vec<String> = process
+                .cmd()
+                .iter()
+                .map(|value| value.to_string_lossy().to_string())
+                .collect();
+            let disk_usage = process.disk_usage();
+            let network = network_bytes.get(&pid.as_u32()).copied().unwrap_or_default();
 
             metrics.push(ProcessMetric {
                 pid: pid.as_u32(),
                 ppid: process.parent().map(|value| value.as_u32()),
                 name: process.name().to_string_lossy().to_string(),
                 exe_path: process.exe().map(|value| value.to_string_lossy().to_string()),
+                sha256: None,
+                signature: None,
+                prevalence: None,
+                cmdline: join_command_line(&args),
                 user: None,
+                real_uid: None,
+                effective_uid: None,
+                real_gid: None,
+                effective_gid: None,
                 cpu_pct: process.cpu_usage().max(0.0),
                 gpu_pct: 0.0,
+                gpu_memory_mb: 0.0,
                 memory_mb: (process.memory() as f32 / 1024.0 / 1024.0).max(0.0),
+                disk_read_bytes_per_sec: disk_usage.read_bytes as f32 / elapsed_secs,
+                disk_write_bytes_per_sec: disk_usage.written_bytes as f32 / elapsed_secs,
+                net_sent_bytes_per_sec: network.sent as f32 / elapsed_secs,
+                net_received_bytes_per_sec: network.received as f32 / elapsed_secs,
                 status: format!("{:?}", process.status()),
                 started_at: started,
                 trust_level: TrustLevel::Unknown,
@@ -55,9 +111,159 @@ impl ProcessCollector {
             });
         }
 
+        for metric in &mut metrics {
+            self.apply_identity_details(metric);
+        }
+
         metrics.sort_by(|a, b| b.cpu_pct.total_cmp(&a.cpu_pct));
         metrics
     }
+
+    #[cfg(target_os = "linux")]
+    fn network_bytes_since_last_poll(&mut self) -> HashMap<u32, NetworkBytes> {
+        // Without sock_diag (e.g. a restricted sandbox) rates simply stay zero.
+        let Ok(current) = sock_diag::tcp_byte_counters() else {
+            self.tcp_counters = None;
+            return HashMap::new();
+        };
+        let network_bytes = match &self.tcp_counters {
+            Some(previous) => {
+                let proc_root = std::path::Path::new(procfs::DEFAULT_PROC_ROOT);
+                let owners = procfs::shared_socket_inode_owners(proc_root);
+                network_bytes_by_pid(previous, &current, &owners)
+            }
+            None => HashMap::new(),
+        };
+        self.tcp_counters = Some(current);
+        network_bytes
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn network_bytes_since_last_poll(&mut self) -> HashMap<u32, NetworkBytes> {
+        HashMap::new()
+    }
+
+    #[cfg(target_os = "linux")]
+    fn apply_identity_details(&mut self, metric: &mut ProcessMetric) {
+        let proc_root = std::path::Path::new(procfs::DEFAULT_PROC_ROOT);
+        let Some(details) = procfs::read_process_details(proc_root, metric.pid) else {
+            return;
+        };
+
+        if let Some(cmdline) = join_command_line(&details.cmdline) {
+            metric.cmdline = Some(cmdline);
+        }
+        if metric.ppid.is_none() {
+            metric.ppid = details.ppid;
+        }
+        metric.real_uid = details.real_uid;
+        metric.effective_uid = details.effective_uid;
+        metric.real_gid = details.real_gid;
+        metric.effective_gid = details.effective_gid;
+
+        let Some(uid) = details.effective_uid.or(details.real_uid) else {
+            return;
+        };
+        if !self.users.contains(uid) {
+            self.reload_users_if_stale();
+        }
+        metric.user = Some(
+            self.users
+                .name_for(uid)
+                .map(ToString::to_string)
+                .unwrap_or_else(|| uid.to_string()),
+        );
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn apply_identity_details(&mut self, metric: &mut ProcessMetric) {
+        let Some(process) = self.system.process(Pid::from_u32(metric.pid)) else {
+            return;
+        };
+        // Windows user ids are SIDs rather than numbers, so only the group
+        // ids (always absent there) come through on that platform.
+        #[cfg(unix)]
+        {
+            metric.real_uid = process.user_id().map(|uid| **uid);
+            metric.effective_uid = process.effective_user_id().map(|uid| **uid);
+        }
+        metric.real_gid = process.group_id().map(|gid| *gid);
+        metric.effective_gid = process.effective_group_id().map(|gid| *gid);
+
+        let Some(uid) = process.effective_user_id().or_else(|| process.user_id()).cloned() else {
+            return;
+        };
+        if self.users.get_user_by_id(&uid).is_none() {
+            self.reload_users_if_stale();
+        }
+        metric.user = self
+            .users
+            .get_user_by_id(&uid)
+            .map(|user| user.name().to_string());
+    }
+
+    fn reload_users_if_stale(&mut self) {
+        if self.users_loaded_at.elapsed().as_secs() < USER_DIRECTORY_RELOAD_SECS {
+            return;
+        }
+        self.users = load_users();
+        self.users_loaded_at = Instant::now();
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct NetworkBytes {
+    sent: u64,
+    received: u64,
+}
+
+/// Bytes each process moved since the previous poll. Sockets opened in
+/// between count from zero; sockets without a live owner are dropped.
+#[cfg(target_os = "linux")]
+fn network_bytes_by_pid(
+    previous: &HashMap<u64, TcpByteCounters>,
+    current: &HashMap<u64, TcpByteCounters>,
+    owners: &HashMap<u64, u32>,
+) -> HashMap<u32, NetworkBytes> {
+    let mut by_pid: HashMap<u32, NetworkBytes> = HashMap::new();
+    for (inode, counters) in current {
+        let Some(pid) = owners.get(inode) else {
+            continue;
+        };
+        let before = previous.get(inode).copied().unwrap_or_default();
+        let entry = by_pid.entry(*pid).or_default();
+        entry.sent += counters.sent.saturating_sub(before.sent);
+        entry.received += counters.received.saturating_sub(before.received);
+    }
+    by_pid
+}
+
+#[cfg(target_os = "linux")]
+fn load_users() -> procfs::UserDirectory {
+    procfs::UserDirectory::load(std::path::Path::new(procfs::DEFAULT_PASSWD_PATH))
+}
+
+#[cfg(not(target_os = "linux"))]
+fn load_users() -> Users {
+    Users::new_with_refreshed_list()
+}
+
+pub fn join_command_line(args: &[String]) -> Option<String> {
+    if args.is_empty() {
+        return None;
+    }
+    let joined = args
+        .iter()
+        .map(|arg| {
+            if arg.chars().any(char::is_whitespace) && !arg.starts_with('"') {
+                format!("\"{}\"", arg)
+            } else {
+                arg.clone()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+    Some(joined)
 }
 
 pub fn build_process_tree(metrics: &[ProcessMetric]) -> Vec<ProcessNode> {
@@ -90,20 +296,42 @@ pub fn build_process_tree(metrics: &[ProcessMetric]) -> Vec<ProcessNode> {
     nodes
 }
 
-pub fn is_binary_signed(path: &str) -> bool {
+pub fn probe_binary_signature(path: &str) -> Result<Option<BinarySignature>, String> {
+    authenticode::inspect_file(Path::new(path))
+}
+
+/// Images without an embedded signature may still be signed through a
+/// Windows catalog, which only the OS can resolve.
+pub fn needs_catalog_lookup(signature: &BinarySignature) -> bool {
+    cfg!(target_os = "windows") && signature.status == SignatureStatus::Unsigned
+}
+
+pub fn apply_catalog_signature(path: &str, signature: &mut BinarySignature) {
+    if os_signature_is_valid(path) {
+        signature.status = SignatureStatus::Catalog;
+        signature.verified = true;
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+fn os_signature_is_valid(_path: &str) -> bool {
+    false
+}
+
+#[cfg(target_os = "windows")]
+fn os_signature_is_valid(path: &str) -> bool {
+    use std::os::windows::process::CommandExt;
+    use std::process::Command;
+
     let escaped = path.replace('\'', "''");
     let script = format!(
         "$ErrorActionPreference='SilentlyContinue'; (Get-AuthenticodeSignature -LiteralPath '{}').Status",
         escaped
     );
-    let mut command = Command::new("powershell.exe");
-    command.args(["-NoProfile", "-Command", &script]);
-    #[cfg(target_os = "windows")]
-    {
-        use std::os::windows::process::CommandExt;
-        command.creation_flags(0x08000000);
-    }
-    let output = command.output();
+    let output = Command::new("powershell.exe")
+        .args(["-NoProfile", "-Command", &script])
+        .creation_flags(0x08000000)
+        .output();
 
     match output {
         Ok(out) if out.status.success() => {
@@ -143,3 +371,56 @@ fn build_node(
         children,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    #[cfg(target_os = "linux")]
+    use std::collections::HashMap;
+
+    use super::join_command_line;
+    #[cfg(target_os = "linux")]
+    use super::{network_bytes_by_pid, NetworkBytes};
+    #[cfg(target_os = "linux")]
+    use crate::monitoring::sock_diag::TcpByteCounters;
+
+    #[test]
+    fn join_command_line_quotes_arguments_with_spaces() {
+        let args = vec![
+            "/usr/bin/python3".to_string(),
+            "-c".to_string(),
+            "print('hi there')".to_string(),
+        ];
+
+        assert_eq!(
+            join_command_line(&args).as_deref(),
+            Some("/usr/bin/python3 -c \"print('hi there')\"")
+        );
+        assert_eq!(join_command_line(&[]), None);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn network_bytes_by_pid_sums_socket_deltas_per_owner() {
+        let counters = |sent, received| TcpByteCounters { sent, received };
+        let previous = HashMap::from([(1, counters(100, 1_000)), (2, counters(50, 50))]);
+        let current = HashMap::from([
+            (1, counters(400, 1_500)),
+            (2, counters(50, 80)),
+            (3, counters(10, 20)),
+            (4, counters(999, 999)),
+        ]);
+        let owners = HashMap::from([(1, 77), (2, 77), (3, 88)]);
+
+        let by_pid = network_bytes_by_pid(&previous, &current, &owners);
+
+        assert_eq!(
+            by_pid.get(&77),
+            Some(&NetworkBytes {
+                sent: 300,
+                received: 530
+            })
+        );
+        assert_eq!(by_pid.get(&88).map(|bytes| bytes.received), Some(20));
+        assert_eq!(by_pid.len(), 2);
+    }
+}
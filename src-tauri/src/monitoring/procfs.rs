@@ -0,0 +1,242 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+pub const DEFAULT_PROC_ROOT: &str = "/proc";
+pub const DEFAULT_PASSWD_PATH: &str = "/etc/passwd";
+/// Shorter than one monitoring tick, so a cached walk never outlives it.
+const SOCKET_OWNERS_TTL: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProcessDetails {
+    pub ppid: Option<u32>,
+    pub cmdline: Vec<String>,
+    pub real_uid: Option<u32>,
+    pub effective_uid: Option<u32>,
+    pub real_gid: Option<u32>,
+    pub effective_gid: Option<u32>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct StatusIds {
+    real_uid: Option<u32>,
+    effective_uid: Option<u32>,
+    real_gid: Option<u32>,
+    effective_gid: Option<u32>,
+}
+
+pub fn read_process_details(proc_root: &Path, pid: u32) -> Option<ProcessDetails> {
+    let pid_dir = proc_root.join(pid.to_string());
+    let status = fs::read_to_string(pid_dir.join("status")).ok()?;
+    let ids = parse_status_ids(&status);
+    let ppid = fs::read_to_string(pid_dir.join("stat"))
+        .ok()
+        .and_then(|raw| parse_stat_ppid(&raw));
+    let cmdline = fs::read(pid_dir.join("cmdline"))
+        .map(|raw| parse_cmdline(&raw))
+        .unwrap_or_default();
+
+    Some(ProcessDetails {
+        ppid,
+        cmdline,
+        real_uid: ids.real_uid,
+        effective_uid: ids.effective_uid,
+        real_gid: ids.real_gid,
+        effective_gid: ids.effective_gid,
+    })
+}
+
+pub fn list_pids(proc_root: &Path) -> Vec<u32> {
+    let Ok(entries) = fs::read_dir(proc_root) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter_map(|entry| entry.file_name().to_str()?.parse::<u32>().ok())
+        .collect()
+}
+
+/// Maps socket inodes to the pid holding them open by resolving the
+/// `socket:[<inode>]` links under `/proc/<pid>/fd`. Processes owned by other
+/// users are skipped silently when the caller lacks permission.
+fn socket_inode_owners(proc_root: &Path) -> HashMap<u64, u32> {
+    let mut owners = HashMap::new();
+    for pid in list_pids(proc_root) {
+        let Ok(entries) = fs::read_dir(proc_root.join(pid.to_string()).join("fd")) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let Ok(target) = fs::read_link(entry.path()) else {
+                continue;
+            };
+            if let Some(inode) = parse_socket_link(&target.to_string_lossy()) {
+                owners.entry(inode).or_insert(pid);
+            }
+        }
+    }
+    owners
+}
+
+/// [`socket_inode_owners`], reused for up to a second. The process and network
+/// collectors both need the map within the same tick, and the fd walk is the
+/// most expensive part of either.
+pub fn shared_socket_inode_owners(proc_root: &Path) -> Arc<HashMap<u64, u32>> {
+    type Cached = (PathBuf, Instant, Arc<HashMap<u64, u32>>);
+    static CACHE: Mutex<Option<Cached>> = Mutex::new(None);
+
+    let mut cache = CACHE.lock().expect("poisoned socket owner cache lock");
+    if let Some((root, walked_at, owners)) = cache.as_ref() {
+        if root == proc_root && walked_at.elapsed() < SOCKET_OWNERS_TTL {
+            return Arc::clone(owners);
+        }
+    }
+    let owners = Arc::new(socket_inode_owners(proc_root));
+    *cache = Some((proc_root.to_path_buf(), Instant::now(), Arc::clone(&owners)));
+    owners
+}
+
+fn parse_socket_link(target: &str) -> Option<u64> {
+    target
+        .strip_prefix("socket:[")?
+        .strip_suffix(']')?
+        .parse::<u64>()
+        .ok()
+}
+
+/// Splits the NUL separated argv of `/proc/<pid>/cmdline`. Kernel threads
+/// have an empty cmdline.
+pub fn parse_cmdline(raw: &[u8]) -> Vec<String> {
+    raw.split(|byte| *byte == 0)
+        .filter(|arg| !arg.is_empty())
+        .map(|arg| String::from_utf8_lossy(arg).to_string())
+        .collect()
+}
+
+fn parse_status_ids(raw: &str) -> StatusIds {
+    let mut ids = StatusIds::default();
+    for line in raw.lines() {
+        if let Some(rest) = line.strip_prefix("Uid:") {
+            let (real, effective) = parse_id_pair(rest);
+            ids.real_uid = real;
+            ids.effective_uid = effective;
+        } else if let Some(rest) = line.strip_prefix("Gid:") {
+            let (real, effective) = parse_id_pair(rest);
+            ids.real_gid = real;
+            ids.effective_gid = effective;
+        }
+    }
+    ids
+}
+
+fn parse_id_pair(raw: &str) -> (Option<u32>, Option<u32>) {
+    let mut values = raw.split_whitespace().map(|value| value.parse::<u32>().ok());
+    let real = values.next().flatten();
+    let effective = values.next().flatten();
+    (real, effective)
+}
+
+/// `comm` may itself contain spaces and parentheses, so fields are read after
+/// the last closing parenthesis.
+fn parse_stat_ppid(raw: &str) -> Option<u32> {
+    let (_, rest) = raw.rsplit_once(')')?;
+    rest.split_whitespace()
+        .nth(1)
+        .and_then(|value| value.parse::<u32>().ok())
+        .filter(|value| *value > 0)
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct UserDirectory {
+    names_by_uid: HashMap<u32, String>,
+}
+
+impl UserDirectory {
+    pub fn load(passwd_path: &Path) -> Self {
+        let raw = fs::read_to_string(passwd_path).unwrap_or_default();
+        Self::from_passwd(&raw)
+    }
+
+    pub fn from_passwd(raw: &str) -> Self {
+        let mut names_by_uid = HashMap::new();
+        for line in raw.lines() {
+            let trimmed = line.trim();
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                continue;
+            }
+            let mut fields = trimmed.split(':');
+            let Some(name) = fields.next().filter(|value| !value.is_empty()) else {
+                continue;
+            };
+            let Some(uid) = fields.nth(1).and_then(|value| value.parse::<u32>().ok()) else {
+                continue;
+            };
+            names_by_uid.entry(uid).or_insert_with(|| name.to_string());
+        }
+        Self { names_by_uid }
+    }
+
+    pub fn name_for(&self, uid: u32) -> Option<&str> {
+        self.names_by_uid.get(&uid).map(String::as_str)
+    }
+
+    pub fn contains(&self, uid: u32) -> bool {
+        self.names_by_uid.contains_key(&uid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        parse_cmdline, parse_socket_link, parse_stat_ppid, parse_status_ids, UserDirectory,
+    };
+
+    #[test]
+    fn parse_cmdline_splits_nul_separated_arguments() {
+        let raw = b"/usr/bin/python3\0-c\0print('hi there')\0";
+
+        assert_eq!(
+            parse_cmdline(raw),
+            vec!["/usr/bin/python3", "-c", "print('hi there')"]
+        );
+        assert!(parse_cmdline(b"").is_empty());
+    }
+
+    #[test]
+    fn parse_stat_ppid_handles_parentheses_in_comm() {
+        let raw = "4242 (evil) (x)) S 101 4242 4242 0 -1 4194560 120 0 0 0";
+
+        assert_eq!(parse_stat_ppid(raw), Some(101));
+        assert_eq!(parse_stat_ppid("1 (systemd) S 0 1 1 0 -1"), None);
+    }
+
+    #[test]
+    fn parse_status_reads_real_and_effective_ids() {
+        let raw = "Name:\tsudo\nUmask:\t0022\nState:\tS (sleeping)\nUid:\t1000\t0\t0\t0\nGid:\t1000\t27\t27\t27\n";
+        let ids = parse_status_ids(raw);
+
+        assert_eq!(ids.real_uid, Some(1000));
+        assert_eq!(ids.effective_uid, Some(0));
+        assert_eq!(ids.real_gid, Some(1000));
+        assert_eq!(ids.effective_gid, Some(27));
+    }
+
+    #[test]
+    fn parse_socket_link_extracts_inode() {
+        assert_eq!(parse_socket_link("socket:[88412]"), Some(88412));
+        assert_eq!(parse_socket_link("pipe:[1234]"), None);
+        assert_eq!(parse_socket_link("/dev/null"), None);
+    }
+
+    #[test]
+    fn user_directory_resolves_names_from_passwd() {
+        let users = UserDirectory::from_passwd(
+            "root:x:0:0:root:/root:/bin/bash\n# comment\nasier:x:1000:1000::/home/asier:/bin/zsh\n",
+        );
+
+        assert_eq!(users.name_for(0), Some("root"));
+        assert_eq!(users.name_for(1000), Some("asier"));
+        assert_eq!(users.name_for(1001), None);
+    }
+}
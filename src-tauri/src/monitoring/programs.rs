@@ -91,7 +91,737 @@ fn collect_from_hive(
     }
 }
 
-#[cfg(not(target_os = "windows"))]
+#[cfg(target_os = "linux")]
+const DPKG_STATUS_PATH: &str = "var/lib/dpkg/status";
+
+#[cfg(target_os = "linux")]
+const DPKG_INFO_DIR: &str = "var/lib/dpkg/info";
+
+#[cfg(target_os = "linux")]
+const RPM_DATABASE_PATHS: &[&str] = &["var/lib/rpm/rpmdb.sqlite", "usr/lib/sysimage/rpm/rpmdb.sqlite"];
+
+#[cfg(target_os = "linux")]
+const SNAP_MOUNT_DIRS: &[&str] = &["snap", "var/lib/snapd/snap"];
+
+#[cfg(target_os = "linux")]
+const BINARY_DIRS: &[&str] = &["/usr/bin/", "/usr/sbin/", "/bin/", "/sbin/", "/usr/local/bin/", "/usr/games/"];
+
+#[cfg(target_os = "linux")]
+pub fn get_installed_programs() -> Vec<InstalledProgram> {
+    collect_linux_programs(std::path::Path::new("/"))
+}
+
+#[cfg(target_os = "linux")]
+pub fn collect_linux_programs(root: &std::path::Path) -> Vec<InstalledProgram> {
+    let mut programs = Vec::new();
+    let mut seen = HashSet::new();
+
+    collect_dpkg_packages(root, &mut programs, &mut seen);
+    for database in RPM_DATABASE_PATHS {
+        if collect_rpm_packages(&root.join(database), &mut programs, &mut seen) {
+            break;
+        }
+    }
+    collect_flatpak_apps(root, &root.join("var/lib/flatpak/app"), "Flatpak (system)", &mut programs, &mut seen);
+    for home in super::persistence_collector::user_homes(root) {
+        let user = home
+            .file_name()
+            .map(|value| value.to_string_lossy().to_string())
+            .unwrap_or_default();
+        collect_flatpak_apps(
+            root,
+            &home.join(".local/share/flatpak/app"),
+            &format!("Flatpak ({})", user),
+            &mut programs,
+            &mut seen,
+        );
+    }
+    for dir in SNAP_MOUNT_DIRS {
+        collect_snap_packages(root, &root.join(dir), &mut programs, &mut seen);
+    }
+
+    programs.sort_by_key(|program| program.name.to_lowercase());
+    programs
+}
+
+#[cfg(target_os = "linux")]
+fn push_program(
+    program: InstalledProgram,
+    out: &mut Vec<InstalledProgram>,
+    seen: &mut HashSet<String>,
+) {
+    let dedupe_key = format!(
+        "{}|{}|{}",
+        program.name.to_lowercase(),
+        program.version.clone().unwrap_or_default().to_lowercase(),
+        program.source.to_lowercase()
+    );
+    if seen.insert(dedupe_key) {
+        out.push(program);
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn linux_program(
+    name: String,
+    version: Option<String>,
+    publisher: Option<String>,
+    install_date: Option<String>,
+    install_location: Option<String>,
+    executable_path: Option<String>,
+    source: &str,
+) -> InstalledProgram {
+    let trust_level = trust::classify_program_trust(
+        &name,
+        publisher.as_deref(),
+        install_location.as_deref(),
+        executable_path.as_deref(),
+    );
+    InstalledProgram {
+        name,
+        version,
+        publisher,
+        install_date,
+        install_location,
+        executable_path,
+        trust_level,
+        trust_label: None,
+        source: source.to_string(),
+    }
+}
+
+/// Install dates use the same `YYYYMMDD` layout as the Windows uninstall keys.
+#[cfg(target_os = "linux")]
+fn format_install_date(timestamp: i64) -> Option<String> {
+    use chrono::{TimeZone, Utc};
+
+    Utc.timestamp_opt(timestamp, 0)
+        .single()
+        .map(|value| value.format("%Y%m%d").to_string())
+}
+
+#[cfg(target_os = "linux")]
+fn modified_date(path: &std::path::Path) -> Option<String> {
+    let modified = std::fs::metadata(path).ok()?.modified().ok()?;
+    let seconds = modified.duration_since(std::time::UNIX_EPOCH).ok()?.as_secs();
+    format_install_date(seconds as i64)
+}
+
+#[cfg(target_os = "linux")]
+fn logical_path(root: &std::path::Path, path: &std::path::Path) -> String {
+    let relative = path.strip_prefix(root).unwrap_or(path);
+    format!("/{}", relative.to_string_lossy().trim_start_matches('/'))
+}
+
+#[cfg(target_os = "linux")]
+fn first_binary<'a>(paths: impl IntoIterator<Item = &'a str>) -> Option<String> {
+    paths
+        .into_iter()
+        .find(|path| {
+            BINARY_DIRS.iter().any(|dir| {
+                path.strip_prefix(dir)
+                    .map(|rest| !rest.is_empty() && !rest.contains('/'))
+                    .unwrap_or(false)
+            })
+        })
+        .map(str::to_string)
+}
+
+#[cfg(target_os = "linux")]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+struct DpkgRecord {
+    package: String,
+    architecture: Option<String>,
+    version: Option<String>,
+    maintainer: Option<String>,
+}
+
+#[cfg(target_os = "linux")]
+fn collect_dpkg_packages(
+    root: &std::path::Path,
+    out: &mut Vec<InstalledProgram>,
+    seen: &mut HashSet<String>,
+) {
+    let Ok(raw) = std::fs::read_to_string(root.join(DPKG_STATUS_PATH)) else {
+        return;
+    };
+    let info_dir = root.join(DPKG_INFO_DIR);
+
+    for record in parse_dpkg_status(&raw) {
+        let list_path = record
+            .architecture
+            .as_ref()
+            .map(|arch| info_dir.join(format!("{}:{}.list", record.package, arch)))
+            .filter(|path| path.is_file())
+            .unwrap_or_else(|| info_dir.join(format!("{}.list", record.package)));
+        let files = std::fs::read_to_string(&list_path).unwrap_or_default();
+        let executable_path = first_binary(files.lines().map(str::trim));
+
+        let program = linux_program(
+            record.package,
+            record.version,
+            record.maintainer,
+            modified_date(&list_path),
+            None,
+            executable_path,
+            "dpkg",
+        );
+        push_program(program, out, seen);
+    }
+}
+
+/// Parses the RFC 822 style paragraphs of `/var/lib/dpkg/status`, keeping only
+/// packages whose status is `install ok installed`.
+#[cfg(target_os = "linux")]
+fn parse_dpkg_status(raw: &str) -> Vec<DpkgRecord> {
+    let mut records = Vec::new();
+    for paragraph in raw.split("\n\n") {
+        let mut record = DpkgRecord::default();
+        let mut installed = false;
+        for line in paragraph.lines() {
+            if line.starts_with(' ') || line.starts_with('\t') {
+                continue;
+            }
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let value = value.trim();
+            match key {
+                "Package" => record.package = value.to_string(),
+                "Status" => installed = value.ends_with(" installed"),
+                "Architecture" => record.architecture = Some(value.to_string()),
+                "Version" => record.version = Some(value.to_string()),
+                "Maintainer" => record.maintainer = Some(value.to_string()),
+                _ => {}
+            }
+        }
+        if installed && !record.package.is_empty() {
+            records.push(record);
+        }
+    }
+    records
+}
+
+#[cfg(target_os = "linux")]
+fn collect_rpm_packages(
+    database: &std::path::Path,
+    out: &mut Vec<InstalledProgram>,
+    seen: &mut HashSet<String>,
+) -> bool {
+    use rusqlite::{Connection, OpenFlags};
+
+    if !database.is_file() {
+        return false;
+    }
+    let Ok(connection) = Connection::open_with_flags(
+        database,
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    ) else {
+        return false;
+    };
+    let Ok(mut statement) = connection.prepare("SELECT blob FROM Packages") else {
+        return false;
+    };
+    let Ok(rows) = statement.query_map([], |row| row.get::<_, Vec<u8>>(0)) else {
+        return false;
+    };
+
+    for blob in rows.flatten() {
+        let Some(header) = RpmHeader::parse(&blob) else {
+            continue;
+        };
+        let Some(name) = header.string(RPMTAG_NAME) else {
+            continue;
+        };
+        // The rpmdb keeps a gpg-pubkey pseudo package per imported signing key.
+        if name == "gpg-pubkey" {
+            continue;
+        }
+
+        let version = header.string(RPMTAG_VERSION).map(|version| {
+            let mut full = match header.int32(RPMTAG_EPOCH) {
+                Some(epoch) => format!("{}:{}", epoch, version),
+                None => version,
+            };
+            if let Some(release) = header.string(RPMTAG_RELEASE) {
+                full = format!("{}-{}", full, release);
+            }
+            full
+        });
+        let publisher = header
+            .string(RPMTAG_VENDOR)
+            .or_else(|| header.string(RPMTAG_PACKAGER));
+        let install_date = header
+            .int32(RPMTAG_INSTALLTIME)
+            .and_then(|value| format_install_date(value as i64));
+        let files = header.file_paths();
+        let executable_path = first_binary(files.iter().map(String::as_str));
+
+        let program = linux_program(
+            name,
+            version,
+            publisher,
+            install_date,
+            None,
+            executable_path,
+            "rpm",
+        );
+        push_program(program, out, seen);
+    }
+    true
+}
+
+#[cfg(target_os = "linux")]
+const RPMTAG_NAME: u32 = 1000;
+#[cfg(target_os = "linux")]
+const RPMTAG_VERSION: u32 = 1001;
+#[cfg(target_os = "linux")]
+const RPMTAG_RELEASE: u32 = 1002;
+#[cfg(target_os = "linux")]
+const RPMTAG_EPOCH: u32 = 1003;
+#[cfg(target_os = "linux")]
+const RPMTAG_INSTALLTIME: u32 = 1008;
+#[cfg(target_os = "linux")]
+const RPMTAG_VENDOR: u32 = 1011;
+#[cfg(target_os = "linux")]
+const RPMTAG_PACKAGER: u32 = 1015;
+#[cfg(target_os = "linux")]
+const RPMTAG_DIRINDEXES: u32 = 1116;
+#[cfg(target_os = "linux")]
+const RPMTAG_BASENAMES: u32 = 1117;
+#[cfg(target_os = "linux")]
+const RPMTAG_DIRNAMES: u32 = 1118;
+
+#[cfg(target_os = "linux")]
+const RPM_TYPE_INT32: u32 = 4;
+#[cfg(target_os = "linux")]
+const RPM_TYPE_STRING: u32 = 6;
+#[cfg(target_os = "linux")]
+const RPM_TYPE_STRING_ARRAY: u32 = 8;
+#[cfg(target_os = "linux")]
+const RPM_TYPE_I18NSTRING: u32 = 9;
+
+#[cfg(target_os = "linux")]
+#[derive(Debug, Clone, Copy)]
+struct RpmIndexEntry {
+    tag: u32,
+    kind: u32,
+    offset: usize,
+    count: usize,
+}
+
+/// Header blob as stored in the `Packages` table of `rpmdb.sqlite`: two
+/// big-endian counts (index entries, data length), the 16-byte index entries
+/// and then the data store they point into.
+#[cfg(target_os = "linux")]
+struct RpmHeader<'a> {
+    entries: Vec<RpmIndexEntry>,
+    data: &'a [u8],
+}
+
+#[cfg(target_os = "linux")]
+impl<'a> RpmHeader<'a> {
+    fn parse(blob: &'a [u8]) -> Option<Self> {
+        let index_count = read_be_u32(blob, 0)? as usize;
+        let data_len = read_be_u32(blob, 4)? as usize;
+        let data_start = 8usize.checked_add(index_count.checked_mul(16)?)?;
+        let data = blob.get(data_start..data_start.checked_add(data_len)?)?;
+
+        let entries = (0..index_count)
+            .map(|index| {
+                let base = 8 + index * 16;
+                Some(RpmIndexEntry {
+                    tag: read_be_u32(blob, base)?,
+                    kind: read_be_u32(blob, base + 4)?,
+                    offset: read_be_u32(blob, base + 8)? as usize,
+                    count: read_be_u32(blob, base + 12)? as usize,
+                })
+            })
+            .collect::<Option<Vec<_>>>()?;
+        Some(Self { entries, data })
+    }
+
+    fn entry(&self, tag: u32) -> Option<&RpmIndexEntry> {
+        self.entries.iter().find(|entry| entry.tag == tag)
+    }
+
+    fn string(&self, tag: u32) -> Option<String> {
+        let entry = self.entry(tag)?;
+        if !matches!(entry.kind, RPM_TYPE_STRING | RPM_TYPE_I18NSTRING | RPM_TYPE_STRING_ARRAY) {
+            return None;
+        }
+        self.strings_at(entry.offset, 1)
+            .into_iter()
+            .next()
+            .filter(|value| !value.is_empty())
+    }
+
+    fn string_array(&self, tag: u32) -> Vec<String> {
+        match self.entry(tag) {
+            Some(entry) if entry.kind == RPM_TYPE_STRING_ARRAY => {
+                self.strings_at(entry.offset, entry.count)
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    fn int32_array(&self, tag: u32) -> Vec<u32> {
+        match self.entry(tag) {
+            Some(entry) if entry.kind == RPM_TYPE_INT32 => (0..entry.count)
+                .map_while(|index| read_be_u32(self.data, entry.offset + index * 4))
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    fn int32(&self, tag: u32) -> Option<u32> {
+        self.int32_array(tag).first().copied()
+    }
+
+    fn strings_at(&self, offset: usize, count: usize) -> Vec<String> {
+        let Some(tail) = self.data.get(offset..) else {
+            return Vec::new();
+        };
+        tail.split(|byte| *byte == 0)
+            .take(count)
+            .map(|value| String::from_utf8_lossy(value).to_string())
+            .collect()
+    }
+
+    fn file_paths(&self) -> Vec<String> {
+        let dirs = self.string_array(RPMTAG_DIRNAMES);
+        let indexes = self.int32_array(RPMTAG_DIRINDEXES);
+        self.string_array(RPMTAG_BASENAMES)
+            .into_iter()
+            .zip(indexes)
+            .filter_map(|(base, index)| {
+                dirs.get(index as usize)
+                    .map(|dir| format!("{}{}", dir, base))
+            })
+            .collect()
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn read_be_u32(bytes: &[u8], offset: usize) -> Option<u32> {
+    let chunk = bytes.get(offset..offset.checked_add(4)?)?;
+    Some(u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+}
+
+#[cfg(target_os = "linux")]
+fn collect_flatpak_apps(
+    root: &std::path::Path,
+    app_dir: &std::path::Path,
+    source: &str,
+    out: &mut Vec<InstalledProgram>,
+    seen: &mut HashSet<String>,
+) {
+    let Ok(entries) = std::fs::read_dir(app_dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let app_id = entry.file_name().to_string_lossy().to_string();
+        let deploy = entry.path().join("current/active");
+        if !deploy.is_dir() {
+            continue;
+        }
+
+        let metainfo = ["metainfo", "appdata"].iter().find_map(|kind| {
+            let extension = if *kind == "metainfo" { "metainfo.xml" } else { "appdata.xml" };
+            std::fs::read_to_string(
+                deploy
+                    .join("files/share")
+                    .join(kind)
+                    .join(format!("{}.{}", app_id, extension)),
+            )
+            .ok()
+        });
+        let details = metainfo
+            .as_deref()
+            .map(parse_appstream_metainfo)
+            .unwrap_or_default();
+
+        let program = linux_program(
+            details.name.unwrap_or_else(|| app_id.clone()),
+            details.version,
+            details.developer,
+            modified_date(&deploy.join("deploy")).or_else(|| modified_date(&deploy)),
+            Some(logical_path(root, &entry.path())),
+            None,
+            source,
+        );
+        push_program(program, out, seen);
+    }
+}
+
+#[cfg(target_os = "linux")]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+struct AppStreamDetails {
+    name: Option<String>,
+    version: Option<String>,
+    developer: Option<String>,
+}
+
+/// Pulls the untranslated name, the newest release and the developer from an
+/// AppStream metainfo file. Releases are listed newest first by convention.
+#[cfg(target_os = "linux")]
+fn parse_appstream_metainfo(raw: &str) -> AppStreamDetails {
+    use regex::Regex;
+
+    let capture = |pattern: &str| {
+        Regex::new(pattern)
+            .ok()
+            .and_then(|regex| regex.captures(raw))
+            .and_then(|captures| captures.get(1))
+            .map(|value| value.as_str().trim().to_string())
+            .filter(|value| !value.is_empty())
+    };
+
+    AppStreamDetails {
+        name: capture(r"<name>([^<]+)</name>"),
+        version: capture(r#"<release[^>]*\sversion="([^"]+)""#),
+        developer: capture(r"<developer_name>([^<]+)</developer_name>")
+            .or_else(|| capture(r"<developer[^>]*>\s*<name>([^<]+)</name>")),
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn collect_snap_packages(
+    root: &std::path::Path,
+    mount_dir: &std::path::Path,
+    out: &mut Vec<InstalledProgram>,
+    seen: &mut HashSet<String>,
+) {
+    let Ok(entries) = std::fs::read_dir(mount_dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let current = entry.path().join("current");
+        let Ok(raw) = std::fs::read_to_string(current.join("meta/snap.yaml")) else {
+            continue;
+        };
+        let metadata = parse_snap_yaml(&raw);
+        let Some(name) = metadata.get("name").cloned() else {
+            continue;
+        };
+
+        let revision_dir = std::fs::read_link(&current)
+            .map(|target| entry.path().join(target))
+            .unwrap_or_else(|_| current.clone());
+        let executable_path = metadata
+            .contains_key("apps")
+            .then(|| format!("/snap/bin/{}", name));
+
+        let program = linux_program(
+            metadata.get("title").cloned().unwrap_or(name),
+            metadata.get("version").cloned(),
+            None,
+            modified_date(&revision_dir),
+            Some(logical_path(root, &entry.path())),
+            executable_path,
+            "snap",
+        );
+        push_program(program, out, seen);
+    }
+}
+
+/// Only top-level scalar keys of `meta/snap.yaml` are needed, so nested blocks
+/// are recorded by key with an empty value.
+#[cfg(target_os = "linux")]
+fn parse_snap_yaml(raw: &str) -> std::collections::HashMap<String, String> {
+    let mut values = std::collections::HashMap::new();
+    for line in raw.lines() {
+        if line.starts_with(' ') || line.starts_with('\t') || line.starts_with('#') {
+            continue;
+        }
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim().trim_matches(|c| c == '"' || c == '\'');
+        values.insert(key.trim().to_string(), value.to_string());
+    }
+    values
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
 pub fn get_installed_programs() -> Vec<InstalledProgram> {
     Vec::new()
 }
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use std::fs;
+
+    use rusqlite::{params, Connection};
+
+    use super::{
+        collect_linux_programs, parse_appstream_metainfo, parse_dpkg_status, RPMTAG_BASENAMES,
+        RPMTAG_DIRINDEXES, RPMTAG_DIRNAMES, RPMTAG_EPOCH, RPMTAG_INSTALLTIME, RPMTAG_NAME,
+        RPMTAG_RELEASE, RPMTAG_VENDOR, RPMTAG_VERSION, RPM_TYPE_INT32, RPM_TYPE_STRING,
+        RPM_TYPE_STRING_ARRAY,
+    };
+    use crate::models::TrustLevel;
+    use crate::test_support::{write, TempDir};
+
+    enum RpmValue<'a> {
+        Int32(Vec<u32>),
+        Strings(Vec<&'a str>),
+    }
+
+    fn rpm_header(tags: &[(u32, RpmValue)]) -> Vec<u8> {
+        let mut index = Vec::new();
+        let mut data = Vec::new();
+        for (tag, value) in tags {
+            let (kind, count) = match value {
+                RpmValue::Int32(values) => {
+                    while data.len() % 4 != 0 {
+                        data.push(0);
+                    }
+                    (RPM_TYPE_INT32, values.len())
+                }
+                RpmValue::Strings(values) if values.len() == 1 => (RPM_TYPE_STRING, 1),
+                RpmValue::Strings(values) => (RPM_TYPE_STRING_ARRAY, values.len()),
+            };
+            for field in [*tag, kind, data.len() as u32, count as u32] {
+                index.extend_from_slice(&field.to_be_bytes());
+            }
+            match value {
+                RpmValue::Int32(values) => {
+                    values.iter().for_each(|v| data.extend_from_slice(&v.to_be_bytes()))
+                }
+                RpmValue::Strings(values) => values.iter().for_each(|v| {
+                    data.extend_from_slice(v.as_bytes());
+                    data.push(0);
+                }),
+            }
+        }
+        let mut blob = Vec::new();
+        blob.extend_from_slice(&(tags.len() as u32).to_be_bytes());
+        blob.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        blob.extend(index);
+        blob.extend(data);
+        blob
+    }
+
+    #[test]
+    fn parse_dpkg_status_keeps_installed_packages_only() {
+        let raw = "Package: curl\nStatus: install ok installed\nArchitecture: amd64\nVersion: 7.88.1-10\nMaintainer: Debian Curl Maintainers <team+curl@tracker.debian.org>\nDescription: command line tool\n transferring data with URL syntax\n\nPackage: old-tool\nStatus: deinstall ok config-files\nVersion: 1.0\n";
+        let records = parse_dpkg_status(raw);
+
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].package, "curl");
+        assert_eq!(records[0].architecture.as_deref(), Some("amd64"));
+        assert_eq!(records[0].version.as_deref(), Some("7.88.1-10"));
+    }
+
+    #[test]
+    fn parse_appstream_metainfo_reads_name_release_and_developer() {
+        let raw = r#"<component type="desktop-application">
+  <id>org.example.Editor</id>
+  <name>Example Editor</name>
+  <name xml:lang="es">Editor de ejemplo</name>
+  <developer id="org.example"><name>Example Org</name></developer>
+  <releases>
+    <release version="2.4.1" date="2024-05-01"/>
+    <release version="2.4.0" date="2024-03-01"/>
+  </releases>
+</component>"#;
+        let details = parse_appstream_metainfo(raw);
+
+        assert_eq!(details.name.as_deref(), Some("Example Editor"));
+        assert_eq!(details.version.as_deref(), Some("2.4.1"));
+        assert_eq!(details.developer.as_deref(), Some("Example Org"));
+    }
+
+    #[test]
+    fn collects_packages_from_all_linux_package_managers() {
+        let root = TempDir::new("programs-inventory");
+        write(
+            &root,
+            "var/lib/dpkg/status",
+            "Package: curl\nStatus: install ok installed\nArchitecture: amd64\nVersion: 7.88.1-10\nMaintainer: Debian Curl Maintainers\n\nPackage: removed\nStatus: deinstall ok config-files\n",
+        );
+        write(
+            &root,
+            "var/lib/dpkg/info/curl:amd64.list",
+            "/.\n/usr\n/usr/bin\n/usr/bin/curl\n/usr/share/doc/curl\n",
+        );
+
+        let database = root.join("var/lib/rpm/rpmdb.sqlite");
+        fs::create_dir_all(database.parent().expect("rpm dir")).expect("create rpm dir");
+        let connection = Connection::open(&database).expect("open rpmdb fixture");
+        connection
+            .execute(
+                "CREATE TABLE Packages (hnum INTEGER PRIMARY KEY AUTOINCREMENT, blob BLOB NOT NULL)",
+                [],
+            )
+            .expect("create packages table");
+        let header = rpm_header(&[
+            (RPMTAG_NAME, RpmValue::Strings(vec!["htop"])),
+            (RPMTAG_VERSION, RpmValue::Strings(vec!["3.3.0"])),
+            (RPMTAG_RELEASE, RpmValue::Strings(vec!["1.fc40"])),
+            (RPMTAG_EPOCH, RpmValue::Int32(vec![1])),
+            (RPMTAG_INSTALLTIME, RpmValue::Int32(vec![1_714_521_600])),
+            (RPMTAG_VENDOR, RpmValue::Strings(vec!["Fedora Project"])),
+            (RPMTAG_DIRNAMES, RpmValue::Strings(vec!["/usr/bin/", "/usr/share/doc/htop/"])),
+            (RPMTAG_BASENAMES, RpmValue::Strings(vec!["htop", "README"])),
+            (RPMTAG_DIRINDEXES, RpmValue::Int32(vec![0, 1])),
+        ]);
+        let pubkey = rpm_header(&[(RPMTAG_NAME, RpmValue::Strings(vec!["gpg-pubkey"]))]);
+        for blob in [header, pubkey] {
+            connection
+                .execute("INSERT INTO Packages (blob) VALUES (?1)", params![blob])
+                .expect("insert header");
+        }
+        drop(connection);
+
+        write(
+            &root,
+            "var/lib/flatpak/app/org.example.Editor/current/active/files/share/metainfo/org.example.Editor.metainfo.xml",
+            "<component><name>Example Editor</name><developer_name>Example Org</developer_name><releases><release version=\"2.4.1\"/></releases></component>",
+        );
+        write(
+            &root,
+            "snap/firefox/42/meta/snap.yaml",
+            "name: firefox\nversion: '128.0'\nsummary: Browser\napps:\n  firefox:\n    command: firefox.launcher\n",
+        );
+        std::os::unix::fs::symlink("42", root.join("snap/firefox/current"))
+            .expect("create snap current link");
+
+        let programs = collect_linux_programs(&root);
+        let find = |source: &str| {
+            programs
+                .iter()
+                .find(|program| program.source == source)
+                .unwrap_or_else(|| panic!("missing {source} program"))
+        };
+
+        assert_eq!(programs.len(), 4);
+
+        let curl = find("dpkg");
+        assert_eq!(curl.name, "curl");
+        assert_eq!(curl.executable_path.as_deref(), Some("/usr/bin/curl"));
+        assert_eq!(curl.trust_level, TrustLevel::SystemNative);
+        assert!(curl.install_date.is_some());
+
+        let htop = find("rpm");
+        assert_eq!(htop.version.as_deref(), Some("1:3.3.0-1.fc40"));
+        assert_eq!(htop.publisher.as_deref(), Some("Fedora Project"));
+        assert_eq!(htop.install_date.as_deref(), Some("20240501"));
+        assert_eq!(htop.executable_path.as_deref(), Some("/usr/bin/htop"));
+
+        let editor = find("Flatpak (system)");
+        assert_eq!(editor.name, "Example Editor");
+        assert_eq!(editor.version.as_deref(), Some("2.4.1"));
+        assert_eq!(editor.publisher.as_deref(), Some("Example Org"));
+        assert_eq!(
+            editor.install_location.as_deref(),
+            Some("/var/lib/flatpak/app/org.example.Editor")
+        );
+
+        let firefox = find("snap");
+        assert_eq!(firefox.version.as_deref(), Some("128.0"));
+        assert_eq!(firefox.install_location.as_deref(), Some("/snap/firefox"));
+        assert_eq!(firefox.executable_path.as_deref(), Some("/snap/bin/firefox"));
+    }
+}
@@ -0,0 +1,256 @@
+//! Per-socket TCP byte counters from the kernel's `sock_diag` netlink
+//! interface. UDP sockets carry no byte counters there, so network rates are
+//! TCP only.
+
+use std::collections::HashMap;
+use std::io;
+
+const NETLINK_SOCK_DIAG: libc::c_int = 4;
+const SOCK_DIAG_BY_FAMILY: u16 = 20;
+const NLMSG_ERROR: u16 = 2;
+const NLMSG_DONE: u16 = 3;
+const INET_DIAG_INFO: u16 = 2;
+
+const NLMSG_HEADER_LEN: usize = 16;
+const INET_DIAG_REQ_LEN: usize = 56;
+const INET_DIAG_MSG_LEN: usize = 72;
+const INODE_OFFSET: usize = 68;
+/// `tcpi_bytes_acked` and `tcpi_bytes_received` in `struct tcp_info`
+/// (kernel 4.1+).
+const BYTES_ACKED_OFFSET: usize = 120;
+const BYTES_RECEIVED_OFFSET: usize = 128;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TcpByteCounters {
+    pub sent: u64,
+    pub received: u64,
+}
+
+/// Byte counters of every IPv4 and IPv6 TCP socket, keyed by socket inode.
+pub fn tcp_byte_counters() -> Result<HashMap<u64, TcpByteCounters>, String> {
+    let socket = NetlinkSocket::open().map_err(|err| format!("sock_diag socket: {err}"))?;
+    let mut counters = HashMap::new();
+    for family in [libc::AF_INET, libc::AF_INET6] {
+        socket
+            .send(&dump_request(family as u8))
+            .map_err(|err| format!("sock_diag request: {err}"))?;
+        let mut buffer = vec![0u8; 32 * 1024];
+        loop {
+            let len = socket
+                .recv(&mut buffer)
+                .map_err(|err| format!("sock_diag receive: {err}"))?;
+            if parse_dump(&buffer[..len], &mut counters)? {
+                break;
+            }
+        }
+    }
+    Ok(counters)
+}
+
+fn dump_request(family: u8) -> Vec<u8> {
+    let len = NLMSG_HEADER_LEN + INET_DIAG_REQ_LEN;
+    let mut request = Vec::with_capacity(len);
+    request.extend_from_slice(&(len as u32).to_ne_bytes());
+    request.extend_from_slice(&SOCK_DIAG_BY_FAMILY.to_ne_bytes());
+    let flags = (libc::NLM_F_REQUEST | libc::NLM_F_DUMP) as u16;
+    request.extend_from_slice(&flags.to_ne_bytes());
+    request.extend_from_slice(&1u32.to_ne_bytes());
+    request.extend_from_slice(&0u32.to_ne_bytes());
+    // inet_diag_req_v2: family, protocol, extensions, pad, states, then a
+    // zeroed socket id to match every socket.
+    request.push(family);
+    request.push(libc::IPPROTO_TCP as u8);
+    request.push(1 << (INET_DIAG_INFO - 1));
+    request.push(0);
+    request.extend_from_slice(&u32::MAX.to_ne_bytes());
+    request.resize(len, 0);
+    request
+}
+
+/// Parses one datagram of a dump reply. Returns true once the dump is done.
+fn parse_dump(
+    mut data: &[u8],
+    counters: &mut HashMap<u64, TcpByteCounters>,
+) -> Result<bool, String> {
+    while data.len() >= NLMSG_HEADER_LEN {
+        let len = read_u32(data, 0) as usize;
+        let kind = read_u16(data, 4);
+        if len < NLMSG_HEADER_LEN || len > data.len() {
+            return Err("truncated sock_diag message".to_string());
+        }
+        match kind {
+            NLMSG_DONE => return Ok(true),
+            NLMSG_ERROR => {
+                let code = data
+                    .get(NLMSG_HEADER_LEN..NLMSG_HEADER_LEN + 4)
+                    .map(|raw| i32::from_ne_bytes(raw.try_into().expect("4 bytes")))
+                    .unwrap_or_default();
+                return Err(format!(
+                    "sock_diag error: {}",
+                    io::Error::from_raw_os_error(-code)
+                ));
+            }
+            SOCK_DIAG_BY_FAMILY => {
+                if let Some((inode, entry)) = parse_socket(&data[NLMSG_HEADER_LEN..len]) {
+                    counters.insert(inode, entry);
+                }
+            }
+            _ => {}
+        }
+        data = &data[align(len).min(data.len())..];
+    }
+    Ok(false)
+}
+
+fn parse_socket(message: &[u8]) -> Option<(u64, TcpByteCounters)> {
+    if message.len() < INET_DIAG_MSG_LEN {
+        return None;
+    }
+    let inode = u64::from(read_u32(message, INODE_OFFSET));
+    let mut attributes = &message[INET_DIAG_MSG_LEN..];
+    while attributes.len() >= 4 {
+        let len = usize::from(read_u16(attributes, 0));
+        let kind = read_u16(attributes, 2);
+        if len < 4 || len > attributes.len() {
+            break;
+        }
+        let payload = &attributes[4..len];
+        if kind == INET_DIAG_INFO && payload.len() >= BYTES_RECEIVED_OFFSET + 8 {
+            return Some((
+                inode,
+                TcpByteCounters {
+                    sent: read_u64(payload, BYTES_ACKED_OFFSET),
+                    received: read_u64(payload, BYTES_RECEIVED_OFFSET),
+                },
+            ));
+        }
+        attributes = &attributes[align(len).min(attributes.len())..];
+    }
+    None
+}
+
+fn align(len: usize) -> usize {
+    (len + 3) & !3
+}
+
+fn read_u16(data: &[u8], offset: usize) -> u16 {
+    u16::from_ne_bytes([data[offset], data[offset + 1]])
+}
+
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    u32::from_ne_bytes(data[offset..offset + 4].try_into().expect("4 bytes"))
+}
+
+fn read_u64(data: &[u8], offset: usize) -> u64 {
+    u64::from_ne_bytes(data[offset..offset + 8].try_into().expect("8 bytes"))
+}
+
+struct NetlinkSocket(libc::c_int);
+
+impl NetlinkSocket {
+    fn open() -> io::Result<Self> {
+        // SAFETY: plain socket(2) call; the descriptor is owned by the
+        // returned value and closed on drop.
+        let fd = unsafe {
+            libc::socket(
+                libc::AF_NETLINK,
+                libc::SOCK_DGRAM | libc::SOCK_CLOEXEC,
+                NETLINK_SOCK_DIAG,
+            )
+        };
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(Self(fd))
+    }
+
+    fn send(&self, request: &[u8]) -> io::Result<()> {
+        // SAFETY: an all-zero sockaddr_nl addresses the kernel.
+        let mut address: libc::sockaddr_nl = unsafe { std::mem::zeroed() };
+        address.nl_family = libc::AF_NETLINK as libc::sa_family_t;
+        // SAFETY: the buffer and address outlive the call and the lengths
+        // match them.
+        let sent = unsafe {
+            libc::sendto(
+                self.0,
+                request.as_ptr().cast(),
+                request.len(),
+                0,
+                (&address as *const libc::sockaddr_nl).cast(),
+                std::mem::size_of::<libc::sockaddr_nl>() as libc::socklen_t,
+            )
+        };
+        if sent < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    fn recv(&self, buffer: &mut [u8]) -> io::Result<usize> {
+        // SAFETY: the kernel writes at most `buffer.len()` bytes.
+        let received = unsafe { libc::recv(self.0, buffer.as_mut_ptr().cast(), buffer.len(), 0) };
+        if received < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(received as usize)
+    }
+}
+
+impl Drop for NetlinkSocket {
+    fn drop(&mut self) {
+        // SAFETY: the descriptor is owned by this value.
+        unsafe {
+            libc::close(self.0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::{
+        parse_dump, TcpByteCounters, BYTES_ACKED_OFFSET, BYTES_RECEIVED_OFFSET, INET_DIAG_INFO,
+        INET_DIAG_MSG_LEN, INODE_OFFSET, NLMSG_DONE, NLMSG_HEADER_LEN, SOCK_DIAG_BY_FAMILY,
+    };
+
+    fn message(kind: u16, body: &[u8]) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(&((NLMSG_HEADER_LEN + body.len()) as u32).to_ne_bytes());
+        out.extend_from_slice(&kind.to_ne_bytes());
+        out.extend_from_slice(&[0; 10]);
+        out.extend_from_slice(body);
+        out
+    }
+
+    fn socket(inode: u32, sent: u64, received: u64) -> Vec<u8> {
+        let mut body = vec![0u8; INET_DIAG_MSG_LEN];
+        body[INODE_OFFSET..INODE_OFFSET + 4].copy_from_slice(&inode.to_ne_bytes());
+        let mut info = vec![0u8; 232];
+        info[BYTES_ACKED_OFFSET..BYTES_ACKED_OFFSET + 8].copy_from_slice(&sent.to_ne_bytes());
+        info[BYTES_RECEIVED_OFFSET..BYTES_RECEIVED_OFFSET + 8]
+            .copy_from_slice(&received.to_ne_bytes());
+        body.extend_from_slice(&((4 + info.len()) as u16).to_ne_bytes());
+        body.extend_from_slice(&INET_DIAG_INFO.to_ne_bytes());
+        body.extend_from_slice(&info);
+        message(SOCK_DIAG_BY_FAMILY, &body)
+    }
+
+    #[test]
+    fn parse_dump_reads_tcp_info_byte_counters_per_inode() {
+        let mut datagram = socket(41, 1_000, 52_000);
+        datagram.extend(socket(42, 7, 9));
+        let mut counters = HashMap::new();
+        assert!(!parse_dump(&datagram, &mut counters).expect("parse sockets"));
+        assert!(parse_dump(&message(NLMSG_DONE, &[0; 4]), &mut counters).expect("parse done"));
+
+        assert_eq!(
+            counters.get(&41),
+            Some(&TcpByteCounters {
+                sent: 1_000,
+                received: 52_000
+            })
+        );
+        assert_eq!(counters.get(&42).map(|entry| entry.received), Some(9));
+    }
+}
@@ -75,9 +75,11 @@ fn collect_run_key(
         out.push(StartupProcess {
             name,
             command,
-            location: executable.unwrap_or_default(),
+            location: executable.clone().unwrap_or_default(),
             source: source.to_string(),
             trust_level,
+            executable,
+            yara_matches: Vec::new(),
         });
     }
 }
@@ -121,11 +123,550 @@ fn collect_startup_folder(
             location: location.clone(),
             source: source.to_string(),
             trust_level,
+            executable: Some(location),
+            yara_matches: Vec::new(),
         });
     }
 }
 
-#[cfg(not(target_os = "windows"))]
+#[cfg(target_os = "linux")]
+const SYSTEM_UNIT_DIRS: &[&str] = &[
+    "etc/systemd/system",
+    "run/systemd/system",
+    "usr/local/lib/systemd/system",
+    "usr/lib/systemd/system",
+    "lib/systemd/system",
+];
+
+#[cfg(target_os = "linux")]
+const USER_UNIT_DIRS: &[&str] = &[
+    "etc/systemd/user",
+    "usr/local/lib/systemd/user",
+    "usr/lib/systemd/user",
+];
+
+#[cfg(target_os = "linux")]
+const EXECUTABLE_SEARCH_DIRS: &[&str] = &[
+    "usr/local/sbin",
+    "usr/local/bin",
+    "usr/sbin",
+    "usr/bin",
+    "sbin",
+    "bin",
+];
+
+#[cfg(target_os = "linux")]
+pub fn get_startup_processes() -> Vec<StartupProcess> {
+    collect_linux_startup_processes(Path::new("/"))
+}
+
+#[cfg(target_os = "linux")]
+pub fn collect_linux_startup_processes(root: &Path) -> Vec<StartupProcess> {
+    use super::persistence_collector::user_homes;
+
+    let mut items = Vec::new();
+    let mut seen = HashSet::new();
+    let homes: Vec<(String, std::path::PathBuf)> = user_homes(root)
+        .into_iter()
+        .filter(|home| home.is_dir())
+        .map(|home| {
+            let user = home
+                .file_name()
+                .map(|value| value.to_string_lossy().to_string())
+                .unwrap_or_default();
+            (user, home)
+        })
+        .collect();
+
+    collect_xdg_autostart(
+        root,
+        &root.join("etc/xdg/autostart"),
+        "XDG Autostart (All Users)",
+        &mut items,
+        &mut seen,
+    );
+    for (user, home) in &homes {
+        collect_xdg_autostart(
+            root,
+            &home.join(".config/autostart"),
+            &format!("XDG Autostart ({})", user),
+            &mut items,
+            &mut seen,
+        );
+    }
+
+    let system_unit_dirs: Vec<_> = SYSTEM_UNIT_DIRS.iter().map(|dir| root.join(dir)).collect();
+    collect_enabled_units(
+        root,
+        &root.join("etc/systemd/system"),
+        &system_unit_dirs,
+        "systemd (system)",
+        &mut items,
+        &mut seen,
+    );
+    let user_unit_dirs: Vec<_> = USER_UNIT_DIRS.iter().map(|dir| root.join(dir)).collect();
+    collect_enabled_units(
+        root,
+        &root.join("etc/systemd/user"),
+        &user_unit_dirs,
+        "systemd (user, all users)",
+        &mut items,
+        &mut seen,
+    );
+    for (user, home) in &homes {
+        let personal_dir = home.join(".config/systemd/user");
+        let mut search_dirs = vec![personal_dir.clone()];
+        search_dirs.extend(user_unit_dirs.iter().cloned());
+        collect_enabled_units(
+            root,
+            &personal_dir,
+            &search_dirs,
+            &format!("systemd (user: {})", user),
+            &mut items,
+            &mut seen,
+        );
+    }
+
+    collect_cron_reboot(root, &root.join("etc/crontab"), true, &mut items, &mut seen);
+    for path in list_files(&root.join("etc/cron.d")) {
+        collect_cron_reboot(root, &path, true, &mut items, &mut seen);
+    }
+    for dir in ["var/spool/cron/crontabs", "var/spool/cron"] {
+        for path in list_files(&root.join(dir)) {
+            collect_cron_reboot(root, &path, false, &mut items, &mut seen);
+        }
+    }
+
+    collect_rc_local(root, &root.join("etc/rc.local"), &mut items, &mut seen);
+
+    items.sort_by_key(|item| item.name.to_lowercase());
+    items
+}
+
+#[cfg(target_os = "linux")]
+fn collect_xdg_autostart(
+    root: &Path,
+    dir: &Path,
+    source: &str,
+    out: &mut Vec<StartupProcess>,
+    seen: &mut HashSet<String>,
+) {
+    for path in list_files(dir) {
+        if path.extension().and_then(|value| value.to_str()) != Some("desktop") {
+            continue;
+        }
+        let Ok(raw) = fs::read_to_string(&path) else {
+            continue;
+        };
+        let Some(entry) = parse_desktop_entry(&raw) else {
+            continue;
+        };
+        let name = entry.name.unwrap_or_else(|| {
+            path.file_stem()
+                .map(|value| value.to_string_lossy().to_string())
+                .unwrap_or_default()
+        });
+        push_linux_entry(root, name, entry.exec, &path, source, out, seen);
+    }
+}
+
+#[cfg(target_os = "linux")]
+#[derive(Debug, Default)]
+struct DesktopEntry {
+    name: Option<String>,
+    exec: String,
+}
+
+#[cfg(target_os = "linux")]
+fn parse_desktop_entry(raw: &str) -> Option<DesktopEntry> {
+    let mut in_entry = false;
+    let mut name = None;
+    let mut exec = None;
+    for line in raw.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('[') {
+            in_entry = trimmed == "[Desktop Entry]";
+            continue;
+        }
+        if !in_entry {
+            continue;
+        }
+        let Some((key, value)) = trimmed.split_once('=') else {
+            continue;
+        };
+        let value = value.trim();
+        match key.trim() {
+            "Name" => name = Some(value.to_string()),
+            "Exec" => exec = Some(strip_desktop_field_codes(value)),
+            "Hidden" if value.eq_ignore_ascii_case("true") => return None,
+            "X-GNOME-Autostart-enabled" if value.eq_ignore_ascii_case("false") => return None,
+            _ => {}
+        }
+    }
+    Some(DesktopEntry {
+        name,
+        exec: exec.filter(|value| !value.is_empty())?,
+    })
+}
+
+#[cfg(target_os = "linux")]
+fn strip_desktop_field_codes(exec: &str) -> String {
+    exec.split_whitespace()
+        .filter(|token| !(token.len() == 2 && token.starts_with('%') && token != &"%%"))
+        .map(|token| token.replace("%%", "%"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(target_os = "linux")]
+fn collect_enabled_units(
+    root: &Path,
+    config_dir: &Path,
+    search_dirs: &[std::path::PathBuf],
+    source: &str,
+    out: &mut Vec<StartupProcess>,
+    seen: &mut HashSet<String>,
+) {
+    let Ok(entries) = fs::read_dir(config_dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let wants_dir = entry.path();
+        let is_dependency_dir = wants_dir
+            .file_name()
+            .and_then(|value| value.to_str())
+            .map(|value| value.ends_with(".wants") || value.ends_with(".requires"))
+            .unwrap_or(false);
+        if !is_dependency_dir || !wants_dir.is_dir() {
+            continue;
+        }
+
+        for link in list_files(&wants_dir) {
+            let Some(unit_name) = link.file_name().and_then(|value| value.to_str()) else {
+                continue;
+            };
+            if !unit_name.ends_with(".service") {
+                continue;
+            }
+            let Some(unit_path) = resolve_unit_file(root, &link, unit_name, search_dirs) else {
+                continue;
+            };
+            let Ok(raw) = fs::read_to_string(&unit_path) else {
+                continue;
+            };
+            let Some(exec_start) = parse_unit_exec_start(&raw) else {
+                continue;
+            };
+            push_linux_entry(
+                root,
+                unit_name.to_string(),
+                exec_start,
+                &unit_path,
+                source,
+                out,
+                seen,
+            );
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn resolve_unit_file(
+    root: &Path,
+    link: &Path,
+    unit_name: &str,
+    search_dirs: &[std::path::PathBuf],
+) -> Option<std::path::PathBuf> {
+    if let Ok(target) = fs::read_link(link) {
+        let candidate = if target.is_absolute() {
+            root.join(target.strip_prefix("/").unwrap_or(&target))
+        } else {
+            link.parent()
+                .map(|parent| parent.join(&target))
+                .unwrap_or(target)
+        };
+        if candidate.is_file() {
+            return Some(candidate);
+        }
+    } else if link.is_file() {
+        return Some(link.to_path_buf());
+    }
+
+    let template_name = unit_name
+        .split_once('@')
+        .map(|(prefix, _)| format!("{}@.service", prefix));
+    search_dirs.iter().find_map(|dir| {
+        std::iter::once(unit_name.to_string())
+            .chain(template_name.clone())
+            .map(|name| dir.join(name))
+            .find(|candidate| candidate.is_file())
+    })
+}
+
+#[cfg(target_os = "linux")]
+fn parse_unit_exec_start(raw: &str) -> Option<String> {
+    let mut in_service = false;
+    let mut exec_start = None;
+    for line in raw.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('[') {
+            in_service = trimmed == "[Service]";
+            continue;
+        }
+        if !in_service {
+            continue;
+        }
+        if let Some(value) = trimmed.strip_prefix("ExecStart=") {
+            let value = value.trim_start_matches(['-', '@', ':', '+', '!']).trim();
+            exec_start = (!value.is_empty())
+                .then(|| value.to_string())
+                .or(exec_start);
+        }
+    }
+    exec_start
+}
+
+#[cfg(target_os = "linux")]
+fn collect_cron_reboot(
+    root: &Path,
+    path: &Path,
+    has_user_field: bool,
+    out: &mut Vec<StartupProcess>,
+    seen: &mut HashSet<String>,
+) {
+    let Ok(raw) = fs::read_to_string(path) else {
+        return;
+    };
+    for line in raw.lines() {
+        let Some(rest) = line.trim().strip_prefix("@reboot") else {
+            continue;
+        };
+        if !rest.starts_with(char::is_whitespace) {
+            continue;
+        }
+        let rest = rest.trim();
+        let command = if has_user_field {
+            rest.split_once(char::is_whitespace)
+                .map(|(_, command)| command.trim())
+                .unwrap_or_default()
+        } else {
+            rest
+        };
+        if command.is_empty() {
+            continue;
+        }
+        let name = command_display_name(root, command);
+        push_linux_entry(
+            root,
+            name,
+            command.to_string(),
+            path,
+            "cron @reboot",
+            out,
+            seen,
+        );
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn collect_rc_local(
+    root: &Path,
+    path: &Path,
+    out: &mut Vec<StartupProcess>,
+    seen: &mut HashSet<String>,
+) {
+    let Ok(raw) = fs::read_to_string(path) else {
+        return;
+    };
+    for line in raw.lines() {
+        let command = line.trim();
+        if command.is_empty()
+            || command.starts_with('#')
+            || command.split_whitespace().next() == Some("exit")
+        {
+            continue;
+        }
+        let name = command_display_name(root, command);
+        push_linux_entry(root, name, command.to_string(), path, "rc.local", out, seen);
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn push_linux_entry(
+    root: &Path,
+    name: String,
+    command: String,
+    definition: &Path,
+    source: &str,
+    out: &mut Vec<StartupProcess>,
+    seen: &mut HashSet<String>,
+) {
+    let location = format!(
+        "/{}",
+        definition
+            .strip_prefix(root)
+            .unwrap_or(definition)
+            .to_string_lossy()
+            .trim_start_matches('/')
+    );
+    let dedupe_key = format!("{}|{}|{}", name.to_lowercase(), command, location);
+    if !seen.insert(dedupe_key) {
+        return;
+    }
+    let executable = resolve_startup_executable(root, &command);
+    let trust_level = trust::classify_startup_trust(&name, executable.as_deref());
+
+    out.push(StartupProcess {
+        name,
+        command,
+        location,
+        source: source.to_string(),
+        trust_level,
+        executable,
+        yara_matches: Vec::new(),
+    });
+}
+
+#[cfg(target_os = "linux")]
+fn command_display_name(root: &Path, command: &str) -> String {
+    resolve_startup_executable(root, command)
+        .and_then(|path| {
+            Path::new(&path)
+                .file_name()
+                .map(|value| value.to_string_lossy().to_string())
+        })
+        .unwrap_or_else(|| command.to_string())
+}
+
+/// Skips `env` wrappers and leading `VAR=value` assignments, then resolves
+/// bare program names against the usual binary directories below `root`.
+#[cfg(target_os = "linux")]
+fn resolve_startup_executable(root: &Path, command: &str) -> Option<String> {
+    let program = command
+        .split_whitespace()
+        .map(|token| token.trim_matches(|c| c == '"' || c == '\''))
+        .find(|token| {
+            !(token.is_empty()
+                || *token == "env"
+                || token.ends_with("/env")
+                || (token.contains('=') && !token.starts_with('/')))
+        })?;
+    if program.starts_with('/') {
+        return Some(program.to_string());
+    }
+    EXECUTABLE_SEARCH_DIRS
+        .iter()
+        .find(|dir| root.join(dir).join(program).is_file())
+        .map(|dir| format!("/{}/{}", dir, program))
+        .or_else(|| Some(program.to_string()))
+}
+
+#[cfg(target_os = "linux")]
+fn list_files(dir: &Path) -> Vec<std::path::PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<_> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() || path.is_symlink())
+        .collect();
+    files.sort();
+    files
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
 pub fn get_startup_processes() -> Vec<StartupProcess> {
     Vec::new()
 }
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use std::fs;
+
+    use super::collect_linux_startup_processes;
+    use crate::models::TrustLevel;
+    use crate::test_support::{write, TempDir};
+
+    #[test]
+    fn collects_linux_startup_sources_from_fixture_tree() {
+        let root = TempDir::new("startup-fixture");
+        write(&root, "usr/bin/nm-applet", "");
+        write(
+            &root,
+            "etc/xdg/autostart/nm-applet.desktop",
+            "[Desktop Entry]\nName=Network\nExec=nm-applet %U\n",
+        );
+        write(
+            &root,
+            "etc/xdg/autostart/disabled.desktop",
+            "[Desktop Entry]\nName=Disabled\nExec=/usr/bin/foo\nHidden=true\n",
+        );
+        write(
+            &root,
+            "home/alice/.config/autostart/sync.desktop",
+            "[Desktop Entry]\nName=Sync\nExec=/home/alice/.local/bin/sync --daemon\n",
+        );
+        write(
+            &root,
+            "usr/lib/systemd/system/ssh.service",
+            "[Unit]\nDescription=SSH\n[Service]\nExecStart=-/usr/sbin/sshd -D\n",
+        );
+        fs::create_dir_all(root.join("etc/systemd/system/multi-user.target.wants"))
+            .expect("create wants dir");
+        std::os::unix::fs::symlink(
+            "/usr/lib/systemd/system/ssh.service",
+            root.join("etc/systemd/system/multi-user.target.wants/ssh.service"),
+        )
+        .expect("create unit symlink");
+        write(
+            &root,
+            "etc/cron.d/updater",
+            "SHELL=/bin/sh\n@reboot root /opt/updater/run.sh --quiet\n*/5 * * * * root true\n",
+        );
+        write(
+            &root,
+            "var/spool/cron/crontabs/alice",
+            "@reboot /tmp/.cache/agent\n@rebootfoo /tmp/.cache/other\n",
+        );
+        write(
+            &root,
+            "etc/rc.local",
+            "#!/bin/sh -e\n/usr/local/bin/boot-hook\nexit 0\n",
+        );
+
+        let items = collect_linux_startup_processes(&root);
+        let find = |name: &str| {
+            items
+                .iter()
+                .find(|item| item.name == name)
+                .unwrap_or_else(|| panic!("missing startup entry {name}"))
+        };
+
+        assert_eq!(items.len(), 6);
+        assert!(items.iter().all(|item| item.name != "Disabled"));
+
+        let network = find("Network");
+        assert_eq!(network.command, "nm-applet");
+        assert_eq!(network.source, "XDG Autostart (All Users)");
+        assert_eq!(network.trust_level, TrustLevel::SystemNative);
+
+        let sync = find("Sync");
+        assert_eq!(sync.source, "XDG Autostart (alice)");
+        assert_eq!(sync.location, "/home/alice/.config/autostart/sync.desktop");
+        assert_eq!(sync.trust_level, TrustLevel::Unknown);
+
+        let ssh = find("ssh.service");
+        assert_eq!(ssh.command, "/usr/sbin/sshd -D");
+        assert_eq!(ssh.source, "systemd (system)");
+        assert_eq!(ssh.location, "/usr/lib/systemd/system/ssh.service");
+
+        let updater = find("run.sh");
+        assert_eq!(updater.command, "/opt/updater/run.sh --quiet");
+        assert_eq!(updater.source, "cron @reboot");
+        assert_eq!(updater.location, "/etc/cron.d/updater");
+
+        assert_eq!(find("agent").location, "/var/spool/cron/crontabs/alice");
+        assert_eq!(find("boot-hook").source, "rc.local");
+    }
+}
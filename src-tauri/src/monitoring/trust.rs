@@ -24,6 +24,19 @@ const TRUSTED_PUBLISHERS: &[&str] = &[
     "jetbrains",
 ];
 
+const LINUX_SYSTEM_PREFIXES: &[&str] = &[
+    "/usr/bin/",
+    "/usr/sbin/",
+    "/usr/lib/",
+    "/usr/lib64/",
+    "/usr/libexec/",
+    "/usr/share/",
+    "/bin/",
+    "/sbin/",
+    "/lib/",
+    "/lib64/",
+];
+
 const TRUSTED_EXECUTABLE_NAMES: &[&str] = &[
     "chatgpt.exe",
     "nyx-monitor.exe",
@@ -55,6 +68,17 @@ pub fn classify_process_trust(name: &str, path: Option<&str>, is_signed: Option<
     TrustLevel::Unknown
 }
 
+/// Startup entries pointing into the distribution's own directories are
+/// listed as system items. Running processes never get this shortcut: a
+/// binary under /usr/bin still has to earn trust so the detectors see it.
+#[cfg(target_os = "linux")]
+pub fn classify_startup_trust(name: &str, executable: Option<&str>) -> TrustLevel {
+    if is_linux_system_path(executable) {
+        return TrustLevel::SystemNative;
+    }
+    classify_process_trust(name, executable, None)
+}
+
 pub fn classify_program_trust(
     name: &str,
     publisher: Option<&str>,
@@ -65,6 +89,9 @@ pub fn classify_program_trust(
     if is_windows_path(executable_path) || is_windows_path(install_location) {
         return TrustLevel::WindowsNative;
     }
+    if is_linux_system_path(executable_path) {
+        return TrustLevel::SystemNative;
+    }
 
     let normalized_publisher = publisher.unwrap_or_default().to_lowercase();
     if TRUSTED_PUBLISHERS
@@ -85,6 +112,13 @@ pub fn is_windows_path(path: Option<&str>) -> bool {
         || lower.contains("\\windows\\syswow64\\")
 }
 
+pub fn is_linux_system_path(path: Option<&str>) -> bool {
+    let value = path.unwrap_or_default();
+    LINUX_SYSTEM_PREFIXES
+        .iter()
+        .any(|prefix| value.starts_with(prefix))
+}
+
 fn is_user_program_path(path: Option<&str>) -> bool {
     let lower = path.unwrap_or_default().to_lowercase();
     lower.contains("\\program files\\")
@@ -158,3 +192,25 @@ pub fn program_match_keys(executable_path: Option<&str>, install_location: Optio
     }
     keys
 }
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::{classify_process_trust, classify_startup_trust};
+    use crate::models::TrustLevel;
+
+    #[test]
+    fn linux_system_paths_are_only_trusted_for_startup_entries() {
+        assert_eq!(
+            classify_process_trust("curl", Some("/usr/bin/curl"), None),
+            TrustLevel::Unknown
+        );
+        assert_eq!(
+            classify_startup_trust("curl", Some("/usr/bin/curl")),
+            TrustLevel::SystemNative
+        );
+        assert_eq!(
+            classify_startup_trust("agent", Some("/tmp/.cache/agent")),
+            TrustLevel::Unknown
+        );
+    }
+}
@@ -0,0 +1,77 @@
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use crate::app_state::RuntimeState;
+use crate::detection::yara::YaraRuleSet;
+use crate::models::YaraMatch;
+
+use super::events::emit_yara_match_event;
+use super::hash_worker::hash_file_cached;
+
+const IDLE_POLL: Duration = Duration::from_millis(500);
+/// Only the head of very large files is scanned; `filesize` conditions still
+/// see the real size.
+const MAX_SCAN_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Scans queued files against the loaded YARA rules on a dedicated thread.
+/// Results are cached by content hash, so the same binary launched from
+/// several paths or many times is only read once per rule set.
+pub fn spawn(state: RuntimeState) {
+    let health = state.clone();
+    let spawned = std::thread::Builder::new()
+        .name("nyx-yara-worker".to_string())
+        .spawn(move || loop {
+            match state.next_yara_job() {
+                Some(path) => {
+                    let started = Instant::now();
+                    match scan_job(&state, &path) {
+                        Ok(()) => state.record_sensor_success(
+                            "yara",
+                            Some(started.elapsed().as_secs_f32() * 1000.0),
+                        ),
+                        Err(err) => state.record_sensor_error("yara", &err),
+                    }
+                }
+                None => std::thread::sleep(IDLE_POLL),
+            }
+        });
+    if let Err(err) = spawned {
+        health.record_sensor_error("yara", &format!("failed to start yara worker: {err}"));
+    }
+}
+
+fn scan_job(state: &RuntimeState, path: &str) -> Result<(), String> {
+    let Some(sha256) = hash_file_cached(state, path)? else {
+        return Ok(());
+    };
+    state.complete_hash_job(path, Some(sha256.clone()));
+    if state.cached_yara_matches(&sha256).is_some() {
+        return Ok(());
+    }
+
+    let rules = state.detection_rules();
+    // Unreadable files are cached as clean so they are not retried every tick.
+    let result = scan_file(rules.yara(), Path::new(path));
+    let matches = result.as_ref().cloned().unwrap_or_default();
+    if !matches.is_empty() {
+        emit_yara_match_event(state, path, &sha256, &matches);
+    }
+    state.complete_yara_scan(&sha256, matches);
+    result.map(|_| ())
+}
+
+pub fn scan_file(rules: &YaraRuleSet, path: &Path) -> Result<Vec<YaraMatch>, String> {
+    let file = File::open(path)
+        .map_err(|err| format!("failed opening file for scanning {}: {err}", path.display()))?;
+    let filesize = file
+        .metadata()
+        .map_err(|err| format!("failed reading metadata for {}: {err}", path.display()))?
+        .len();
+    let mut data = Vec::with_capacity(filesize.min(MAX_SCAN_BYTES) as usize);
+    file.take(MAX_SCAN_BYTES)
+        .read_to_end(&mut data)
+        .map_err(|err| format!("failed reading file for scanning {}: {err}", path.display()))?;
+    Ok(rules.scan(&data, filesize))
+}
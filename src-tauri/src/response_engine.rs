@@ -2,7 +2,7 @@ use std::process::Command;
 
 use crate::models::ResponseActionType;
 
-const CRITICAL_PROCESS_NAMES: &[&str] = &[
+pub(crate) const CRITICAL_PROCESS_NAMES: &[&str] = &[
     "system",
     "registry",
     "smss.exe",
@@ -1,28 +1,36 @@
 use std::cmp::min;
+use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
 
-use anyhow::{Context, Result};
+use anyhow::{anyhow, Context, Result};
 use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
 
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 
+use crate::detection::sequence::SequenceState;
 use crate::models::{
-    Alert, AlertStatus, EventEnvelope, KnownEntity, KnownEntityKind, ResponseActionRecord, TrustLevel,
+    Alert, AppBaseline, BaselineConfig, BinaryPrevalence, DetectionProfile, EventEnvelope,
+    KnownEntity, KnownEntityKind, ResponseActionRecord, SuppressionRule, TrustLevel,
 };
 
 #[derive(Debug)]
 pub struct AlertStore {
     path: PathBuf,
     alerts: Vec<Alert>,
+    /// Resolved and archived alerts kept as history; open ones never count.
+    max_closed: usize,
 }
 
 impl AlertStore {
     pub fn load(path: PathBuf) -> Result<Self> {
+        let max_closed = 2_000;
         if !path.exists() {
             return Ok(Self {
                 path,
                 alerts: Vec::new(),
+                max_closed,
             });
         }
 
@@ -30,7 +38,11 @@ impl AlertStore {
             .with_context(|| format!("failed to read alert store from {}", path.display()))?;
         let alerts: Vec<Alert> = serde_json::from_str(&raw).unwrap_or_default();
 
-        Ok(Self { path, alerts })
+        Ok(Self {
+            path,
+            alerts,
+            max_closed,
+        })
     }
 
     pub fn push(&mut self, alert: Alert) -> Result<()> {
@@ -38,11 +50,12 @@ impl AlertStore {
         self.persist()
     }
 
+    /// Alerts still awaiting a verdict: active or acknowledged.
     pub fn active_alerts(&self) -> Vec<Alert> {
         let mut list: Vec<Alert> = self
             .alerts
             .iter()
-            .filter(|a| a.status == AlertStatus::Active)
+            .filter(|a| a.status.is_open())
             .cloned()
             .collect();
         list.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
@@ -55,27 +68,43 @@ impl AlertStore {
         list
     }
 
-    pub fn delete(&mut self, alert_id: &str) -> Result<bool> {
-        let before = self.alerts.len();
-        self.alerts.retain(|alert| alert.id != alert_id);
-        let changed = self.alerts.len() != before;
-        if changed {
-            self.persist()?;
-        }
-        Ok(changed)
+    /// Applies `apply` to one alert and persists the result. Nothing is
+    /// written when `apply` fails.
+    pub fn update<F>(&mut self, alert_id: &str, apply: F) -> Result<Alert>
+    where
+        F: FnOnce(&mut Alert) -> Result<()>,
+    {
+        let alert = self
+            .alerts
+            .iter_mut()
+            .find(|alert| alert.id == alert_id)
+            .ok_or_else(|| anyhow!("unknown alert '{alert_id}'"))?;
+        let mut updated = alert.clone();
+        apply(&mut updated)?;
+        *alert = updated.clone();
+        self.persist()?;
+        Ok(updated)
     }
 
-    pub fn delete_all_active(&mut self) -> Result<usize> {
-        let before = self.alerts.len();
-        self.alerts.retain(|alert| alert.status != AlertStatus::Active);
-        let deleted = before.saturating_sub(self.alerts.len());
-        if deleted > 0 {
+    /// Applies `apply` to every alert matching `filter`; returns the updated alerts.
+    pub fn update_all<P, F>(&mut self, filter: P, mut apply: F) -> Result<Vec<Alert>>
+    where
+        P: Fn(&Alert) -> bool,
+        F: FnMut(&mut Alert),
+    {
+        let mut updated = Vec::new();
+        for alert in self.alerts.iter_mut().filter(|alert| filter(alert)) {
+            apply(alert);
+            updated.push(alert.clone());
+        }
+        if !updated.is_empty() {
             self.persist()?;
         }
-        Ok(deleted)
+        Ok(updated)
     }
 
-    fn persist(&self) -> Result<()> {
+    fn persist(&mut self) -> Result<()> {
+        self.prune_closed();
         if let Some(parent) = self.path.parent() {
             fs::create_dir_all(parent).with_context(|| {
                 format!("failed creating alert store directory {}", parent.display())
@@ -89,6 +118,23 @@ impl AlertStore {
         })?;
         Ok(())
     }
+
+    /// Drops the oldest closed alerts beyond `max_closed`.
+    fn prune_closed(&mut self) {
+        let mut closed: Vec<String> = self
+            .alerts
+            .iter()
+            .filter(|alert| !alert.status.is_open())
+            .map(|alert| alert.timestamp.clone())
+            .collect();
+        if closed.len() <= self.max_closed {
+            return;
+        }
+        closed.sort_unstable();
+        let cutoff = &closed[closed.len() - self.max_closed];
+        self.alerts
+            .retain(|alert| alert.status.is_open() || alert.timestamp >= *cutoff);
+    }
 }
 
 #[derive(Debug)]
@@ -217,6 +263,11 @@ pub struct EventStore {
     max_events: usize,
 }
 
+#[derive(Debug)]
+pub struct HashCacheStore {
+    path: PathBuf,
+}
+
 #[derive(Debug)]
 pub struct ResponseActionStore {
     path: PathBuf,
@@ -286,6 +337,375 @@ impl ResponseActionStore {
     }
 }
 
+#[derive(Debug)]
+pub struct BaselineStore {
+    path: PathBuf,
+    config: BaselineConfig,
+    baselines: HashMap<String, AppBaseline>,
+    dirty: bool,
+}
+
+/// On-disk layout of the baseline store. Files written before the learning
+/// settings were persisted hold only the baseline list.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum BaselineFile {
+    Legacy(Vec<AppBaseline>),
+    Current {
+        #[serde(default)]
+        config: BaselineConfig,
+        baselines: Vec<AppBaseline>,
+    },
+}
+
+#[derive(Serialize)]
+struct BaselineFileRef<'a> {
+    config: &'a BaselineConfig,
+    baselines: Vec<AppBaseline>,
+}
+
+impl BaselineStore {
+    pub fn load(path: PathBuf) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self {
+                path,
+                config: BaselineConfig::default(),
+                baselines: HashMap::new(),
+                dirty: false,
+            });
+        }
+
+        let raw = fs::read_to_string(&path)
+            .with_context(|| format!("failed to read baseline store from {}", path.display()))?;
+        let (config, baselines) = match serde_json::from_str(&raw) {
+            Ok(BaselineFile::Current { config, baselines }) => (config, baselines),
+            Ok(BaselineFile::Legacy(baselines)) => (BaselineConfig::default(), baselines),
+            Err(_) => (BaselineConfig::default(), Vec::new()),
+        };
+
+        Ok(Self {
+            path,
+            config,
+            baselines: baselines
+                .into_iter()
+                .map(|baseline| (baseline.app_key.clone(), baseline))
+                .collect(),
+            dirty: false,
+        })
+    }
+
+    pub fn config(&self) -> &BaselineConfig {
+        &self.config
+    }
+
+    pub fn set_config(&mut self, config: BaselineConfig) -> Result<()> {
+        self.config = config;
+        self.dirty = true;
+        self.flush()
+    }
+
+    pub fn get(&self, app_key: &str) -> Option<&AppBaseline> {
+        self.baselines.get(app_key)
+    }
+
+    /// Changes made through this handle are written on the next [`Self::flush`].
+    pub fn entry(&mut self, app_key: &str) -> Option<&mut AppBaseline> {
+        self.dirty = true;
+        self.baselines.get_mut(app_key)
+    }
+
+    pub fn insert(&mut self, baseline: AppBaseline) {
+        self.dirty = true;
+        self.baselines.insert(baseline.app_key.clone(), baseline);
+    }
+
+    pub fn list(&self) -> Vec<AppBaseline> {
+        let mut list: Vec<AppBaseline> = self.baselines.values().cloned().collect();
+        list.sort_by(|a, b| a.app_key.cmp(&b.app_key));
+        list
+    }
+
+    pub fn remove(&mut self, app_key: &str) -> Result<bool> {
+        let removed = self.baselines.remove(app_key).is_some();
+        if removed {
+            self.dirty = true;
+            self.flush()?;
+        }
+        Ok(removed)
+    }
+
+    pub fn flush(&mut self) -> Result<()> {
+        if !self.dirty {
+            return Ok(());
+        }
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).with_context(|| {
+                format!("failed creating baseline store directory {}", parent.display())
+            })?;
+        }
+
+        let file = BaselineFileRef {
+            config: &self.config,
+            baselines: self.list(),
+        };
+        let payload =
+            serde_json::to_string_pretty(&file).context("failed serializing baselines")?;
+        fs::write(&self.path, payload).with_context(|| {
+            format!("failed writing baseline store to {}", self.path.display())
+        })?;
+        self.dirty = false;
+        Ok(())
+    }
+}
+
+/// Custom detection profiles and the id of the active one. Built-in presets
+/// are never written.
+#[derive(Debug)]
+pub struct ProfileStore {
+    path: PathBuf,
+    data: ProfileFile,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ProfileFile {
+    #[serde(default)]
+    active: Option<String>,
+    #[serde(default)]
+    profiles: Vec<DetectionProfile>,
+}
+
+impl ProfileStore {
+    pub fn load(path: PathBuf) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self {
+                path,
+                data: ProfileFile::default(),
+            });
+        }
+
+        let raw = fs::read_to_string(&path)
+            .with_context(|| format!("failed to read profile store from {}", path.display()))?;
+        let mut data: ProfileFile = serde_json::from_str(&raw).unwrap_or_default();
+        data.profiles.retain(|profile| !profile.builtin);
+
+        Ok(Self { path, data })
+    }
+
+    pub fn active_id(&self) -> Option<&str> {
+        self.data.active.as_deref()
+    }
+
+    pub fn set_active(&mut self, id: &str) -> Result<()> {
+        self.data.active = Some(id.to_string());
+        self.persist()
+    }
+
+    pub fn get(&self, id: &str) -> Option<&DetectionProfile> {
+        self.data.profiles.iter().find(|profile| profile.id == id)
+    }
+
+    pub fn list(&self) -> Vec<DetectionProfile> {
+        self.data.profiles.clone()
+    }
+
+    pub fn upsert(&mut self, profile: DetectionProfile) -> Result<()> {
+        match self
+            .data
+            .profiles
+            .iter_mut()
+            .find(|existing| existing.id == profile.id)
+        {
+            Some(existing) => *existing = profile,
+            None => self.data.profiles.push(profile),
+        }
+        self.persist()
+    }
+
+    fn persist(&self) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).with_context(|| {
+                format!("failed creating profile store directory {}", parent.display())
+            })?;
+        }
+
+        let payload =
+            serde_json::to_string_pretty(&self.data).context("failed serializing profiles")?;
+        fs::write(&self.path, payload).with_context(|| {
+            format!("failed writing profile store to {}", self.path.display())
+        })?;
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct SuppressionStore {
+    path: PathBuf,
+    rules: Vec<SuppressionRule>,
+    /// When each (rule, pid, alert type, title) was last counted, so an alert
+    /// rebuilt on every tick is counted once per window.
+    counted_hits: HashMap<(String, Option<u32>, String, String), DateTime<Utc>>,
+    /// Hit counters changed since the last write.
+    dirty: bool,
+}
+
+impl SuppressionStore {
+    pub fn load(path: PathBuf) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self {
+                path,
+                rules: Vec::new(),
+                counted_hits: HashMap::new(),
+                dirty: false,
+            });
+        }
+
+        let raw = fs::read_to_string(&path)
+            .with_context(|| format!("failed to read suppression store from {}", path.display()))?;
+        let rules: Vec<SuppressionRule> = serde_json::from_str(&raw).unwrap_or_default();
+
+        Ok(Self {
+            path,
+            rules,
+            counted_hits: HashMap::new(),
+            dirty: false,
+        })
+    }
+
+    pub fn list(&self) -> Vec<SuppressionRule> {
+        self.rules.clone()
+    }
+
+    pub fn rules(&self) -> &[SuppressionRule] {
+        &self.rules
+    }
+
+    pub fn insert(&mut self, rule: SuppressionRule) -> Result<()> {
+        self.rules.push(rule);
+        self.persist()
+    }
+
+    pub fn remove(&mut self, rule_id: &str) -> Result<bool> {
+        let before = self.rules.len();
+        self.rules.retain(|rule| rule.id != rule_id);
+        let changed = self.rules.len() != before;
+        if changed {
+            self.persist()?;
+        }
+        Ok(changed)
+    }
+
+    /// Counts a suppressed alert unless the rule already counted the same
+    /// alert within `window_seconds`. Counters are written on [`Self::flush`].
+    pub fn record_hit(
+        &mut self,
+        rule_id: &str,
+        alert: &Alert,
+        at: DateTime<Utc>,
+        window_seconds: i64,
+    ) {
+        let Some(rule) = self.rules.iter_mut().find(|rule| rule.id == rule_id) else {
+            return;
+        };
+        rule.last_suppressed_at = Some(at.to_rfc3339());
+        self.dirty = true;
+
+        self.counted_hits
+            .retain(|_, counted_at| (at - *counted_at).num_seconds() < window_seconds);
+        let key = (
+            rule_id.to_string(),
+            alert.pid,
+            alert.alert_type.clone(),
+            alert.title.clone(),
+        );
+        if self.counted_hits.contains_key(&key) {
+            return;
+        }
+        self.counted_hits.insert(key, at);
+        rule.suppressed_count = rule.suppressed_count.saturating_add(1);
+    }
+
+    pub fn flush(&mut self) -> Result<()> {
+        if !self.dirty {
+            return Ok(());
+        }
+        self.persist()
+    }
+
+    fn persist(&mut self) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).with_context(|| {
+                format!("failed creating suppression store directory {}", parent.display())
+            })?;
+        }
+
+        let payload = serde_json::to_string_pretty(&self.rules)
+            .context("failed serializing suppression rules")?;
+        fs::write(&self.path, payload).with_context(|| {
+            format!("failed writing suppression store to {}", self.path.display())
+        })?;
+        self.dirty = false;
+        Ok(())
+    }
+}
+
+/// Open and recently completed event sequences, written on [`Self::flush`].
+#[derive(Debug)]
+pub struct SequenceStore {
+    path: PathBuf,
+    state: SequenceState,
+    dirty: bool,
+}
+
+impl SequenceStore {
+    pub fn load(path: PathBuf) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self {
+                path,
+                state: SequenceState::default(),
+                dirty: false,
+            });
+        }
+
+        let raw = fs::read_to_string(&path)
+            .with_context(|| format!("failed to read sequence store from {}", path.display()))?;
+        let state: SequenceState = serde_json::from_str(&raw).unwrap_or_default();
+
+        Ok(Self {
+            path,
+            state,
+            dirty: false,
+        })
+    }
+
+    pub fn state(&self) -> &SequenceState {
+        &self.state
+    }
+
+    pub fn state_mut(&mut self) -> &mut SequenceState {
+        self.dirty = true;
+        &mut self.state
+    }
+
+    pub fn flush(&mut self) -> Result<()> {
+        if !self.dirty {
+            return Ok(());
+        }
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).with_context(|| {
+                format!("failed creating sequence store directory {}", parent.display())
+            })?;
+        }
+
+        let payload = serde_json::to_string_pretty(&self.state)
+            .context("failed serializing sequence state")?;
+        fs::write(&self.path, payload).with_context(|| {
+            format!("failed writing sequence store to {}", self.path.display())
+        })?;
+        self.dirty = false;
+        Ok(())
+    }
+}
+
 impl EventStore {
     pub fn load(path: PathBuf) -> Result<Self> {
         let store = Self {
@@ -372,6 +792,7 @@ impl EventStore {
                     .as_ref()
                     .map(|proc| {
                         proc.image_name.to_lowercase().contains(filter)
+                            || proc.sha256.as_deref() == Some(filter)
                             || proc
                                 .image_path
                                 .as_deref()
@@ -394,6 +815,30 @@ impl EventStore {
         Ok(output)
     }
 
+    /// Events with `start <= timestamp_utc <= end` (RFC 3339, UTC), oldest first.
+    pub fn list_events_between(
+        &self,
+        start: &str,
+        end: &str,
+        limit: usize,
+    ) -> Result<Vec<EventEnvelope>> {
+        let conn = self.open_connection()?;
+        let mut stmt = conn
+            .prepare(
+                "SELECT payload FROM events
+                WHERE timestamp_utc >= ?1 AND timestamp_utc <= ?2
+                ORDER BY timestamp_utc ASC LIMIT ?3",
+            )
+            .context("failed preparing event range statement")?;
+        let rows = stmt
+            .query_map(params![start, end, limit as i64], |row| row.get::<_, String>(0))
+            .context("failed querying event range rows")?;
+        Ok(rows
+            .filter_map(|row| row.ok())
+            .filter_map(|raw| serde_json::from_str(&raw).ok())
+            .collect())
+    }
+
     pub fn total_events(&self) -> Result<u64> {
         let conn = self.open_connection()?;
         let total: i64 = conn
@@ -455,3 +900,188 @@ impl EventStore {
         Ok(())
     }
 }
+
+impl HashCacheStore {
+    pub fn load(path: PathBuf) -> Result<Self> {
+        let store = Self { path };
+        store.init()?;
+        Ok(store)
+    }
+
+    pub fn lookup(&self, file_path: &str, size: u64, modified_ns: i64) -> Result<Option<String>> {
+        let conn = self.open_connection()?;
+        let mut stmt = conn
+            .prepare(
+                "SELECT sha256 FROM file_hashes WHERE path = ?1 AND size = ?2 AND modified_ns = ?3",
+            )
+            .context("failed preparing hash cache lookup")?;
+        let mut rows = stmt
+            .query(params![file_path, size as i64, modified_ns])
+            .context("failed querying hash cache")?;
+        match rows.next().context("failed reading hash cache row")? {
+            Some(row) => Ok(Some(row.get(0).context("failed decoding cached hash")?)),
+            None => Ok(None),
+        }
+    }
+
+    pub fn upsert(&self, file_path: &str, size: u64, modified_ns: i64, sha256: &str) -> Result<()> {
+        let conn = self.open_connection()?;
+        conn.execute(
+            "INSERT OR REPLACE INTO file_hashes (path, size, modified_ns, sha256, hashed_at_utc)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                file_path,
+                size as i64,
+                modified_ns,
+                sha256,
+                Utc::now().to_rfc3339()
+            ],
+        )
+        .context("failed writing hash cache entry")?;
+        Ok(())
+    }
+
+    pub fn total_entries(&self) -> Result<u64> {
+        let conn = self.open_connection()?;
+        let total: i64 = conn
+            .query_row("SELECT COUNT(1) FROM file_hashes", [], |row| row.get(0))
+            .context("failed reading hash cache size")?;
+        Ok(total.max(0) as u64)
+    }
+
+    fn init(&self) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).with_context(|| {
+                format!("failed creating hash cache directory {}", parent.display())
+            })?;
+        }
+
+        let conn = self.open_connection()?;
+        conn.execute_batch(
+            "
+            CREATE TABLE IF NOT EXISTS file_hashes (
+                path TEXT PRIMARY KEY,
+                size INTEGER NOT NULL,
+                modified_ns INTEGER NOT NULL,
+                sha256 TEXT NOT NULL,
+                hashed_at_utc TEXT NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_file_hashes_sha256 ON file_hashes(sha256);
+            ",
+        )
+        .context("failed initializing sqlite hash cache schema")?;
+        Ok(())
+    }
+
+    fn open_connection(&self) -> Result<Connection> {
+        Connection::open(&self.path)
+            .with_context(|| format!("failed opening sqlite hash cache {}", self.path.display()))
+    }
+}
+
+#[derive(Debug)]
+pub struct PrevalenceStore {
+    path: PathBuf,
+}
+
+impl PrevalenceStore {
+    pub fn load(path: PathBuf) -> Result<Self> {
+        let store = Self { path };
+        store.init()?;
+        Ok(store)
+    }
+
+    pub fn lookup(&self, sha256: &str, file_path: &str) -> Result<Option<BinaryPrevalence>> {
+        let conn = self.open_connection()?;
+        let mut stmt = conn
+            .prepare(
+                "SELECT first_seen_utc, last_seen_utc, run_count, parents, file_modified_utc
+                 FROM binary_prevalence WHERE sha256 = ?1 AND path = ?2",
+            )
+            .context("failed preparing prevalence lookup")?;
+        let mut rows = stmt
+            .query(params![sha256, file_path])
+            .context("failed querying prevalence store")?;
+        let Some(row) = rows.next().context("failed reading prevalence row")? else {
+            return Ok(None);
+        };
+        let parents: String = row.get(3).context("failed decoding prevalence parents")?;
+        Ok(Some(BinaryPrevalence {
+            sha256: sha256.to_string(),
+            path: file_path.to_string(),
+            first_seen: row.get(0).context("failed decoding prevalence first seen")?,
+            last_seen: row.get(1).context("failed decoding prevalence last seen")?,
+            run_count: row.get::<_, i64>(2).context("failed decoding run count")?.max(0) as u64,
+            parents: serde_json::from_str(&parents).unwrap_or_default(),
+            file_modified: row.get(4).context("failed decoding prevalence mtime")?,
+        }))
+    }
+
+    pub fn upsert(&self, prevalence: &BinaryPrevalence) -> Result<()> {
+        let parents = serde_json::to_string(&prevalence.parents)
+            .context("failed serializing prevalence parents")?;
+        let conn = self.open_connection()?;
+        conn.execute(
+            "INSERT OR REPLACE INTO binary_prevalence (
+                sha256, path, first_seen_utc, last_seen_utc, run_count, parents, file_modified_utc
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                prevalence.sha256,
+                prevalence.path,
+                prevalence.first_seen,
+                prevalence.last_seen,
+                prevalence.run_count as i64,
+                parents,
+                prevalence.file_modified
+            ],
+        )
+        .context("failed writing prevalence entry")?;
+        Ok(())
+    }
+
+    /// When this host started recording prevalence, so "first seen" can be
+    /// told apart from "first seen since Nyx was installed".
+    pub fn earliest_first_seen(&self) -> Result<Option<DateTime<Utc>>> {
+        let conn = self.open_connection()?;
+        let earliest: Option<String> = conn
+            .query_row("SELECT MIN(first_seen_utc) FROM binary_prevalence", [], |row| {
+                row.get(0)
+            })
+            .context("failed reading earliest prevalence entry")?;
+        Ok(earliest
+            .and_then(|value| DateTime::parse_from_rfc3339(&value).ok())
+            .map(|value| value.with_timezone(&Utc)))
+    }
+
+    fn init(&self) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).with_context(|| {
+                format!("failed creating prevalence store directory {}", parent.display())
+            })?;
+        }
+
+        let conn = self.open_connection()?;
+        conn.execute_batch(
+            "
+            CREATE TABLE IF NOT EXISTS binary_prevalence (
+                sha256 TEXT NOT NULL,
+                path TEXT NOT NULL,
+                first_seen_utc TEXT NOT NULL,
+                last_seen_utc TEXT NOT NULL,
+                run_count INTEGER NOT NULL,
+                parents TEXT NOT NULL,
+                file_modified_utc TEXT,
+                PRIMARY KEY (sha256, path)
+            );
+            CREATE INDEX IF NOT EXISTS idx_binary_prevalence_path ON binary_prevalence(path);
+            ",
+        )
+        .context("failed initializing sqlite prevalence schema")?;
+        Ok(())
+    }
+
+    fn open_connection(&self) -> Result<Connection> {
+        Connection::open(&self.path)
+            .with_context(|| format!("failed opening sqlite prevalence store {}", self.path.display()))
+    }
+}
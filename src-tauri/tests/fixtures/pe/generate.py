@@ -0,0 +1,228 @@
+"""Regenerates the Authenticode fixtures used by monitoring::authenticode tests.
+
+Requires the `cryptography` package. `signed.exe` carries a throwaway
+self-signed certificate and `chained.exe` a publisher certificate issued by a
+throwaway CA that is embedded next to it; only the structure and the embedded
+digest matter to the parser.
+"""
+
+import datetime
+import hashlib
+import struct
+from pathlib import Path
+
+from cryptography import x509
+from cryptography.hazmat.primitives import hashes, serialization
+from cryptography.hazmat.primitives.asymmetric import padding, rsa
+from cryptography.x509.oid import NameOID
+
+HERE = Path(__file__).parent
+
+OID_SIGNED_DATA = "1.2.840.113549.1.7.2"
+OID_SPC_INDIRECT_DATA = "1.3.6.1.4.1.311.2.1.4"
+OID_SPC_PE_IMAGE_DATA = "1.3.6.1.4.1.311.2.1.15"
+OID_SHA256 = "2.16.840.1.101.3.4.2.1"
+OID_RSA = "1.2.840.113549.1.1.1"
+OID_CONTENT_TYPE = "1.2.840.113549.1.9.3"
+OID_MESSAGE_DIGEST = "1.2.840.113549.1.9.4"
+
+
+def der(tag, body):
+    length = len(body)
+    if length < 0x80:
+        encoded = bytes([length])
+    else:
+        raw = length.to_bytes((length.bit_length() + 7) // 8, "big")
+        encoded = bytes([0x80 | len(raw)]) + raw
+    return bytes([tag]) + encoded + body
+
+
+def seq(*items):
+    return der(0x30, b"".join(items))
+
+
+def set_of(*items):
+    return der(0x31, b"".join(sorted(items)))
+
+
+def oid(dotted):
+    parts = [int(p) for p in dotted.split(".")]
+    body = bytes([parts[0] * 40 + parts[1]])
+    for part in parts[2:]:
+        chunk = [part & 0x7F]
+        part >>= 7
+        while part:
+            chunk.insert(0, 0x80 | (part & 0x7F))
+            part >>= 7
+        body += bytes(chunk)
+    return der(0x06, body)
+
+
+def integer(value):
+    raw = value.to_bytes((value.bit_length() + 8) // 8, "big")
+    return der(0x02, raw)
+
+
+def null():
+    return b"\x05\x00"
+
+
+def explicit(number, body):
+    return der(0xA0 | number, body)
+
+
+def build_pe():
+    dos = bytearray(0x40)
+    dos[0:2] = b"MZ"
+    struct.pack_into("<I", dos, 0x3C, 0x40)
+
+    coff = struct.pack("<HHIIIHH", 0x8664, 1, 0x5F5E1000, 0, 0, 240, 0x22)
+    optional = bytearray(240)
+    struct.pack_into("<H", optional, 0, 0x20B)
+    struct.pack_into("<I", optional, 16, 0x1000)  # AddressOfEntryPoint
+    struct.pack_into("<Q", optional, 24, 0x140000000)  # ImageBase
+    struct.pack_into("<I", optional, 32, 0x1000)  # SectionAlignment
+    struct.pack_into("<I", optional, 36, 0x200)  # FileAlignment
+    struct.pack_into("<I", optional, 56, 0x2000)  # SizeOfImage
+    struct.pack_into("<I", optional, 60, 0x200)  # SizeOfHeaders
+    struct.pack_into("<I", optional, 64, 0x1234)  # CheckSum (excluded from digest)
+    struct.pack_into("<H", optional, 68, 3)  # Subsystem: console
+    struct.pack_into("<I", optional, 108, 16)  # NumberOfRvaAndSizes
+
+    section = bytearray(40)
+    section[0:8] = b".text\x00\x00\x00"
+    struct.pack_into("<IIII", section, 8, 0x200, 0x1000, 0x200, 0x200)
+    struct.pack_into("<I", section, 36, 0x60000020)
+
+    headers = bytes(dos) + b"PE\x00\x00" + coff + bytes(optional) + bytes(section)
+    headers += b"\x00" * (0x200 - len(headers))
+    body = (b"\x48\x31\xc0\xc3" + b"nyx authenticode fixture").ljust(0x200, b"\xcc")
+    return bytearray(headers + body)
+
+
+def authenticode_digest(image, cert_offset, cert_size):
+    checksum_offset = 0x40 + 4 + 20 + 64
+    certdir_offset = 0x40 + 4 + 20 + 112 + 4 * 8
+    digest = hashlib.sha256()
+    digest.update(image[:checksum_offset])
+    digest.update(image[checksum_offset + 4 : certdir_offset])
+    digest.update(image[certdir_offset + 8 : cert_offset])
+    digest.update(image[cert_offset + cert_size :])
+    return digest.digest()
+
+
+def build_signed_data(image_digest, key, cert, extra_certs=()):
+    spc_pe_image_data = seq(der(0x03, b"\x00"), explicit(0, explicit(2, der(0x80, b""))))
+    indirect = seq(
+        seq(oid(OID_SPC_PE_IMAGE_DATA), spc_pe_image_data),
+        seq(seq(oid(OID_SHA256), null()), der(0x04, image_digest)),
+    )
+    content_info = seq(oid(OID_SPC_INDIRECT_DATA), explicit(0, indirect))
+
+    # The signed digest covers the SpcIndirectDataContent value without its
+    # outer SEQUENCE header.
+    header_len = 2 if indirect[1] < 0x80 else 2 + (indirect[1] & 0x7F)
+    content_digest = hashlib.sha256(indirect[header_len:]).digest()
+    attributes = [
+        seq(oid(OID_CONTENT_TYPE), set_of(oid(OID_SPC_INDIRECT_DATA))),
+        seq(oid(OID_MESSAGE_DIGEST), set_of(der(0x04, content_digest))),
+    ]
+    signed_attributes = set_of(*attributes)
+    signature = key.sign(signed_attributes, padding.PKCS1v15(), hashes.SHA256())
+
+    cert_der = cert.public_bytes(serialization.Encoding.DER)
+    cert_der += b"".join(extra.public_bytes(serialization.Encoding.DER) for extra in extra_certs)
+    issuer_der = cert.issuer.public_bytes()
+    signer_info = seq(
+        integer(1),
+        seq(issuer_der, integer(cert.serial_number)),
+        seq(oid(OID_SHA256), null()),
+        b"\xa0" + signed_attributes[1:],
+        seq(oid(OID_RSA), null()),
+        der(0x04, signature),
+    )
+    signed_data = seq(
+        integer(1),
+        set_of(seq(oid(OID_SHA256), null())),
+        content_info,
+        der(0xA0, cert_der),
+        set_of(signer_info),
+    )
+    return seq(oid(OID_SIGNED_DATA), explicit(0, signed_data))
+
+
+def fixture_name(common_name):
+    return x509.Name(
+        [
+            x509.NameAttribute(NameOID.COUNTRY_NAME, "ES"),
+            x509.NameAttribute(NameOID.ORGANIZATION_NAME, "Nyx Test Signing"),
+            x509.NameAttribute(NameOID.COMMON_NAME, common_name),
+        ]
+    )
+
+
+def build_certificate(subject, issuer, public_key, serial, signing_key):
+    return (
+        x509.CertificateBuilder()
+        .subject_name(subject)
+        .issuer_name(issuer)
+        .public_key(public_key)
+        .serial_number(serial)
+        .not_valid_before(datetime.datetime(2024, 1, 1, tzinfo=datetime.timezone.utc))
+        .not_valid_after(datetime.datetime(2034, 1, 1, tzinfo=datetime.timezone.utc))
+        .sign(signing_key, hashes.SHA256())
+    )
+
+
+def sign_image(unsigned, key, cert, extra_certs=()):
+    cert_offset = len(unsigned)
+    # The digest excludes the certificate table, so it can be computed before
+    # the table size is known.
+    image_digest = authenticode_digest(bytes(unsigned), cert_offset, 0)
+    pkcs7 = build_signed_data(image_digest, key, cert, extra_certs)
+    win_certificate = struct.pack("<IHH", 8 + len(pkcs7), 0x0200, 0x0002) + pkcs7
+    win_certificate += b"\x00" * (-len(win_certificate) % 8)
+
+    signed = bytearray(unsigned)
+    certdir_offset = 0x40 + 4 + 20 + 112 + 4 * 8
+    struct.pack_into("<II", signed, certdir_offset, cert_offset, len(win_certificate))
+    signed += win_certificate
+    return signed
+
+
+def main():
+    key = rsa.generate_private_key(public_exponent=65537, key_size=2048)
+    name = fixture_name("Nyx Fixture Publisher")
+    cert = build_certificate(name, name, key.public_key(), 0x4E595801, key)
+
+    ca_key = rsa.generate_private_key(public_exponent=65537, key_size=2048)
+    ca_name = fixture_name("Nyx Fixture CA")
+    ca_cert = build_certificate(ca_name, ca_name, ca_key.public_key(), 0x4E595800, ca_key)
+    publisher_key = rsa.generate_private_key(public_exponent=65537, key_size=2048)
+    publisher_cert = build_certificate(
+        fixture_name("Nyx Chained Publisher"),
+        ca_name,
+        publisher_key.public_key(),
+        0x4E595802,
+        ca_key,
+    )
+
+    unsigned = build_pe()
+    (HERE / "unsigned.exe").write_bytes(bytes(unsigned))
+
+    signed = sign_image(unsigned, key, cert)
+    (HERE / "signed.exe").write_bytes(bytes(signed))
+
+    chained = sign_image(unsigned, publisher_key, publisher_cert, [ca_cert])
+    (HERE / "chained.exe").write_bytes(bytes(chained))
+
+    tampered = bytearray(signed)
+    tampered[0x204] ^= 0xFF
+    (HERE / "tampered.exe").write_bytes(bytes(tampered))
+
+    thumbprint = hashlib.sha1(cert.public_bytes(serialization.Encoding.DER)).hexdigest().upper()
+    (HERE / "signed.thumbprint").write_text(thumbprint + "\n")
+
+
+if __name__ == "__main__":
+    main()
@@ -8,16 +8,21 @@ import {
   type ReactNode
 } from "react";
 import { AlertsPanel } from "./components/AlertsPanel";
+import { IncidentsPanel } from "./components/IncidentsPanel";
+import { SuppressionRulesPanel } from "./components/SuppressionRulesPanel";
 import nyxLogo from "./assets/nyx-logo.svg";
 import { ProcessDetailsDialog } from "./components/ProcessDetailsDialog";
 import { StatCard } from "./components/StatCard";
 import type { RefreshSpeed } from "./components/ProcessTable";
 import {
+  activateDetectionProfile,
+  cloneDetectionProfile,
+  getActiveDetectionProfile,
   getFileSha256,
+  listDetectionProfiles,
   openProcessFolderByPid,
   openPathInExplorer,
   openUrlInBrowser,
-  setDetectionProfile,
   setProcessTrustOverride
 } from "./lib/api";
 import { buildVirusTotalSearchUrl } from "./lib/externalLinks";
@@ -59,8 +64,6 @@ const tabs: TabConfig[] = [
   { id: "history", label: "History", hint: "Usage timeline" }
 ];
 
-const profileOptions: DetectionProfile[] = ["conservative", "balanced", "aggressive"];
-
 const UsageChart = lazy(() =>
   import("./components/UsageChart").then((module) => ({ default: module.UsageChart }))
 );
@@ -132,7 +135,8 @@ function LazyTabSection({
 
 export default function App() {
   const [activeTab, setActiveTab] = useState<Tab>("overview");
-  const [profile, setProfile] = useState<DetectionProfile>("conservative");
+  const [profileId, setProfileId] = useState("conservative");
+  const [profiles, setProfiles] = useState<DetectionProfile[]>([]);
   const [theme, setTheme] = useState<ThemeMode>(() => {
     const preferred = localStorage.getItem("nyx-monitor-theme");
     return preferred === "light" ? "light" : "dark";
@@ -147,11 +151,16 @@ export default function App() {
     processTree,
     processMetrics,
     alerts,
+    incidents,
+    suppressionRules,
     programs,
     startupProcesses,
     appUsageHistory,
+    appBaselines,
     eventTimeline,
     sensorHealth,
+    detectionRules,
+    sigmaRules,
     performanceStats,
     responsePolicy,
     responseActions,
@@ -160,7 +169,12 @@ export default function App() {
     refresh,
     onDeleteAlert,
     onDeleteAllAlerts,
+    onAcknowledgeAlert,
+    onResolveAlert,
+    onSuppressAlert,
+    onDeleteSuppressionRule,
     onAddKnownProgram,
+    onResetAppBaseline,
     onSetResponsePolicy,
     onRunResponseAction
   } = useMonitoringData({
@@ -168,6 +182,21 @@ export default function App() {
     refreshSpeed
   });
 
+  const loadProfiles = useCallback(async () => {
+    const [available, active] = await Promise.all([
+      listDetectionProfiles(),
+      getActiveDetectionProfile()
+    ]);
+    setProfiles(available);
+    if (active) {
+      setProfileId(active.id);
+    }
+  }, []);
+
+  useEffect(() => {
+    void loadProfiles();
+  }, [loadProfiles]);
+
   useEffect(() => {
     document.documentElement.setAttribute("data-theme", theme);
     localStorage.setItem("nyx-monitor-theme", theme);
@@ -222,11 +251,24 @@ export default function App() {
     }
   }, [processByPid, selectedProcess?.pid]);
 
-  const onProfileChange = useCallback(async (nextProfile: DetectionProfile) => {
-    setProfile(nextProfile);
-    await setDetectionProfile(nextProfile);
+  const onProfileChange = useCallback(async (nextProfileId: string) => {
+    setProfileId(nextProfileId);
+    await activateDetectionProfile(nextProfileId);
   }, []);
 
+  const onCloneProfile = useCallback(async () => {
+    const name = window.prompt("Name for the new detection profile:");
+    if (!name?.trim()) {
+      return;
+    }
+
+    const created = await cloneDetectionProfile(profileId, name);
+    if (created) {
+      await activateDetectionProfile(created.id);
+      await loadProfiles();
+    }
+  }, [loadProfiles, profileId]);
+
   const onOpenPath = useCallback(async (path?: string) => {
     if (!path) {
       return;
@@ -250,7 +292,7 @@ export default function App() {
     (processItem: { pid: number }) => {
       const processDetails = processByPid.get(processItem.pid) ?? null;
       setSelectedProcess(processDetails);
-      setSelectedProcessHash("");
+      setSelectedProcessHash(processDetails?.sha256 ?? "");
     },
     [processByPid]
   );
@@ -380,6 +422,8 @@ export default function App() {
           alerts={overviewAlerts}
           onDelete={onDeleteAlert}
           onDeleteAll={onDeleteAllAlerts}
+          onAcknowledge={onAcknowledgeAlert}
+          onResolve={onResolveAlert}
         />
       </TabSection>
     );
@@ -426,7 +470,12 @@ export default function App() {
       case "health":
         return (
           <LazyTabSection fallback="Loading sensor health...">
-            <HealthPanel sensors={sensorHealth} performance={performanceStats} />
+            <HealthPanel
+              sensors={sensorHealth}
+              performance={performanceStats}
+              rules={detectionRules}
+              sigmaRules={sigmaRules}
+            />
           </LazyTabSection>
         );
       case "response":
@@ -444,10 +493,18 @@ export default function App() {
       case "alerts":
         return (
           <TabSection>
+            <IncidentsPanel incidents={incidents} />
             <AlertsPanel
               alerts={alerts}
               onDelete={onDeleteAlert}
               onDeleteAll={onDeleteAllAlerts}
+              onAcknowledge={onAcknowledgeAlert}
+              onResolve={onResolveAlert}
+              onSuppress={onSuppressAlert}
+            />
+            <SuppressionRulesPanel
+              rules={suppressionRules}
+              onDelete={onDeleteSuppressionRule}
             />
           </TabSection>
         );
@@ -471,7 +528,12 @@ export default function App() {
       case "history":
         return (
           <LazyTabSection fallback="Loading usage history...">
-            <AppUsageHistoryTable entries={appUsageHistory} onOpenPath={onOpenPath} />
+            <AppUsageHistoryTable
+              entries={appUsageHistory}
+              baselines={appBaselines}
+              onOpenPath={onOpenPath}
+              onResetBaseline={onResetAppBaseline}
+            />
           </LazyTabSection>
         );
     }
@@ -505,16 +567,16 @@ export default function App() {
 
         <div className="rail-footnote">
           <p>Profile</p>
-          <select
-            value={profile}
-            onChange={(event) => onProfileChange(event.target.value as DetectionProfile)}
-          >
-            {profileOptions.map((option) => (
-              <option key={option} value={option}>
-                {option.charAt(0).toUpperCase() + option.slice(1)}
+          <select value={profileId} onChange={(event) => onProfileChange(event.target.value)}>
+            {profiles.map((option) => (
+              <option key={option.id} value={option.id}>
+                {option.builtin ? option.name : `${option.name} (custom)`}
               </option>
             ))}
           </select>
+          <button className="btn btn--small" onClick={onCloneProfile}>
+            Clone profile
+          </button>
         </div>
       </aside>
 
@@ -1,13 +1,36 @@
 ﻿import { formatDate, severityLabel } from "../lib/format";
-import type { Alert } from "../types";
+import type { Alert, AlertDisposition } from "../types";
 
 interface AlertsPanelProps {
   alerts: Alert[];
   onDelete: (alertId: string) => Promise<void>;
   onDeleteAll?: () => Promise<void>;
+  onAcknowledge?: (alertId: string) => Promise<void>;
+  onResolve?: (alertId: string, disposition: AlertDisposition) => Promise<void>;
+  onSuppress?: (alert: Alert, reason: string) => Promise<void>;
 }
 
-export function AlertsPanel({ alerts, onDelete, onDeleteAll }: AlertsPanelProps) {
+const dispositions: Array<{ value: AlertDisposition; label: string }> = [
+  { value: "true_positive", label: "True positive" },
+  { value: "false_positive", label: "False positive" },
+  { value: "benign", label: "Benign" }
+];
+
+export function AlertsPanel({
+  alerts,
+  onDelete,
+  onDeleteAll,
+  onAcknowledge,
+  onResolve,
+  onSuppress
+}: AlertsPanelProps) {
+  const suppress = (alert: Alert) => {
+    const reason = window.prompt("Reason for suppressing similar alerts:");
+    if (onSuppress && reason?.trim()) {
+      void onSuppress(alert, reason.trim());
+    }
+  };
+
   return (
     <div className="panel">
       <div className="panel__header">
@@ -16,7 +39,7 @@ export function AlertsPanel({ alerts, onDelete, onDeleteAll }: AlertsPanelProps)
           <span>{alerts.length} active</span>
           {onDeleteAll ? (
             <button className="btn btn--small" onClick={onDeleteAll} disabled={alerts.length === 0}>
-              Clear all
+              Archive all
             </button>
           ) : null}
         </div>
@@ -32,11 +55,44 @@ export function AlertsPanel({ alerts, onDelete, onDeleteAll }: AlertsPanelProps)
                   <p className="alert-card__title">{alert.title}</p>
                   <p className="alert-card__meta">
                     {severityLabel(alert.severity)} | {formatDate(alert.timestamp)}
+                    {alert.status === "acknowledged" ? " | Acknowledged" : ""}
                   </p>
                 </div>
                 <div className="alert-card__actions">
+                  {onAcknowledge && alert.status === "active" ? (
+                    <button onClick={() => onAcknowledge(alert.id)} className="btn btn--small">
+                      Acknowledge
+                    </button>
+                  ) : null}
+                  {onResolve ? (
+                    <select
+                      className="btn btn--small"
+                      value=""
+                      onChange={(event) =>
+                        event.target.value &&
+                        onResolve(alert.id, event.target.value as AlertDisposition)
+                      }
+                    >
+                      <option value="">Resolve as...</option>
+                      {dispositions.map((item) => (
+                        <option key={item.value} value={item.value}>
+                          {item.label}
+                        </option>
+                      ))}
+                    </select>
+                  ) : null}
+                  {onSuppress ? (
+                    <button
+                      onClick={() => suppress(alert)}
+                      className="btn btn--small"
+                      disabled={!alert.process?.image_path && !alert.process?.sha256}
+                      title="Suppresses alerts of this type for this binary for 30 days"
+                    >
+                      Suppress
+                    </button>
+                  ) : null}
                   <button onClick={() => onDelete(alert.id)} className="btn btn--small btn--danger">
-                    Remove
+                    Archive
                   </button>
                 </div>
               </div>
@@ -48,6 +104,9 @@ export function AlertsPanel({ alerts, onDelete, onDeleteAll }: AlertsPanelProps)
                   ))}
                 </ul>
               ) : null}
+              {alert.evidence_refs?.length ? (
+                <p className="alert-card__meta">Events: {alert.evidence_refs.join(", ")}</p>
+              ) : null}
             </li>
           ))}
         </ul>
@@ -2,15 +2,35 @@ import { useMemo } from "react";
 import { formatDate, formatPercent } from "../lib/format";
 import { useSearchQuery } from "../hooks/useSearchQuery";
 import { matchesSearchQuery } from "../lib/search";
-import type { AppUsageEntry } from "../types";
+import type { AppBaseline, AppUsageEntry } from "../types";
 import { DataPanel } from "./DataPanel";
 
 interface AppUsageHistoryTableProps {
   entries: AppUsageEntry[];
+  baselines: AppBaseline[];
   onOpenPath: (path?: string) => void;
+  onResetBaseline: (appKey: string) => Promise<void>;
 }
 
-export function AppUsageHistoryTable({ entries, onOpenPath }: AppUsageHistoryTableProps) {
+function describeBaseline(baseline?: AppBaseline): string {
+  if (!baseline) {
+    return "-";
+  }
+  if (!baseline.learned_at) {
+    return `Aprendiendo (${baseline.samples} muestras)`;
+  }
+  return (
+    `Aprendido: ${baseline.parents.length} padres, ${baseline.children.length} hijos, ` +
+    `${baseline.remote_ports.length} puertos`
+  );
+}
+
+export function AppUsageHistoryTable({
+  entries,
+  baselines,
+  onOpenPath,
+  onResetBaseline
+}: AppUsageHistoryTableProps) {
   const { query, setQuery, normalizedQuery } = useSearchQuery();
 
   const rows = useMemo(() => {
@@ -19,6 +39,11 @@ export function AppUsageHistoryTable({ entries, onOpenPath }: AppUsageHistoryTab
     );
   }, [entries, normalizedQuery]);
 
+  const baselineByKey = useMemo(
+    () => new Map(baselines.map((baseline) => [baseline.app_key, baseline])),
+    [baselines]
+  );
+
   return (
     <DataPanel
       title="App Usage History"
@@ -39,25 +64,47 @@ export function AppUsageHistoryTable({ entries, onOpenPath }: AppUsageHistoryTab
             <th>CPU pico</th>
             <th>Primer uso</th>
             <th>Ultimo uso</th>
+            <th>Linea base</th>
           </tr>
         </thead>
         <tbody>
-          {rows.map((entry) => (
-            <tr
-              key={entry.app_key}
-              className="click-row"
-              onClick={() => onOpenPath(entry.executable_path)}
-            >
-              <td>
-                <div className="cell-primary">{entry.name}</div>
-                <div className="cell-secondary">{entry.executable_path ?? "-"}</div>
-              </td>
-              <td>{entry.launch_count}</td>
-              <td>{formatPercent(entry.max_cpu_pct)}</td>
-              <td>{formatDate(entry.first_seen)}</td>
-              <td>{formatDate(entry.last_seen)}</td>
-            </tr>
-          ))}
+          {rows.map((entry) => {
+            const baseline = baselineByKey.get(entry.app_key);
+            return (
+              <tr
+                key={entry.app_key}
+                className="click-row"
+                onClick={() => onOpenPath(entry.executable_path)}
+              >
+                <td>
+                  <div className="cell-primary">{entry.name}</div>
+                  <div className="cell-secondary">{entry.executable_path ?? "-"}</div>
+                </td>
+                <td>{entry.launch_count}</td>
+                <td>{formatPercent(entry.max_cpu_pct)}</td>
+                <td>{formatDate(entry.first_seen)}</td>
+                <td>{formatDate(entry.last_seen)}</td>
+                <td>
+                  <div className="cell-primary with-actions">
+                    <span>{describeBaseline(baseline)}</span>
+                    {baseline ? (
+                      <button
+                        className="vt-link"
+                        onClick={async (event) => {
+                          event.preventDefault();
+                          event.stopPropagation();
+                          await onResetBaseline(entry.app_key);
+                        }}
+                        title="Descartar la linea base y volver a aprender"
+                      >
+                        Reset
+                      </button>
+                    ) : null}
+                  </div>
+                </td>
+              </tr>
+            );
+          })}
         </tbody>
       </table>
     </DataPanel>
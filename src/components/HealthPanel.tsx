@@ -1,11 +1,13 @@
-import type { PerformanceStats, SensorHealth } from "../types";
+import type { DetectionRule, PerformanceStats, SensorHealth, SigmaRuleSummary } from "../types";
 
 interface HealthPanelProps {
   sensors: SensorHealth[];
   performance: PerformanceStats;
+  rules: DetectionRule[];
+  sigmaRules: SigmaRuleSummary[];
 }
 
-export function HealthPanel({ sensors, performance }: HealthPanelProps) {
+export function HealthPanel({ sensors, performance, rules, sigmaRules }: HealthPanelProps) {
   return (
     <div className="panel-stack panel-stack--health">
       <div className="panel panel--health-metrics">
@@ -17,6 +19,9 @@ export function HealthPanel({ sensors, performance }: HealthPanelProps) {
           <p>Loop avg: {performance.loop_avg_ms.toFixed(1)} ms</p>
           <p>Loop p95: {performance.loop_p95_ms.toFixed(1)} ms</p>
           <p>Tracked processes: {performance.tracked_processes}</p>
+          <p>
+            Hash backlog: {performance.hash_backlog} ({performance.hash_cache_entries} cached)
+          </p>
           <p>Total events: {performance.total_events}</p>
         </div>
       </div>
@@ -58,6 +63,72 @@ export function HealthPanel({ sensors, performance }: HealthPanelProps) {
           </div>
         )}
       </div>
+
+      <div className="panel panel--table">
+        <div className="panel__header">
+          <h3>Detection Rules</h3>
+        </div>
+        {rules.length === 0 ? (
+          <p className="panel__empty">No detection rules loaded.</p>
+        ) : (
+          <div className="table-wrapper table-wrapper--tall">
+            <table className="data-table data-table--compact">
+              <thead>
+                <tr>
+                  <th>Rule</th>
+                  <th>Weight</th>
+                  <th>Reason</th>
+                  <th>Source</th>
+                  <th>Enabled</th>
+                </tr>
+              </thead>
+              <tbody>
+                {rules.map((rule) => (
+                  <tr key={rule.id} title={rule.description}>
+                    <td>{rule.id}</td>
+                    <td>{rule.weight}</td>
+                    <td>{rule.reason}</td>
+                    <td>{rule.source}</td>
+                    <td>{rule.enabled ? "yes" : "no"}</td>
+                  </tr>
+                ))}
+              </tbody>
+            </table>
+          </div>
+        )}
+      </div>
+
+      <div className="panel panel--table">
+        <div className="panel__header">
+          <h3>Sigma Rules</h3>
+        </div>
+        {sigmaRules.length === 0 ? (
+          <p className="panel__empty">No Sigma rules loaded.</p>
+        ) : (
+          <div className="table-wrapper table-wrapper--tall">
+            <table className="data-table data-table--compact">
+              <thead>
+                <tr>
+                  <th>Title</th>
+                  <th>Level</th>
+                  <th>Category</th>
+                  <th>Source</th>
+                </tr>
+              </thead>
+              <tbody>
+                {sigmaRules.map((rule) => (
+                  <tr key={`${rule.source}:${rule.id}`} title={rule.id}>
+                    <td>{rule.title}</td>
+                    <td>{rule.level}</td>
+                    <td>{rule.category}</td>
+                    <td>{rule.source}</td>
+                  </tr>
+                ))}
+              </tbody>
+            </table>
+          </div>
+        )}
+      </div>
     </div>
   );
 }
@@ -0,0 +1,61 @@
+import { formatDate, severityLabel } from "../lib/format";
+import type { Incident, IncidentProcess } from "../types";
+
+interface IncidentsPanelProps {
+  incidents: Incident[];
+}
+
+function ProcessBranch({ node }: { node: IncidentProcess }) {
+  return (
+    <li>
+      {node.name} (PID {node.pid})
+      {node.alert_ids.length > 0 ? ` | ${node.alert_ids.length} alert(s)` : ""}
+      {node.children.length > 0 ? (
+        <ul>
+          {node.children.map((child) => (
+            <ProcessBranch key={child.pid} node={child} />
+          ))}
+        </ul>
+      ) : null}
+    </li>
+  );
+}
+
+export function IncidentsPanel({ incidents }: IncidentsPanelProps) {
+  const grouped = incidents.filter((incident) => incident.open && incident.alerts.length > 1);
+
+  return (
+    <div className="panel">
+      <div className="panel__header">
+        <h3>Correlated Incidents</h3>
+        <div className="panel__actions">
+          <span>{grouped.length} open</span>
+        </div>
+      </div>
+      {grouped.length === 0 ? (
+        <p className="panel__empty">No related alerts have been grouped into an incident.</p>
+      ) : (
+        <ul className="alert-list">
+          {grouped.map((incident) => (
+            <li key={incident.id} className={`alert-card alert-card--${incident.severity}`}>
+              <div className="alert-card__head">
+                <div>
+                  <p className="alert-card__title">{incident.title}</p>
+                  <p className="alert-card__meta">
+                    {severityLabel(incident.severity)} | {formatDate(incident.first_seen)} -{" "}
+                    {formatDate(incident.last_seen)} | {incident.alerts.length} alerts
+                  </p>
+                </div>
+              </div>
+              <ul className="alert-evidence">
+                {incident.processes.map((node) => (
+                  <ProcessBranch key={node.pid} node={node} />
+                ))}
+              </ul>
+            </li>
+          ))}
+        </ul>
+      )}
+    </div>
+  );
+}
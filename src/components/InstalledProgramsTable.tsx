@@ -26,7 +26,7 @@ function matchesFilter(level: TrustLevel, active: ProgramsFilter): boolean {
     return level === "unknown";
   }
 
-  return level === "trusted" || level === "windows_native";
+  return level === "trusted" || level === "windows_native" || level === "system_native";
 }
 
 export function InstalledProgramsTable({
@@ -1,5 +1,6 @@
 ﻿import { useEffect, useState } from "react";
-import type { ProcessMetric, TrustLevel } from "../types";
+import { formatBytesRate, formatDate, formatMemoryMb } from "../lib/format";
+import type { BinarySignature, ProcessMetric, SignatureStatus, TrustLevel } from "../types";
 
 interface ProcessDetailsDialogProps {
   process: ProcessMetric | null;
@@ -25,6 +26,24 @@ const trustOptions: { value: TrustLevel; label: string }[] = [
   { value: "unknown", label: "Rojo (Desconocido/Sospechoso)" }
 ];
 
+const signatureLabels: Record<SignatureStatus, string> = {
+  valid: "Firmado (Authenticode valido)",
+  catalog: "Firmado por catalogo",
+  digest_mismatch: "Firma invalida (el binario fue modificado)",
+  malformed: "Firma corrupta",
+  unsigned: "Sin firma"
+};
+
+function describeSignature(signature?: BinarySignature): string {
+  if (!signature) {
+    return "(no disponible)";
+  }
+  if (signature.status === "valid" && !signature.verified) {
+    return "Firma intacta (sin cadena de emisor)";
+  }
+  return signatureLabels[signature.status];
+}
+
 export function ProcessDetailsDialog({
   process,
   parentInfo,
@@ -103,6 +122,15 @@ export function ProcessDetailsDialog({
             <p>
               <strong>Ruta:</strong> {process.exe_path ?? "(sin ruta disponible)"}
             </p>
+            <p>
+              <strong>Linea de comandos:</strong> {process.cmdline ?? "(no disponible)"}
+            </p>
+            <p>
+              <strong>Usuario:</strong> {process.user ?? "-"}
+              {process.real_uid !== undefined
+                ? ` (uid ${process.real_uid}/${process.effective_uid ?? "-"}, gid ${process.real_gid ?? "-"}/${process.effective_gid ?? "-"})`
+                : ""}
+            </p>
             <p>
               <strong>PID:</strong> {process.pid}
             </p>
@@ -124,9 +152,59 @@ export function ProcessDetailsDialog({
                 "-"
               )}
             </p>
+            <p>
+              <strong>Memoria GPU:</strong> {formatMemoryMb(process.gpu_memory_mb)}
+            </p>
+            <p>
+              <strong>Disco:</strong> lectura {formatBytesRate(process.disk_read_bytes_per_sec)}, escritura{" "}
+              {formatBytesRate(process.disk_write_bytes_per_sec)}
+            </p>
+            <p>
+              <strong>Red (TCP):</strong> enviado {formatBytesRate(process.net_sent_bytes_per_sec)}, recibido{" "}
+              {formatBytesRate(process.net_received_bytes_per_sec)}
+            </p>
             <p>
               <strong>Hash SHA-256:</strong> {hashValue || "(vacio)"}
             </p>
+            <p>
+              <strong>Firma:</strong> {describeSignature(process.signature)}
+            </p>
+            {process.signature?.signer ? (
+              <>
+                <p>
+                  <strong>Firmante:</strong> {process.signature.signer.subject}
+                </p>
+                <p>
+                  <strong>Emisor:</strong> {process.signature.signer.issuer}
+                </p>
+                <p>
+                  <strong>Huella SHA-1:</strong> {process.signature.signer.thumbprint}
+                </p>
+                <p>
+                  <strong>Validez:</strong> {process.signature.signer.not_before ?? "-"} a{" "}
+                  {process.signature.signer.not_after ?? "-"}
+                </p>
+              </>
+            ) : null}
+            {process.prevalence ? (
+              <>
+                <p>
+                  <strong>Visto por primera vez:</strong> {formatDate(process.prevalence.first_seen)}
+                </p>
+                <p>
+                  <strong>Ejecuciones:</strong> {process.prevalence.run_count} (ultima{" "}
+                  {formatDate(process.prevalence.last_seen)})
+                </p>
+                <p>
+                  <strong>Padres observados:</strong>{" "}
+                  {process.prevalence.parents.length ? process.prevalence.parents.join(", ") : "-"}
+                </p>
+                <p>
+                  <strong>Modificado en disco:</strong>{" "}
+                  {process.prevalence.file_modified ? formatDate(process.prevalence.file_modified) : "-"}
+                </p>
+              </>
+            ) : null}
             <p>
               <strong>Risk score:</strong> {process.risk_score}
             </p>
@@ -141,6 +219,18 @@ export function ProcessDetailsDialog({
                 ))}
               </ul>
             </div>
+            {process.suspicion.evidence?.length ? (
+              <div>
+                <strong>Evidencia:</strong>
+                <ul className="threat-evidence">
+                  {process.suspicion.evidence.map((item) => (
+                    <li key={item}>
+                      <code>{item}</code>
+                    </li>
+                  ))}
+                </ul>
+              </div>
+            ) : null}
           </div>
         </div>
 
@@ -1,7 +1,7 @@
 import { useMemo, useState } from "react";
 import { useSearchQuery } from "../hooks/useSearchQuery";
 import { buildVirusTotalSearchUrl } from "../lib/externalLinks";
-import { formatDate, formatMemoryMb, formatPercent, riskLabel } from "../lib/format";
+import { formatBytesRate, formatDate, formatMemoryMb, formatPercent, riskLabel } from "../lib/format";
 import { matchesSearchQuery } from "../lib/search";
 import type { ProcessMetric, TrustLevel } from "../types";
 import { DataPanel } from "./DataPanel";
@@ -81,6 +81,7 @@ export function ProcessTable({
     const result: Record<ProcessFilter, number> = {
       all: metrics.length,
       windows_native: 0,
+      system_native: 0,
       trusted: 0,
       unknown: 0
     };
@@ -148,6 +149,8 @@ export function ProcessTable({
             <th>CPU Load</th>
             <th>GPU</th>
             <th>Memory</th>
+            <th>Disk I/O</th>
+            <th>Network</th>
             <th>Started</th>
             <th>Risk</th>
           </tr>
@@ -195,6 +198,12 @@ export function ProcessTable({
               </td>
               <td>{formatPercent(row.gpu_pct)}</td>
               <td>{formatMemoryMb(row.memory_mb)}</td>
+              <td title={`Lectura ${formatBytesRate(row.disk_read_bytes_per_sec)} / Escritura ${formatBytesRate(row.disk_write_bytes_per_sec)}`}>
+                {formatBytesRate(row.disk_read_bytes_per_sec + row.disk_write_bytes_per_sec)}
+              </td>
+              <td title={`Enviado ${formatBytesRate(row.net_sent_bytes_per_sec)} / Recibido ${formatBytesRate(row.net_received_bytes_per_sec)}`}>
+                {formatBytesRate(row.net_sent_bytes_per_sec + row.net_received_bytes_per_sec)}
+              </td>
               <td>{formatDate(row.started_at)}</td>
               <td>
                 <span className={`risk-pill risk-pill--${row.suspicion.level}`}>
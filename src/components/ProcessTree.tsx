@@ -33,7 +33,8 @@ function sortTree(nodes: ProcessNode[], byColor: boolean): ProcessNode[] {
   const trustOrder: Record<TrustLevel, number> = {
     unknown: 0,
     trusted: 1,
-    windows_native: 2
+    windows_native: 2,
+    system_native: 2
   };
 
   return [...nodes]
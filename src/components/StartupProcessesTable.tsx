@@ -14,6 +14,7 @@ interface StartupProcessesTableProps {
 const filters: { id: "all" | TrustLevel; label: string }[] = [
   { id: "all", label: "Todos" },
   { id: "windows_native", label: "Windows" },
+  { id: "system_native", label: "Sistema" },
   { id: "trusted", label: "Conocidos" },
   { id: "unknown", label: "Desconocidos" }
 ];
@@ -52,6 +53,7 @@ export function StartupProcessesTable({ processes, onOpenPath }: StartupProcesse
             <th>Confianza</th>
             <th>Comando</th>
             <th>Fuente</th>
+            <th>YARA</th>
           </tr>
         </thead>
         <tbody>
@@ -67,6 +69,9 @@ export function StartupProcessesTable({ processes, onOpenPath }: StartupProcesse
               </td>
               <td className="cell-secondary">{item.command}</td>
               <td>{item.source}</td>
+              <td title={item.yara_matches.map((hit) => hit.reason).join("\n")}>
+                {item.yara_matches.length > 0 ? item.yara_matches.map((hit) => hit.rule).join(", ") : "-"}
+              </td>
             </tr>
           ))}
         </tbody>
@@ -0,0 +1,56 @@
+import { formatDate } from "../lib/format";
+import type { SuppressionRule } from "../types";
+
+interface SuppressionRulesPanelProps {
+  rules: SuppressionRule[];
+  onDelete: (ruleId: string) => Promise<void>;
+}
+
+function describeRule(rule: SuppressionRule): string {
+  return [
+    rule.alert_type ? `type ${rule.alert_type}` : null,
+    rule.process_path ? `path ${rule.process_path}` : null,
+    rule.sha256 ? `hash ${rule.sha256.slice(0, 12)}...` : null,
+    rule.parent ? `parent ${rule.parent}` : null,
+    rule.evidence_contains ? `evidence "${rule.evidence_contains}"` : null
+  ]
+    .filter(Boolean)
+    .join(", ");
+}
+
+export function SuppressionRulesPanel({ rules, onDelete }: SuppressionRulesPanelProps) {
+  return (
+    <div className="panel">
+      <div className="panel__header">
+        <h3>Suppression Rules</h3>
+        <div className="panel__actions">
+          <span>{rules.length} rules</span>
+        </div>
+      </div>
+      {rules.length === 0 ? (
+        <p className="panel__empty">No suppression rules. Use "Suppress" on an alert to add one.</p>
+      ) : (
+        <ul className="alert-list">
+          {rules.map((rule) => (
+            <li key={rule.id} className="alert-card alert-card--info">
+              <div className="alert-card__head">
+                <div>
+                  <p className="alert-card__title">{describeRule(rule)}</p>
+                  <p className="alert-card__meta">
+                    {rule.reason} | by {rule.author} | {rule.suppressed_count} suppressed
+                    {rule.expires_at ? ` | expires ${formatDate(rule.expires_at)}` : ""}
+                  </p>
+                </div>
+                <div className="alert-card__actions">
+                  <button onClick={() => onDelete(rule.id)} className="btn btn--small btn--danger">
+                    Delete
+                  </button>
+                </div>
+              </div>
+            </li>
+          ))}
+        </ul>
+      )}
+    </div>
+  );
+}
@@ -1,8 +1,12 @@
 import { useCallback, useEffect, useRef, useState } from "react";
 import type {
   Alert,
+  AlertDisposition,
+  AppBaseline,
   AppUsageEntry,
+  DetectionRule,
   EventEnvelope,
+  Incident,
   InstalledProgram,
   PerformanceStats,
   ResponseActionRecord,
@@ -11,15 +15,21 @@ import type {
   ProcessNode,
   ResponseActionType,
   SensorHealth,
-  StartupProcess
+  SigmaRuleSummary,
+  StartupProcess,
+  SuppressionRule
 } from "../types";
 import type { RefreshSpeed } from "../components/ProcessTable";
 import {
+  acknowledgeAlert,
   addKnownProgram,
+  createSuppressionRule,
   deleteAlert,
   deleteAllAlerts,
+  deleteSuppressionRule,
   getActiveAlerts,
   getAppUsageHistory,
+  listAppBaselines,
   getEventTimeline,
   getInstalledPrograms,
   getPerformanceStats,
@@ -29,6 +39,12 @@ import {
   getResponsePolicy,
   getSensorHealth,
   getStartupProcesses,
+  listDetectionRules,
+  listIncidents,
+  listSigmaRules,
+  listSuppressionRules,
+  resetAppBaseline,
+  resolveAlert,
   runResponseAction,
   setResponsePolicy
 } from "../lib/api";
@@ -42,6 +58,7 @@ const refreshIntervals: Record<RefreshSpeed, number> = {
 
 const operationalRefreshIntervalMs = 5000;
 const inventoryRefreshIntervalMs = 60000;
+const suppressionTtlMs = 30 * 24 * 60 * 60 * 1000;
 
 export function useMonitoringData(options: {
   processRefreshPaused: boolean;
@@ -55,18 +72,25 @@ export function useMonitoringData(options: {
   const [processTree, setProcessTree] = useState<ProcessNode[]>([]);
   const [processMetrics, setProcessMetrics] = useState<ProcessMetric[]>([]);
   const [alerts, setAlerts] = useState<Alert[]>([]);
+  const [incidents, setIncidents] = useState<Incident[]>([]);
+  const [suppressionRules, setSuppressionRules] = useState<SuppressionRule[]>([]);
   const [programs, setPrograms] = useState<InstalledProgram[]>([]);
   const [startupProcesses, setStartupProcesses] = useState<StartupProcess[]>([]);
   const [appUsageHistory, setAppUsageHistory] = useState<AppUsageEntry[]>([]);
+  const [appBaselines, setAppBaselines] = useState<AppBaseline[]>([]);
   const [eventTimeline, setEventTimeline] = useState<EventEnvelope[]>([]);
   const [sensorHealth, setSensorHealth] = useState<SensorHealth[]>([]);
+  const [detectionRules, setDetectionRules] = useState<DetectionRule[]>([]);
+  const [sigmaRules, setSigmaRules] = useState<SigmaRuleSummary[]>([]);
   const [performanceStats, setPerformanceStats] = useState<PerformanceStats>({
     loop_last_ms: 0,
     loop_avg_ms: 0,
     loop_p95_ms: 0,
     total_events: 0,
     event_store_size: 0,
-    tracked_processes: 0
+    tracked_processes: 0,
+    hash_backlog: 0,
+    hash_cache_entries: 0
   });
   const [responsePolicy, setResponsePolicyState] = useState<ResponsePolicy>({
     mode: "audit",
@@ -102,20 +126,42 @@ export function useMonitoringData(options: {
 
     operationalRefreshInFlight.current = true;
     try {
-      const [activeAlerts, history, timeline, health, perf, actions] = await Promise.all([
+      const [
+        activeAlerts,
+        currentIncidents,
+        history,
+        baselines,
+        timeline,
+        health,
+        perf,
+        actions,
+        rules,
+        sigma,
+        suppressions
+      ] = await Promise.all([
         getActiveAlerts(),
+        listIncidents(),
         getAppUsageHistory(),
+        listAppBaselines(),
         getEventTimeline({ limit: 250 }),
         getSensorHealth(),
         getPerformanceStats(),
-        getResponseActions(200)
+        getResponseActions(200),
+        listDetectionRules(),
+        listSigmaRules(),
+        listSuppressionRules()
       ]);
       setAlerts(activeAlerts);
+      setIncidents(currentIncidents);
       setAppUsageHistory(history);
+      setAppBaselines(baselines);
       setEventTimeline(timeline);
       setSensorHealth(health);
       setPerformanceStats(perf);
       setResponseActions(actions);
+      setDetectionRules(rules);
+      setSigmaRules(sigma);
+      setSuppressionRules(suppressions);
       setLastUpdated(new Date());
     } finally {
       operationalRefreshInFlight.current = false;
@@ -282,6 +328,53 @@ export function useMonitoringData(options: {
     setAlerts(active);
   }, []);
 
+  const onAcknowledgeAlert = useCallback(async (alertId: string) => {
+    await acknowledgeAlert(alertId);
+    setAlerts(await getActiveAlerts());
+  }, []);
+
+  const onResolveAlert = useCallback(
+    async (alertId: string, disposition: AlertDisposition) => {
+      setAlerts((prev) => prev.filter((item) => item.id !== alertId));
+      await resolveAlert(alertId, disposition);
+      setAlerts(await getActiveAlerts());
+    },
+    []
+  );
+
+  const onSuppressAlert = useCallback(async (alert: Alert, reason: string) => {
+    const processPath = alert.process?.image_path ?? null;
+    const sha256 = alert.process?.sha256 ?? null;
+    if (!processPath && !sha256) {
+      return;
+    }
+    const created = await createSuppressionRule({
+      alert_type: alert.alert_type,
+      process_path: processPath,
+      sha256,
+      expires_at: new Date(Date.now() + suppressionTtlMs).toISOString(),
+      reason
+    });
+    if (created) {
+      await deleteAlert(alert.id);
+      const [active, rules] = await Promise.all([getActiveAlerts(), listSuppressionRules()]);
+      setAlerts(active);
+      setSuppressionRules(rules);
+    }
+  }, []);
+
+  const onDeleteSuppressionRule = useCallback(async (ruleId: string) => {
+    setSuppressionRules((prev) => prev.filter((rule) => rule.id !== ruleId));
+    await deleteSuppressionRule(ruleId);
+    setSuppressionRules(await listSuppressionRules());
+  }, []);
+
+  const onResetAppBaseline = useCallback(async (appKey: string) => {
+    setAppBaselines((prev) => prev.filter((item) => item.app_key !== appKey));
+    await resetAppBaseline(appKey);
+    setAppBaselines(await listAppBaselines());
+  }, []);
+
   const onAddKnownProgram = useCallback(
     async (program: InstalledProgram, label: string) => {
       const changed = await addKnownProgram({
@@ -319,11 +412,16 @@ export function useMonitoringData(options: {
     processTree,
     processMetrics,
     alerts,
+    incidents,
+    suppressionRules,
     programs,
     startupProcesses,
     appUsageHistory,
+    appBaselines,
     eventTimeline,
     sensorHealth,
+    detectionRules,
+    sigmaRules,
     performanceStats,
     responsePolicy,
     responseActions,
@@ -332,7 +430,12 @@ export function useMonitoringData(options: {
     refresh,
     onDeleteAlert,
     onDeleteAllAlerts,
+    onAcknowledgeAlert,
+    onResolveAlert,
+    onSuppressAlert,
+    onDeleteSuppressionRule,
     onAddKnownProgram,
+    onResetAppBaseline,
     onSetResponsePolicy,
     onRunResponseAction
   };
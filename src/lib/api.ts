@@ -1,9 +1,14 @@
 import { invoke } from "@tauri-apps/api/core";
 import type {
   Alert,
+  AlertDisposition,
+  AppBaseline,
   AppUsageEntry,
+  BaselineConfig,
   DetectionProfile,
+  DetectionRule,
   EventEnvelope,
+  Incident,
   InstalledProgram,
   PerformanceStats,
   ResponseActionRecord,
@@ -12,6 +17,9 @@ import type {
   ProcessMetric,
   ProcessNode,
   SensorHealth,
+  SuppressionRule,
+  SequenceRule,
+  SigmaRuleSummary,
   TrustLevel,
   StartupProcess
 } from "../types";
@@ -24,7 +32,14 @@ const defaultPerformanceStats: PerformanceStats = {
   loop_p95_ms: 0,
   total_events: 0,
   event_store_size: 0,
-  tracked_processes: 0
+  tracked_processes: 0,
+  hash_backlog: 0,
+  hash_cache_entries: 0
+};
+
+const defaultBaselineConfig: BaselineConfig = {
+  learning_hours: 72,
+  min_samples: 150
 };
 
 const defaultResponsePolicy: ResponsePolicy = {
@@ -79,6 +94,26 @@ export async function getAppUsageHistory(): Promise<AppUsageEntry[]> {
   return invokeOrFallback("get_app_usage_history", []);
 }
 
+export async function listAppBaselines(): Promise<AppBaseline[]> {
+  return invokeOrFallback("list_app_baselines", []);
+}
+
+export async function getAppBaseline(appKey: string): Promise<AppBaseline | null> {
+  return invokeOrFallback("get_app_baseline", null, { app_key: appKey });
+}
+
+export async function resetAppBaseline(appKey: string): Promise<boolean> {
+  return invokeOrFallback("reset_app_baseline", false, { app_key: appKey });
+}
+
+export async function getBaselineConfig(): Promise<BaselineConfig> {
+  return invokeOrFallback("get_baseline_config", defaultBaselineConfig);
+}
+
+export async function setBaselineConfig(config: BaselineConfig): Promise<void> {
+  await invokeOrSkip("set_baseline_config", { config });
+}
+
 export async function getActiveAlerts(): Promise<Alert[]> {
   return invokeOrFallback("get_active_alerts", []);
 }
@@ -146,6 +181,52 @@ export async function runResponseAction(payload: {
   );
 }
 
+export async function getAlertHistory(includeArchived = false): Promise<Alert[]> {
+  return invokeOrFallback("get_alert_history", [], { include_archived: includeArchived });
+}
+
+export async function acknowledgeAlert(alertId: string): Promise<Alert | null> {
+  return invokeOrFallback("acknowledge_alert", null, { alert_id: alertId });
+}
+
+export async function resolveAlert(
+  alertId: string,
+  disposition: AlertDisposition,
+  note?: string
+): Promise<Alert | null> {
+  return invokeOrFallback("resolve_alert", null, { alert_id: alertId, disposition, note });
+}
+
+export async function reopenAlert(alertId: string, note?: string): Promise<Alert | null> {
+  return invokeOrFallback("reopen_alert", null, { alert_id: alertId, note });
+}
+
+export async function addAlertNote(alertId: string, note: string): Promise<Alert | null> {
+  return invokeOrFallback("add_alert_note", null, { alert_id: alertId, note });
+}
+
+export async function listIncidents(includeArchived = false): Promise<Incident[]> {
+  return invokeOrFallback("list_incidents", [], { include_archived: includeArchived });
+}
+
+export async function getIncident(incidentId: string): Promise<Incident | null> {
+  return invokeOrFallback("get_incident", null, { incident_id: incidentId });
+}
+
+export async function listSuppressionRules(): Promise<SuppressionRule[]> {
+  return invokeOrFallback("list_suppression_rules", []);
+}
+
+export async function createSuppressionRule(
+  rule: Omit<SuppressionRule, "id" | "created_at" | "suppressed_count" | "author"> & { author?: string }
+): Promise<SuppressionRule | null> {
+  return invokeOrFallback("create_suppression_rule", null, { rule });
+}
+
+export async function deleteSuppressionRule(ruleId: string): Promise<boolean> {
+  return invokeOrFallback("delete_suppression_rule", false, { rule_id: ruleId });
+}
+
 export async function deleteAlert(alertId: string): Promise<boolean> {
   return invokeOrFallback("delete_alert", false, { alert_id: alertId });
 }
@@ -154,8 +235,47 @@ export async function deleteAllAlerts(): Promise<number> {
   return invokeOrFallback("delete_all_alerts", 0);
 }
 
-export async function setDetectionProfile(profile: DetectionProfile): Promise<void> {
-  await invokeOrSkip("set_detection_profile", { profile });
+export async function listDetectionProfiles(): Promise<DetectionProfile[]> {
+  return invokeOrFallback("list_detection_profiles", []);
+}
+
+export async function getActiveDetectionProfile(): Promise<DetectionProfile | null> {
+  return invokeOrFallback("get_active_detection_profile", null);
+}
+
+export async function createDetectionProfile(
+  profile: DetectionProfile
+): Promise<DetectionProfile | null> {
+  return invokeOrFallback("create_detection_profile", null, { profile });
+}
+
+export async function updateDetectionProfile(
+  profile: DetectionProfile
+): Promise<DetectionProfile | null> {
+  return invokeOrFallback("update_detection_profile", null, { profile });
+}
+
+export async function cloneDetectionProfile(
+  sourceId: string,
+  name: string
+): Promise<DetectionProfile | null> {
+  return invokeOrFallback("clone_detection_profile", null, { source_id: sourceId, name });
+}
+
+export async function activateDetectionProfile(profileId: string): Promise<void> {
+  await invokeOrSkip("activate_detection_profile", { profile_id: profileId });
+}
+
+export async function listDetectionRules(): Promise<DetectionRule[]> {
+  return invokeOrFallback("list_detection_rules", []);
+}
+
+export async function listSequenceRules(): Promise<SequenceRule[]> {
+  return invokeOrFallback("list_sequence_rules", []);
+}
+
+export async function listSigmaRules(): Promise<SigmaRuleSummary[]> {
+  return invokeOrFallback("list_sigma_rules", []);
 }
 
 export async function openPathInExplorer(path?: string): Promise<boolean> {
@@ -14,6 +14,19 @@ export function formatMemoryMb(value: number): string {
   return `${value.toFixed(1)} MB`;
 }
 
+export function formatBytesRate(value: number): string {
+  if (!Number.isFinite(value) || value <= 0) {
+    return "0 B/s";
+  }
+  if (value >= 1024 * 1024) {
+    return `${(value / 1024 / 1024).toFixed(1)} MB/s`;
+  }
+  if (value >= 1024) {
+    return `${(value / 1024).toFixed(1)} KB/s`;
+  }
+  return `${value.toFixed(0)} B/s`;
+}
+
 export function formatDate(value?: string): string {
   if (!value) {
     return "-";
@@ -49,6 +62,9 @@ export function trustLabel(level: TrustLevel): string {
   if (level === "windows_native") {
     return "Windows";
   }
+  if (level === "system_native") {
+    return "System";
+  }
   if (level === "trusted") {
     return "Known";
   }
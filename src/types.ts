@@ -1,20 +1,37 @@
 export type RiskLevel = "legitimate" | "unknown" | "suspicious";
-export type TrustLevel = "windows_native" | "trusted" | "unknown";
+export type TrustLevel = "windows_native" | "system_native" | "trusted" | "unknown";
 export type ThreatVerdict =
   | "benign"
   | "low_risk"
   | "suspicious"
   | "likely_malicious"
   | "confirmed_malicious";
-export type DetectionProfile = "conservative" | "balanced" | "aggressive";
 export type AlertSeverity = "info" | "warn" | "critical";
-export type AlertStatus = "active" | "acknowledged" | "deleted";
+export type AlertStatus = "active" | "acknowledged" | "resolved" | "archived";
+export type AlertDisposition = "true_positive" | "false_positive" | "benign";
+export type AlertAction =
+  | "raised"
+  | "acknowledged"
+  | "resolved"
+  | "reopened"
+  | "note_added"
+  | "archived";
+
+export interface AlertAuditEntry {
+  timestamp: string;
+  action: AlertAction;
+  from_status?: AlertStatus | null;
+  to_status: AlertStatus;
+  disposition?: AlertDisposition | null;
+  note?: string | null;
+}
 
 export interface SuspicionAssessment {
   level: RiskLevel;
   score: number;
   reasons: string[];
   confidence: number;
+  evidence?: string[];
 }
 
 export interface ProcessNode {
@@ -29,15 +46,56 @@ export interface ProcessNode {
   children: ProcessNode[];
 }
 
+export type SignatureStatus = "valid" | "catalog" | "digest_mismatch" | "malformed" | "unsigned";
+
+export interface SignerDetails {
+  subject: string;
+  issuer: string;
+  thumbprint: string;
+  serial_number: string;
+  not_before?: string;
+  not_after?: string;
+  digest_algorithm: string;
+}
+
+export interface BinarySignature {
+  status: SignatureStatus;
+  signer?: SignerDetails;
+  verified: boolean;
+}
+
+export interface BinaryPrevalence {
+  sha256: string;
+  path: string;
+  first_seen: string;
+  last_seen: string;
+  run_count: number;
+  parents: string[];
+  file_modified?: string;
+}
+
 export interface ProcessMetric {
   pid: number;
   ppid?: number;
   name: string;
   exe_path?: string;
+  sha256?: string;
+  signature?: BinarySignature;
+  prevalence?: BinaryPrevalence;
+  cmdline?: string;
   user?: string;
+  real_uid?: number;
+  effective_uid?: number;
+  real_gid?: number;
+  effective_gid?: number;
   cpu_pct: number;
   gpu_pct: number;
+  gpu_memory_mb: number;
   memory_mb: number;
+  disk_read_bytes_per_sec: number;
+  disk_write_bytes_per_sec: number;
+  net_sent_bytes_per_sec: number;
+  net_received_bytes_per_sec: number;
   status: string;
   started_at?: string;
   trust_level: TrustLevel;
@@ -48,6 +106,70 @@ export interface ProcessMetric {
   verdict: ThreatVerdict;
 }
 
+export interface FieldMatcher {
+  equals: string[];
+  contains: string[];
+  starts_with: string[];
+  ends_with: string[];
+  regex: string[];
+}
+
+export interface RuleConditions {
+  name?: FieldMatcher;
+  path?: FieldMatcher;
+  parent?: FieldMatcher;
+  cmdline?: FieldMatcher;
+  user?: FieldMatcher;
+  signed?: boolean;
+  lineage: LineageStep[];
+}
+
+export interface LineageStep {
+  name?: FieldMatcher;
+  path?: FieldMatcher;
+  cmdline?: FieldMatcher;
+  user?: FieldMatcher;
+}
+
+export interface DetectionRule {
+  id: string;
+  description?: string;
+  enabled: boolean;
+  weight: number;
+  reason: string;
+  match: RuleConditions;
+  source: string;
+}
+
+export type SequenceScope = "process" | "lineage" | "host";
+
+export interface SequenceStep {
+  event_types: string[];
+  image?: string;
+  rule_hit?: string;
+}
+
+export interface SequenceRule {
+  id: string;
+  title: string;
+  description?: string;
+  enabled: boolean;
+  window_secs: number;
+  scope: SequenceScope;
+  bonus: number;
+  alert?: AlertSeverity;
+  steps: SequenceStep[];
+  source: string;
+}
+
+export interface SigmaRuleSummary {
+  id: string;
+  title: string;
+  level: string;
+  category: string;
+  source: string;
+}
+
 export interface InstalledProgram {
   name: string;
   version?: string;
@@ -60,12 +182,21 @@ export interface InstalledProgram {
   source: string;
 }
 
+export interface YaraMatch {
+  rule: string;
+  weight: number;
+  reason: string;
+  source: string;
+}
+
 export interface StartupProcess {
   name: string;
   command: string;
   location: string;
   source: string;
   trust_level: TrustLevel;
+  executable?: string;
+  yara_matches: YaraMatch[];
 }
 
 export interface AppUsageEntry {
@@ -79,6 +210,53 @@ export interface AppUsageEntry {
   last_seen: string;
 }
 
+export interface ValueRange {
+  min: number;
+  max: number;
+}
+
+export interface AppBaseline {
+  app_key: string;
+  name: string;
+  first_seen: string;
+  last_seen: string;
+  learned_at?: string;
+  samples: number;
+  parents: string[];
+  children: string[];
+  remote_hosts: string[];
+  remote_ports: number[];
+  users: string[];
+  cpu_pct?: ValueRange;
+  memory_mb?: ValueRange;
+}
+
+export interface VerdictThresholds {
+  legitimate_low_risk: number;
+  confirmed_malicious: number;
+  confirmed_min_correlations: number;
+  likely_malicious: number;
+  likely_min_correlations: number;
+  suspicious: number;
+  low_risk: number;
+}
+
+export interface DetectionProfile {
+  id: string;
+  name: string;
+  builtin: boolean;
+  suspicious_threshold: number;
+  unknown_threshold: number;
+  rule_weights: Record<string, number>;
+  correlation_cap: number;
+  verdict: VerdictThresholds;
+}
+
+export interface BaselineConfig {
+  learning_hours: number;
+  min_samples: number;
+}
+
 export interface Alert {
   id: string;
   alert_type: string;
@@ -89,6 +267,47 @@ export interface Alert {
   evidence: string[];
   timestamp: string;
   status: AlertStatus;
+  evidence_refs?: string[];
+  disposition?: AlertDisposition | null;
+  audit?: AlertAuditEntry[];
+  process?: ProcessIdentity | null;
+}
+
+export interface SuppressionRule {
+  id: string;
+  alert_type?: string | null;
+  process_path?: string | null;
+  sha256?: string | null;
+  parent?: string | null;
+  evidence_contains?: string | null;
+  expires_at?: string | null;
+  reason: string;
+  author: string;
+  created_at: string;
+  suppressed_count: number;
+  last_suppressed_at?: string | null;
+}
+
+export interface IncidentProcess {
+  pid: number;
+  ppid?: number | null;
+  name: string;
+  exe_path?: string | null;
+  sha256?: string | null;
+  alert_ids: string[];
+  children: IncidentProcess[];
+}
+
+export interface Incident {
+  id: string;
+  title: string;
+  severity: AlertSeverity;
+  open: boolean;
+  first_seen: string;
+  last_seen: string;
+  alerts: Alert[];
+  processes: IncidentProcess[];
+  timeline: EventEnvelope[];
 }
 
 export interface CpuSpikeConfig {
@@ -97,6 +316,19 @@ export interface CpuSpikeConfig {
   deviation_ratio: number;
 }
 
+export interface IoSpikeConfig {
+  threshold_bytes_per_sec: number;
+  min_consecutive_samples: number;
+  deviation_ratio: number;
+}
+
+export interface MemoryGrowthConfig {
+  window_samples: number;
+  min_slope_mb_per_min: number;
+  min_growth_mb: number;
+  min_increasing_ratio: number;
+}
+
 export type EventSeverity = "info" | "warn" | "critical";
 
 export interface ProcessIdentity {
@@ -104,8 +336,13 @@ export interface ProcessIdentity {
   ppid?: number;
   image_name: string;
   image_path?: string;
+  sha256?: string;
   cmdline?: string;
   user?: string;
+  real_uid?: number;
+  effective_uid?: number;
+  real_gid?: number;
+  effective_gid?: number;
 }
 
 export interface NetworkEvidence {
@@ -157,6 +394,8 @@ export interface PerformanceStats {
   total_events: number;
   event_store_size: number;
   tracked_processes: number;
+  hash_backlog: number;
+  hash_cache_entries: number;
 }
 
 export type ResponseMode = "audit" | "constrain";